@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
-use rasn_arrow::IpRangeTableV4;
+use rasn_arrow::{IpRangeTableV4, Ipv4Lookup, PrefixTrieV4};
 use std::path::Path;
 
 fn load_table() -> IpRangeTableV4 {
@@ -29,7 +29,37 @@ fn bench_single_lookup(c: &mut Criterion) {
     });
 }
 
-fn bench_scalar_vs_simd(c: &mut Criterion) {
+/// Build a table of `rows` sorted, non-overlapping ranges spread over the
+/// whole IPv4 space, with small gaps between some of them
+fn synthetic_table(rows: u32) -> IpRangeTableV4 {
+    let step = u32::MAX / rows;
+    let starts: Vec<u32> = (0..rows).map(|i| i * step).collect();
+    let ends: Vec<u32> = starts.iter().map(|s| s + step - 1 - (s % 7)).collect();
+
+    IpRangeTableV4::from_vecs(
+        starts,
+        ends,
+        (0..rows).collect(),
+        vec!["US".to_string(); rows as usize],
+        (0..rows).map(|i| format!("ORG-{}", i % 1000)).collect(),
+    )
+    .expect("Failed to build synthetic table")
+}
+
+/// Pseudo-random lookup addresses (xorshift, fixed seed)
+fn random_ips(count: usize) -> Vec<u32> {
+    let mut state = 0x9e37_79b9u32;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        })
+        .collect()
+}
+
+fn bench_scalar_vs_tree(c: &mut Criterion) {
     let table = load_table();
 
     let mut group = c.benchmark_group("lookup_comparison");
@@ -38,14 +68,92 @@ fn bench_scalar_vs_simd(c: &mut Criterion) {
         b.iter(|| table.find_ip_scalar(black_box(0x08080808)))
     });
 
-    #[cfg(target_arch = "x86_64")]
-    {
-        if is_x86_feature_detected!("avx2") {
-            group.bench_function("simd_lookup", |b| {
-                b.iter(|| table.find_ip(black_box(0x08080808)))
-            });
-        }
+    group.bench_function("tree_lookup", |b| {
+        b.iter(|| table.find_ip(black_box(0x08080808)))
+    });
+
+    group.finish();
+}
+
+fn bench_large_table(c: &mut Criterion) {
+    let table = synthetic_table(500_000);
+    let ips = random_ips(4096);
+
+    let mut group = c.benchmark_group("large_table_500k");
+    group.throughput(Throughput::Elements(ips.len() as u64));
+
+    group.bench_function("scalar_lookup", |b| {
+        b.iter(|| {
+            for &ip in &ips {
+                black_box(table.find_ip_scalar(black_box(ip)));
+            }
+        })
+    });
+
+    group.bench_function("tree_lookup", |b| {
+        b.iter(|| {
+            for &ip in &ips {
+                black_box(table.find_ip(black_box(ip)));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_find_many(c: &mut Criterion) {
+    let table = synthetic_table(500_000);
+    let ips = random_ips(1_000_000);
+
+    let mut group = c.benchmark_group("batch_1m");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(ips.len() as u64));
+
+    group.bench_function("find_ip", |b| {
+        b.iter(|| ips.iter().map(|&ip| table.find_ip(ip)).collect::<Vec<_>>())
+    });
+
+    group.bench_function("find_many", |b| b.iter(|| table.find_many(black_box(&ips))));
+
+    group.finish();
+}
+
+/// Build a table of `rows` ranges that are each exactly one prefix
+/// (/19 to /24), so a trie built from it holds the same data
+fn synthetic_prefix_table(rows: u32) -> IpRangeTableV4 {
+    let starts: Vec<u32> = (0..rows).map(|i| i << 13).collect();
+    let ends: Vec<u32> = starts
+        .iter()
+        .zip(0..)
+        .map(|(start, i)| start + (1 << (8 + i % 6)) - 1)
+        .collect();
+
+    IpRangeTableV4::from_vecs(
+        starts,
+        ends,
+        (0..rows).collect(),
+        vec!["US".to_string(); rows as usize],
+        (0..rows).map(|i| format!("ORG-{}", i % 1000)).collect(),
+    )
+    .expect("Failed to build synthetic table")
+}
+
+fn lookup_all<L: Ipv4Lookup>(backend: &L, ips: &[u32]) {
+    for &ip in ips {
+        black_box(backend.find_ip(black_box(ip)));
     }
+}
+
+fn bench_backends(c: &mut Criterion) {
+    let table = synthetic_prefix_table(500_000);
+    let trie = PrefixTrieV4::from_ranges(&table).expect("Failed to build trie");
+    let ips = random_ips(4096);
+
+    let mut group = c.benchmark_group("backends_500k");
+    group.throughput(Throughput::Elements(ips.len() as u64));
+
+    group.bench_function("range_table", |b| b.iter(|| lookup_all(&table, &ips)));
+    group.bench_function("prefix_trie", |b| b.iter(|| lookup_all(&trie, &ips)));
 
     group.finish();
 }
@@ -84,8 +192,11 @@ fn bench_batch_lookups(c: &mut Criterion) {
 
 criterion_group!(
     benches,
+    bench_large_table,
+    bench_find_many,
+    bench_backends,
     bench_single_lookup,
-    bench_scalar_vs_simd,
+    bench_scalar_vs_tree,
     bench_batch_lookups
 );
 criterion_main!(benches);
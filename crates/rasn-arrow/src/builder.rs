@@ -0,0 +1,770 @@
+//! Native database builder
+//!
+//! Converts the reference datasets in `reference_data/` into the Parquet
+//! files loaded by [`IpAsnDatabase::open`](crate::IpAsnDatabase::open),
+//! replacing `scripts/build_databases.py`:
+//!
+//! | Input | Output |
+//! |-------|--------|
+//! | `ip2asn-v4.tsv[.gz]` (iptoasn) | `ip2asn-v4.parquet` |
+//! | `ip2asn-v6.tsv[.gz]` (iptoasn) | `ip2asn-v6.parquet` |
+//! | `asn-info.csv` (ipverse) | `asn-metadata.parquet` |
+//! | `asn-country-ipv4.csv` (sapics) | `country-index.parquet` |
+//!
+//! Missing inputs are skipped. Ranges are sorted by start address and
+//! validated: rows with `start > end` or unparsable fields are dropped and
+//! counted, overlapping ranges fail the build.
+//!
+//! The range tables are also written as memory-mappable Arrow IPC files
+//! (`ip2asn-v4.arrow`, `ip2asn-v6.arrow`), which
+//! [`IpAsnDatabase::open`](crate::IpAsnDatabase::open) prefers.
+//!
+//! Every file records its provenance (build time, source file, URL and
+//! SHA-256, row count) as key-value metadata, see [`DatasetInfo`].
+
+use crate::database::{
+    ASN_METADATA_FILE, COUNTRY_INDEX_FILE, IPV4_IPC_FILE, IPV4_TABLE_FILE, IPV6_IPC_FILE,
+    IPV6_TABLE_FILE,
+};
+use crate::dataset::{now_rfc3339, SCHEMA_VERSION};
+use crate::{ArrowError, DatasetInfo, IpRangeTableV4, IpRangeTableV6, Result};
+use arrow::array::{
+    ArrayRef, FixedSizeBinaryArray, StringArray, StringDictionaryBuilder, UInt32Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, UInt8Type};
+use arrow::record_batch::RecordBatch;
+use flate2::read::GzDecoder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// iptoasn IPv4 input file name (optionally gzip-compressed with `.gz`)
+pub const IPV4_TSV_FILE: &str = "ip2asn-v4.tsv";
+
+/// iptoasn IPv6 input file name (optionally gzip-compressed with `.gz`)
+pub const IPV6_TSV_FILE: &str = "ip2asn-v6.tsv";
+
+/// ipverse ASN metadata input file name
+pub const ASN_INFO_FILE: &str = "asn-info.csv";
+
+/// sapics country ranges input file name
+pub const ASN_COUNTRY_FILE: &str = "asn-country-ipv4.csv";
+
+/// Download URLs of the input files, recorded in the built files
+const SOURCE_URLS: &[(&str, &str)] = &[
+    (IPV4_TSV_FILE, "https://iptoasn.com/data/ip2asn-v4.tsv.gz"),
+    (IPV6_TSV_FILE, "https://iptoasn.com/data/ip2asn-v6.tsv.gz"),
+    (ASN_INFO_FILE, "https://github.com/ipverse/asn-info"),
+    (ASN_COUNTRY_FILE, "https://github.com/sapics/ip-location-db"),
+];
+
+/// Statistics of one written table file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TableStats {
+    /// Input file that was read
+    pub input: PathBuf,
+    /// Parquet or Arrow IPC file that was written
+    pub output: PathBuf,
+    /// Number of rows written
+    pub records: usize,
+    /// Number of input rows dropped (unparsable, `start > end`, duplicates)
+    pub skipped: usize,
+    /// Size of the input file in bytes
+    pub input_bytes: u64,
+    /// Size of the written file in bytes
+    pub output_bytes: u64,
+}
+
+/// Result of [`DatabaseBuilder::build_all`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BuildReport {
+    /// IPv4 range table, if `ip2asn-v4.tsv[.gz]` was found
+    pub ipv4: Option<TableStats>,
+    /// IPv6 range table, if `ip2asn-v6.tsv[.gz]` was found
+    pub ipv6: Option<TableStats>,
+    /// Memory-mappable copy of the IPv4 range table
+    pub ipv4_ipc: Option<TableStats>,
+    /// Memory-mappable copy of the IPv6 range table
+    pub ipv6_ipc: Option<TableStats>,
+    /// ASN metadata table, if `asn-info.csv` was found
+    pub asn_metadata: Option<TableStats>,
+    /// Country index, if `asn-country-ipv4.csv` was found
+    pub country_index: Option<TableStats>,
+}
+
+impl BuildReport {
+    /// Iterate over the tables that were built
+    pub fn tables(&self) -> impl Iterator<Item = &TableStats> {
+        [
+            &self.ipv4,
+            &self.ipv6,
+            &self.ipv4_ipc,
+            &self.ipv6_ipc,
+            &self.asn_metadata,
+            &self.country_index,
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Builds the Parquet database files from reference data
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasn_arrow::builder::DatabaseBuilder;
+/// use std::path::Path;
+///
+/// let builder = DatabaseBuilder::new(Path::new("reference_data"), Path::new("data/arrow"));
+/// let report = builder.build_all()?;
+/// for table in report.tables() {
+///     println!("{}: {} records", table.output.display(), table.records);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct DatabaseBuilder {
+    reference_dir: PathBuf,
+    output_dir: PathBuf,
+}
+
+impl DatabaseBuilder {
+    /// Create a builder reading from `reference_dir` and writing to `output_dir`
+    pub fn new(reference_dir: &Path, output_dir: &Path) -> Self {
+        Self {
+            reference_dir: reference_dir.to_path_buf(),
+            output_dir: output_dir.to_path_buf(),
+        }
+    }
+
+    /// Build every table whose input file is present
+    ///
+    /// Fails with [`ArrowError::FileNotFound`] when no input is found at all.
+    pub fn build_all(&self) -> Result<BuildReport> {
+        let mut report = BuildReport {
+            ipv4: self.build_ipv4()?,
+            ipv6: self.build_ipv6()?,
+            asn_metadata: self.build_asn_metadata()?,
+            country_index: self.build_country_index()?,
+            ..Default::default()
+        };
+
+        if report.tables().next().is_none() {
+            return Err(ArrowError::FileNotFound(format!(
+                "no reference data in {}",
+                self.reference_dir.display()
+            )));
+        }
+
+        if report.ipv4.is_some() {
+            report.ipv4_ipc = self.build_ipv4_ipc()?;
+        }
+        if report.ipv6.is_some() {
+            report.ipv6_ipc = self.build_ipv6_ipc()?;
+        }
+
+        Ok(report)
+    }
+
+    /// Build `ip2asn-v4.parquet` from `ip2asn-v4.tsv[.gz]`
+    pub fn build_ipv4(&self) -> Result<Option<TableStats>> {
+        let Some(input) = self.find_input(IPV4_TSV_FILE) else {
+            return Ok(None);
+        };
+
+        let (mut rows, skipped) = read_ip2asn(&input, |s| s.parse::<Ipv4Addr>().ok())?;
+        sort_and_validate(&mut rows, &input)?;
+
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("start_ip", DataType::UInt32, false),
+                Field::new("end_ip", DataType::UInt32, false),
+                Field::new("asn", DataType::UInt32, false),
+                country_field(),
+                Field::new("org", DataType::Utf8, false),
+            ])),
+            vec![
+                Arc::new(UInt32Array::from_iter_values(
+                    rows.iter().map(|r| u32::from(r.start)),
+                )),
+                Arc::new(UInt32Array::from_iter_values(
+                    rows.iter().map(|r| u32::from(r.end)),
+                )),
+                Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.asn))),
+                country_array(rows.iter().map(|r| r.country.as_str()))?,
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|r| r.org.as_str()),
+                )),
+            ],
+        )?;
+
+        self.write(&input, IPV4_TABLE_FILE, &batch, 100_000, skipped)
+            .map(Some)
+    }
+
+    /// Build `ip2asn-v6.parquet` from `ip2asn-v6.tsv[.gz]`
+    pub fn build_ipv6(&self) -> Result<Option<TableStats>> {
+        let Some(input) = self.find_input(IPV6_TSV_FILE) else {
+            return Ok(None);
+        };
+
+        let (mut rows, skipped) = read_ip2asn(&input, |s| s.parse::<Ipv6Addr>().ok())?;
+        sort_and_validate(&mut rows, &input)?;
+
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("start_ip", DataType::FixedSizeBinary(16), false),
+                Field::new("end_ip", DataType::FixedSizeBinary(16), false),
+                Field::new("asn", DataType::UInt32, false),
+                country_field(),
+                Field::new("org", DataType::Utf8, false),
+            ])),
+            vec![
+                Arc::new(FixedSizeBinaryArray::try_from_iter(
+                    rows.iter().map(|r| r.start.octets()),
+                )?),
+                Arc::new(FixedSizeBinaryArray::try_from_iter(
+                    rows.iter().map(|r| r.end.octets()),
+                )?),
+                Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.asn))),
+                country_array(rows.iter().map(|r| r.country.as_str()))?,
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|r| r.org.as_str()),
+                )),
+            ],
+        )?;
+
+        self.write(&input, IPV6_TABLE_FILE, &batch, 50_000, skipped)
+            .map(Some)
+    }
+
+    /// Write `ip2asn-v4.arrow` from the `ip2asn-v4.parquet` in the output directory
+    pub fn build_ipv4_ipc(&self) -> Result<Option<TableStats>> {
+        let input = self.output_dir.join(IPV4_TABLE_FILE);
+        if !input.exists() {
+            return Ok(None);
+        }
+
+        let table = IpRangeTableV4::from_parquet(&input)?;
+        let output = self.output_dir.join(IPV4_IPC_FILE);
+        table.write_ipc(&output)?;
+
+        ipc_stats(input, output, table.len()).map(Some)
+    }
+
+    /// Write `ip2asn-v6.arrow` from the `ip2asn-v6.parquet` in the output directory
+    pub fn build_ipv6_ipc(&self) -> Result<Option<TableStats>> {
+        let input = self.output_dir.join(IPV6_TABLE_FILE);
+        if !input.exists() {
+            return Ok(None);
+        }
+
+        let table = IpRangeTableV6::from_parquet(&input)?;
+        let output = self.output_dir.join(IPV6_IPC_FILE);
+        table.write_ipc(&output)?;
+
+        ipc_stats(input, output, table.len()).map(Some)
+    }
+
+    /// Build `asn-metadata.parquet` from `asn-info.csv`
+    ///
+    /// The CSV has an `asn,handle,description` header. Duplicate ASNs keep
+    /// their first row.
+    pub fn build_asn_metadata(&self) -> Result<Option<TableStats>> {
+        let input = self.reference_dir.join(ASN_INFO_FILE);
+        if !input.exists() {
+            return Ok(None);
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(open_input(&input)?);
+
+        let mut rows: Vec<(u32, String, String)> = Vec::new();
+        let mut skipped = 0;
+        for record in reader.records() {
+            let Ok(record) = record else {
+                skipped += 1;
+                continue;
+            };
+            let Some(asn) = record.get(0).and_then(|s| s.trim().parse::<u32>().ok()) else {
+                skipped += 1;
+                continue;
+            };
+            rows.push((
+                asn,
+                record.get(1).unwrap_or_default().to_string(),
+                record.get(2).unwrap_or_default().to_string(),
+            ));
+        }
+
+        // Stable sort keeps the first row of duplicate ASNs in front
+        rows.sort_by_key(|row| row.0);
+        let before = rows.len();
+        rows.dedup_by_key(|row| row.0);
+        skipped += before - rows.len();
+
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("asn", DataType::UInt32, false),
+                Field::new("handle", DataType::Utf8, false),
+                Field::new("description", DataType::Utf8, false),
+            ])),
+            vec![
+                Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.0))),
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|r| r.1.as_str()),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|r| r.2.as_str()),
+                )),
+            ],
+        )?;
+
+        self.write(&input, ASN_METADATA_FILE, &batch, 100_000, skipped)
+            .map(Some)
+    }
+
+    /// Build `country-index.parquet` from `asn-country-ipv4.csv`
+    ///
+    /// The CSV has no header: `start_ip,end_ip,country`. Rows are sorted by
+    /// country, then start address; rows without a country are dropped.
+    pub fn build_country_index(&self) -> Result<Option<TableStats>> {
+        let input = self.reference_dir.join(ASN_COUNTRY_FILE);
+        if !input.exists() {
+            return Ok(None);
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(open_input(&input)?);
+
+        let mut rows: Vec<(String, u32, u32)> = Vec::new();
+        let mut skipped = 0;
+        for record in reader.records() {
+            let parsed = record.ok().and_then(|record| {
+                let start = record.get(0)?.trim().parse::<Ipv4Addr>().ok()?;
+                let end = record.get(1)?.trim().parse::<Ipv4Addr>().ok()?;
+                let country = record.get(2)?.trim().to_uppercase();
+                (start <= end && !country.is_empty())
+                    .then(|| (country, u32::from(start), u32::from(end)))
+            });
+
+            match parsed {
+                Some(row) => rows.push(row),
+                None => skipped += 1,
+            }
+        }
+
+        rows.sort_unstable();
+
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                country_field(),
+                Field::new("start_ip", DataType::UInt32, false),
+                Field::new("end_ip", DataType::UInt32, false),
+            ])),
+            vec![
+                country_array(rows.iter().map(|r| r.0.as_str()))?,
+                Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.1))),
+                Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.2))),
+            ],
+        )?;
+
+        self.write(&input, COUNTRY_INDEX_FILE, &batch, 100_000, skipped)
+            .map(Some)
+    }
+
+    /// Find an input file, falling back to its `.gz` variant
+    fn find_input(&self, name: &str) -> Option<PathBuf> {
+        let plain = self.reference_dir.join(name);
+        if plain.exists() {
+            return Some(plain);
+        }
+
+        let gz = self.reference_dir.join(format!("{}.gz", name));
+        gz.exists().then_some(gz)
+    }
+
+    /// Write a record batch as a zstd-compressed Parquet file
+    ///
+    /// The file's key-value metadata records the [`DatasetInfo`] of the build.
+    /// The file is replaced atomically.
+    fn write(
+        &self,
+        input: &Path,
+        file_name: &str,
+        batch: &RecordBatch,
+        row_group_size: usize,
+        skipped: usize,
+    ) -> Result<TableStats> {
+        fs::create_dir_all(&self.output_dir)?;
+        let output = self.output_dir.join(file_name);
+
+        let metadata = dataset_info(input, batch.num_rows())?
+            .to_metadata()
+            .into_iter()
+            .map(|(key, value)| KeyValue::new(key, value))
+            .collect();
+        let props = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::try_new(9)?))
+            .set_max_row_group_size(row_group_size)
+            .set_key_value_metadata(Some(metadata))
+            .build();
+
+        // Write beside the live file and rename over it, so a reloading
+        // server never reads a half-written table
+        let tmp_path = output.with_extension("parquet.tmp");
+        let file = File::create(&tmp_path)?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+        writer.write(batch)?;
+        writer.close()?;
+        fs::rename(&tmp_path, &output)?;
+
+        Ok(TableStats {
+            input: input.to_path_buf(),
+            records: batch.num_rows(),
+            skipped,
+            input_bytes: fs::metadata(input)?.len(),
+            output_bytes: fs::metadata(&output)?.len(),
+            output,
+        })
+    }
+}
+
+/// Statistics of an Arrow IPC file converted from a Parquet file
+fn ipc_stats(input: PathBuf, output: PathBuf, records: usize) -> Result<TableStats> {
+    Ok(TableStats {
+        input_bytes: fs::metadata(&input)?.len(),
+        output_bytes: fs::metadata(&output)?.len(),
+        input,
+        output,
+        records,
+        skipped: 0,
+    })
+}
+
+/// Describe a table built from `input`
+fn dataset_info(input: &Path, rows: usize) -> Result<DatasetInfo> {
+    let source_file = input
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned());
+    let source_url = source_file.as_deref().and_then(|name| {
+        let name = name.strip_suffix(".gz").unwrap_or(name);
+        SOURCE_URLS
+            .iter()
+            .find(|(file, _)| *file == name)
+            .map(|(_, url)| url.to_string())
+    });
+
+    Ok(DatasetInfo {
+        schema_version: Some(SCHEMA_VERSION),
+        build_time: Some(now_rfc3339()),
+        source_file,
+        source_url,
+        source_sha256: Some(sha256_hex(input)?),
+        row_count: Some(rows),
+    })
+}
+
+/// Hex-encoded SHA-256 of a file's bytes (compressed bytes for `.gz` inputs)
+fn sha256_hex(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// One parsed iptoasn row
+struct RangeRow<A> {
+    start: A,
+    end: A,
+    asn: u32,
+    country: String,
+    org: String,
+}
+
+/// Open an input file, transparently decompressing `.gz` files
+fn open_input(path: &Path) -> Result<Box<dyn Read>> {
+    let file = File::open(path)?;
+
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        Ok(Box::new(GzDecoder::new(file)))
+    } else {
+        Ok(Box::new(file))
+    }
+}
+
+/// Read an iptoasn TSV file (`start end asn country org`)
+///
+/// Returns the parsed rows and the number of skipped lines. Like the Python
+/// builder, `None` countries become empty and missing orgs become `Unknown`.
+fn read_ip2asn<A: Ord>(
+    path: &Path,
+    parse_ip: impl Fn(&str) -> Option<A>,
+) -> Result<(Vec<RangeRow<A>>, usize)> {
+    let reader = BufReader::new(open_input(path)?);
+
+    let mut rows = Vec::new();
+    let mut skipped = 0;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut fields = line.split('\t');
+        let parsed = (|| {
+            let start = parse_ip(fields.next()?.trim())?;
+            let end = parse_ip(fields.next()?.trim())?;
+            let asn = fields.next()?.trim().parse::<u32>().ok()?;
+            let country = missing_to_empty(fields.next().unwrap_or_default());
+            let org = match missing_to_empty(fields.next().unwrap_or_default()) {
+                org if org.is_empty() => "Unknown".to_string(),
+                org => org,
+            };
+
+            (start <= end).then_some(RangeRow {
+                start,
+                end,
+                asn,
+                country,
+                org,
+            })
+        })();
+
+        match parsed {
+            Some(row) => rows.push(row),
+            None => skipped += 1,
+        }
+    }
+
+    Ok((rows, skipped))
+}
+
+/// Sort ranges by start address and reject overlapping ranges
+fn sort_and_validate<A: Ord + std::fmt::Display>(
+    rows: &mut [RangeRow<A>],
+    path: &Path,
+) -> Result<()> {
+    rows.sort_by(|a, b| a.start.cmp(&b.start));
+
+    if let Some(pair) = rows.windows(2).find(|pair| pair[1].start <= pair[0].end) {
+        return Err(ArrowError::InvalidData(format!(
+            "{}: range {}-{} overlaps {}-{}",
+            path.display(),
+            pair[1].start,
+            pair[1].end,
+            pair[0].start,
+            pair[0].end
+        )));
+    }
+
+    Ok(())
+}
+
+/// Map the placeholders pandas treats as missing to an empty string
+fn missing_to_empty(value: &str) -> String {
+    match value.trim() {
+        "None" | "nan" => String::new(),
+        value => value.to_string(),
+    }
+}
+
+/// `country` column: `Dictionary(UInt8, Utf8)`
+fn country_field() -> Field {
+    Field::new(
+        "country",
+        DataType::Dictionary(Box::new(DataType::UInt8), Box::new(DataType::Utf8)),
+        false,
+    )
+}
+
+/// Dictionary-encode a country column
+///
+/// Fails if there are more than 256 distinct values.
+fn country_array<'a>(values: impl Iterator<Item = &'a str>) -> Result<ArrayRef> {
+    let mut builder = StringDictionaryBuilder::<UInt8Type>::new();
+    for value in values {
+        builder.append(value)?;
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AsnMetadataTable, CountryIndex, IpAsnDatabase};
+    use flate2::write::GzEncoder;
+    use rasn_core::Asn;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const IPV4_TSV: &str = "\
+8.8.8.0\t8.8.8.255\t15169\tUS\tGOOGLE
+1.0.0.0\t1.0.0.255\t13335\tUS\tCLOUDFLARENET
+1.0.1.0\t1.0.3.255\t0\tNone\tNot routed
+not-an-ip\t1.0.0.1\t1\tUS\tBROKEN
+9.9.9.9\t9.9.9.0\t19281\tUS\tQUAD9
+";
+
+    const IPV6_TSV: &str = "\
+2606:4700::\t2606:4700:ffff:ffff:ffff:ffff:ffff:ffff\t13335\tUS\tCLOUDFLARENET
+2001:4860::\t2001:4860:ffff:ffff:ffff:ffff:ffff:ffff\t15169\tUS\tGOOGLE
+";
+
+    const ASN_INFO_CSV: &str = "\
+asn,handle,description
+15169,GOOGLE,Google LLC
+13335,CLOUDFLARENET,\"Cloudflare, Inc.\"
+15169,DUPLICATE,Should be dropped
+";
+
+    const COUNTRY_CSV: &str = "\
+8.8.8.0,8.8.8.255,US
+1.0.0.0,1.0.0.255,AU
+5.0.0.0,5.0.0.255,
+";
+
+    fn reference_dir() -> TempDir {
+        let dir = TempDir::new().unwrap();
+
+        // IPv4 input is gzip-compressed, like the iptoasn download
+        let mut gz = GzEncoder::new(
+            File::create(dir.path().join("ip2asn-v4.tsv.gz")).unwrap(),
+            flate2::Compression::default(),
+        );
+        gz.write_all(IPV4_TSV.as_bytes()).unwrap();
+        gz.finish().unwrap();
+
+        fs::write(dir.path().join(IPV6_TSV_FILE), IPV6_TSV).unwrap();
+        fs::write(dir.path().join(ASN_INFO_FILE), ASN_INFO_CSV).unwrap();
+        fs::write(dir.path().join(ASN_COUNTRY_FILE), COUNTRY_CSV).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_build_all_round_trip() {
+        let reference = reference_dir();
+        let output = TempDir::new().unwrap();
+
+        let report = DatabaseBuilder::new(reference.path(), output.path())
+            .build_all()
+            .unwrap();
+
+        let ipv4 = report.ipv4.as_ref().unwrap();
+        assert_eq!(ipv4.records, 3);
+        assert_eq!(ipv4.skipped, 2);
+        assert_eq!(report.ipv6.as_ref().unwrap().records, 2);
+        assert_eq!(report.asn_metadata.as_ref().unwrap().skipped, 1);
+        assert_eq!(report.country_index.as_ref().unwrap().records, 2);
+        assert_eq!(report.ipv4_ipc.as_ref().unwrap().records, 3);
+        assert_eq!(report.ipv6_ipc.as_ref().unwrap().records, 2);
+        assert_eq!(report.tables().count(), 6);
+
+        // Files are renamed into place; no temporary files are left behind
+        let leftovers: Vec<_> = fs::read_dir(output.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .filter(|name| name.to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty(), "{:?}", leftovers);
+
+        // The memory-mapped tables match the Parquet tables row by row
+        let parquet = IpRangeTableV4::from_parquet(&output.path().join(IPV4_TABLE_FILE)).unwrap();
+        let ipc = IpRangeTableV4::from_ipc(&output.path().join(IPV4_IPC_FILE)).unwrap();
+        assert_eq!(ipc.len(), parquet.len());
+        for row in 0..parquet.len() {
+            assert_eq!(ipc.range_at(row), parquet.range_at(row));
+            assert_eq!(ipc.info_at(row), parquet.info_at(row));
+        }
+
+        // Both copies carry the build metadata
+        let info = parquet.dataset_info().unwrap();
+        assert_eq!(info.schema_version, Some(SCHEMA_VERSION));
+        assert_eq!(info.source_file.as_deref(), Some("ip2asn-v4.tsv.gz"));
+        assert_eq!(
+            info.source_url.as_deref(),
+            Some("https://iptoasn.com/data/ip2asn-v4.tsv.gz")
+        );
+        assert_eq!(
+            info.source_sha256,
+            Some(sha256_hex(&reference.path().join("ip2asn-v4.tsv.gz")).unwrap())
+        );
+        assert_eq!(info.row_count, Some(3));
+        assert!(info.build_time.is_some());
+        assert_eq!(ipc.dataset_info(), Some(info));
+
+        // The written files load through the regular readers
+        let db = IpAsnDatabase::open(output.path()).unwrap();
+        let info = db.lookup("8.8.8.8".parse().unwrap()).unwrap();
+        assert_eq!(info.asn, Asn(15169));
+        assert_eq!(info.description, Some("Google LLC".to_string()));
+
+        let info = db.lookup("1.0.2.1".parse().unwrap()).unwrap();
+        assert_eq!(info.asn, Asn(0));
+        assert_eq!(info.country, Some(String::new()));
+
+        let info = db.lookup("2606:4700:4700::1111".parse().unwrap()).unwrap();
+        assert_eq!(info.asn, Asn(13335));
+
+        let metadata =
+            AsnMetadataTable::from_parquet(&output.path().join(ASN_METADATA_FILE)).unwrap();
+        assert_eq!(metadata.get(Asn(15169)).unwrap().handle, "GOOGLE");
+        assert_eq!(
+            metadata.get(Asn(13335)).unwrap().description,
+            "Cloudflare, Inc."
+        );
+
+        let countries =
+            CountryIndex::from_parquet(&output.path().join(COUNTRY_INDEX_FILE)).unwrap();
+        assert_eq!(countries.ranges("AU"), &[(0x01000000, 0x010000FF)]);
+
+        // IPv6 rows are sorted by start address
+        let v6 = IpRangeTableV6::from_parquet(&output.path().join(IPV6_TABLE_FILE)).unwrap();
+        assert_eq!(v6.info_at(0).unwrap().asn, Asn(15169));
+        let v6 = IpRangeTableV6::from_ipc(&output.path().join(IPV6_IPC_FILE)).unwrap();
+        assert_eq!(v6.range_at(1), db.v6().unwrap().range_at(1));
+        assert_eq!(v6.dataset_info().unwrap().row_count, Some(2));
+    }
+
+    #[test]
+    fn test_overlapping_ranges_rejected() {
+        let reference = TempDir::new().unwrap();
+        fs::write(
+            reference.path().join(IPV4_TSV_FILE),
+            "1.0.0.0\t1.0.0.255\t1\tUS\tA\n1.0.0.128\t1.0.1.255\t2\tUS\tB\n",
+        )
+        .unwrap();
+        let output = TempDir::new().unwrap();
+
+        let result = DatabaseBuilder::new(reference.path(), output.path()).build_ipv4();
+        assert!(matches!(result, Err(ArrowError::InvalidData(_))));
+    }
+
+    #[test]
+    fn test_missing_inputs() {
+        let reference = TempDir::new().unwrap();
+        let output = TempDir::new().unwrap();
+        let builder = DatabaseBuilder::new(reference.path(), output.path());
+
+        assert_eq!(builder.build_ipv4().unwrap(), None);
+        assert!(matches!(
+            builder.build_all(),
+            Err(ArrowError::FileNotFound(_))
+        ));
+    }
+}
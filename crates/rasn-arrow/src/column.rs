@@ -0,0 +1,217 @@
+//! Dictionary-encoded string columns
+//!
+//! Country and organization columns have few distinct values, so the range
+//! tables store them as per-row keys into an array of distinct strings
+//! instead of one `String` per row. Dictionary arrays read from Parquet or
+//! Arrow IPC files are used as-is, without copying; plain string columns are
+//! interned on load.
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, DictionaryArray, StringArray, StringDictionaryBuilder, UInt16Array,
+    UInt32Array, UInt8Array,
+};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, UInt16Type, UInt32Type, UInt8Type};
+use std::sync::Arc;
+
+/// Per-row dictionary keys, in the width found in the file
+#[derive(Debug, Clone)]
+enum Keys {
+    U8(UInt8Array),
+    U16(UInt16Array),
+    U32(UInt32Array),
+}
+
+impl Keys {
+    fn len(&self) -> usize {
+        match self {
+            Keys::U8(keys) => keys.len(),
+            Keys::U16(keys) => keys.len(),
+            Keys::U32(keys) => keys.len(),
+        }
+    }
+
+    /// Get the size of the keys in bytes
+    fn memory_size(&self) -> usize {
+        match self {
+            Keys::U8(keys) => keys.len(),
+            Keys::U16(keys) => keys.len() * 2,
+            Keys::U32(keys) => keys.len() * 4,
+        }
+    }
+
+    /// Get the key of a row, `None` for null keys
+    fn get(&self, row: usize) -> Option<usize> {
+        match self {
+            Keys::U8(keys) => keys.is_valid(row).then(|| keys.value(row) as usize),
+            Keys::U16(keys) => keys.is_valid(row).then(|| keys.value(row) as usize),
+            Keys::U32(keys) => keys.is_valid(row).then(|| keys.value(row) as usize),
+        }
+    }
+}
+
+/// Dictionary-encoded string column
+#[derive(Debug, Clone)]
+pub(crate) struct StringColumn {
+    keys: Keys,
+    values: StringArray,
+}
+
+impl StringColumn {
+    /// Intern owned strings into a dictionary column
+    pub(crate) fn from_strings<S: AsRef<str>>(strings: impl IntoIterator<Item = S>) -> Self {
+        let mut builder = StringDictionaryBuilder::<UInt32Type>::new();
+        for s in strings {
+            builder.append_value(s.as_ref());
+        }
+
+        let dictionary = builder.finish();
+        Self {
+            keys: Keys::U32(dictionary.keys().clone()),
+            values: dictionary.values().as_string::<i32>().clone(),
+        }
+    }
+
+    /// Wrap a string array
+    ///
+    /// `Dictionary` arrays with `UInt8`, `UInt16` or `UInt32` keys and `Utf8`
+    /// values are used without copying. Other integer key types, `LargeUtf8`
+    /// values and plain `Utf8`/`LargeUtf8` arrays are converted. Returns
+    /// `None` for any other type.
+    pub(crate) fn from_array(array: &ArrayRef) -> Option<Self> {
+        let is_string = |t: &DataType| matches!(t, DataType::Utf8 | DataType::LargeUtf8);
+
+        let keys = match array.data_type() {
+            DataType::Dictionary(key, value) if value.as_ref() == &DataType::Utf8 => {
+                match key.as_ref() {
+                    DataType::UInt8 => Keys::U8(array.as_dictionary::<UInt8Type>().keys().clone()),
+                    DataType::UInt16 => {
+                        Keys::U16(array.as_dictionary::<UInt16Type>().keys().clone())
+                    }
+                    DataType::UInt32 => {
+                        Keys::U32(array.as_dictionary::<UInt32Type>().keys().clone())
+                    }
+                    key if key.is_integer() => return Self::from_array(&intern(array)?),
+                    _ => return None,
+                }
+            }
+            DataType::Dictionary(key, value) if key.is_integer() && is_string(value) => {
+                return Self::from_array(&intern(array)?)
+            }
+            other if is_string(other) => return Self::from_array(&intern(array)?),
+            _ => return None,
+        };
+
+        let values = array
+            .as_any_dictionary()
+            .values()
+            .as_string::<i32>()
+            .clone();
+        Some(Self { keys, values })
+    }
+
+    /// Get the string of a row; null values are empty strings
+    pub(crate) fn get(&self, row: usize) -> Option<&str> {
+        if row >= self.len() {
+            return None;
+        }
+
+        Some(match self.keys.get(row) {
+            Some(key) if key < self.values.len() && self.values.is_valid(key) => {
+                self.values.value(key)
+            }
+            _ => "",
+        })
+    }
+
+    /// Iterate over the strings of all rows
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &str> {
+        (0..self.len()).map(|row| self.get(row).unwrap_or_default())
+    }
+
+    /// Get the number of rows
+    pub(crate) fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Get the number of dictionary values
+    pub(crate) fn distinct(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Get the size of the keys and dictionary values in bytes
+    pub(crate) fn memory_size(&self) -> usize {
+        self.keys.memory_size()
+            + self.values.value_data().len()
+            + std::mem::size_of_val(self.values.value_offsets())
+    }
+
+    /// Get the column as a dictionary array (shares the underlying buffers)
+    pub(crate) fn to_array(&self) -> ArrayRef {
+        let values: ArrayRef = Arc::new(self.values.clone());
+        match &self.keys {
+            Keys::U8(keys) => Arc::new(DictionaryArray::new(keys.clone(), values)),
+            Keys::U16(keys) => Arc::new(DictionaryArray::new(keys.clone(), values)),
+            Keys::U32(keys) => Arc::new(DictionaryArray::new(keys.clone(), values)),
+        }
+    }
+}
+
+/// Convert a string or dictionary array to `Dictionary(UInt32, Utf8)`
+fn intern(array: &ArrayRef) -> Option<ArrayRef> {
+    cast(
+        array,
+        &DataType::Dictionary(Box::new(DataType::UInt32), Box::new(DataType::Utf8)),
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, LargeStringArray};
+    use arrow::datatypes::Int32Type;
+
+    #[test]
+    fn test_from_strings() {
+        let column = StringColumn::from_strings(["US", "NL", "US", ""]);
+        assert_eq!(column.len(), 4);
+        assert_eq!(column.values.len(), 3);
+        assert_eq!(
+            column.iter().collect::<Vec<_>>(),
+            vec!["US", "NL", "US", ""]
+        );
+        assert_eq!(column.get(4), None);
+        assert_eq!(column.distinct(), 3);
+        // 4 u32 keys, "USNL" and 4 i32 offsets
+        assert_eq!(column.memory_size(), 16 + 4 + 16);
+    }
+
+    #[test]
+    fn test_from_array_keeps_dictionary() {
+        let dict: DictionaryArray<UInt8Type> = vec!["US", "NL", "US"].into_iter().collect();
+        let array: ArrayRef = Arc::new(dict);
+        let column = StringColumn::from_array(&array).unwrap();
+
+        assert!(matches!(column.keys, Keys::U8(_)));
+        assert_eq!(column.get(2), Some("US"));
+        assert_eq!(column.to_array().data_type(), array.data_type());
+    }
+
+    #[test]
+    fn test_from_array_converts() {
+        let plain: ArrayRef = Arc::new(StringArray::from(vec![Some("x"), None, Some("x")]));
+        let column = StringColumn::from_array(&plain).unwrap();
+        assert_eq!(column.iter().collect::<Vec<_>>(), vec!["x", "", "x"]);
+
+        let large: ArrayRef = Arc::new(LargeStringArray::from(vec!["l1", "l2"]));
+        assert_eq!(StringColumn::from_array(&large).unwrap().get(1), Some("l2"));
+
+        let dict32: DictionaryArray<Int32Type> = vec!["a", "b", "a"].into_iter().collect();
+        let dict32: ArrayRef = Arc::new(dict32);
+        assert_eq!(StringColumn::from_array(&dict32).unwrap().get(2), Some("a"));
+
+        let ints: ArrayRef = Arc::new(Int32Array::from(vec![1]));
+        assert!(StringColumn::from_array(&ints).is_none());
+    }
+}
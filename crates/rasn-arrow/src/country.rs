@@ -0,0 +1,145 @@
+//! Country index
+//!
+//! Loads `country-index.parquet` (country → IPv4 allocations, sorted by
+//! country then start address) so that "all address space of country XX"
+//! needs no table scan. The index can also be derived from the `countries`
+//! column of an IPv4 range table when the file is not available.
+
+use crate::loader::LoadedTable;
+use crate::{IpRangeTableV4, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Country → IPv4 ranges index
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasn_arrow::CountryIndex;
+/// use std::path::Path;
+///
+/// let index = CountryIndex::from_parquet(Path::new("data/arrow/country-index.parquet"))?;
+/// println!("NL has {} IPv4 ranges", index.ranges("NL").len());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct CountryIndex {
+    ranges: HashMap<String, Vec<(u32, u32)>>,
+}
+
+impl CountryIndex {
+    /// Build the index from `(country, start, end)` rows
+    ///
+    /// Country codes are upper-cased; rows with an empty country are skipped.
+    /// Ranges are sorted by start address.
+    pub fn from_rows<'a>(rows: impl IntoIterator<Item = (&'a str, u32, u32)>) -> Self {
+        let mut ranges: HashMap<String, Vec<(u32, u32)>> = HashMap::new();
+
+        for (country, start, end) in rows {
+            if country.is_empty() {
+                continue;
+            }
+            ranges
+                .entry(country.to_uppercase())
+                .or_default()
+                .push((start, end));
+        }
+
+        for list in ranges.values_mut() {
+            list.sort_unstable();
+        }
+
+        Self { ranges }
+    }
+
+    /// Derive the index from the `countries` column of an IPv4 range table
+    pub fn from_table(table: &IpRangeTableV4) -> Self {
+        Self::from_rows(
+            table
+                .org_rows()
+                .enumerate()
+                .filter_map(|(row, (_, _, country))| {
+                    let (start, end) = table.range_at(row)?;
+                    Some((country, start, end))
+                }),
+        )
+    }
+
+    /// Load the country index from Parquet file
+    pub fn from_parquet(path: &Path) -> Result<Self> {
+        let table = LoadedTable::read_parquet(path)?;
+
+        // Resolve columns by name
+        let countries = table.string_column("country")?;
+        let start_ips = table.u32_column("start_ip")?;
+        let end_ips = table.u32_column("end_ip")?;
+
+        Ok(Self::from_rows(
+            countries
+                .iter()
+                .zip(start_ips.values().iter().zip(end_ips.values().iter()))
+                .map(|(country, (&start, &end))| (country.as_str(), start, end)),
+        ))
+    }
+
+    /// Get the IPv4 ranges of a country (case-insensitive), sorted by address
+    pub fn ranges(&self, country: &str) -> &[(u32, u32)] {
+        self.ranges
+            .get(&country.trim().to_uppercase())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Iterate over all country codes (in no particular order)
+    pub fn countries(&self) -> impl Iterator<Item = &str> {
+        self.ranges.keys().map(String::as_str)
+    }
+
+    /// Get the number of countries
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Check if the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_rows() {
+        let index = CountryIndex::from_rows([
+            ("US", 0x08080800, 0x080808FF),
+            ("nl", 0x05000000, 0x050000FF),
+            ("US", 0x04000000, 0x04FFFFFF),
+            ("", 0x0A000000, 0x0AFFFFFF),
+        ]);
+
+        assert_eq!(index.len(), 2);
+        assert_eq!(
+            index.ranges("us"),
+            &[(0x04000000, 0x04FFFFFF), (0x08080800, 0x080808FF)]
+        );
+        assert_eq!(index.ranges("NL").len(), 1);
+        assert!(index.ranges("DE").is_empty());
+    }
+
+    #[test]
+    fn test_from_table() {
+        let table = IpRangeTableV4::from_vecs(
+            vec![0x01000000, 0x08080800],
+            vec![0x010000FF, 0x080808FF],
+            vec![13335, 15169],
+            vec!["AU".to_string(), "US".to_string()],
+            vec!["CLOUDFLARENET".to_string(), "GOOGLE".to_string()],
+        )
+        .unwrap();
+
+        let index = CountryIndex::from_table(&table);
+        assert_eq!(index.ranges("AU"), &[(0x01000000, 0x010000FF)]);
+        assert_eq!(index.countries().count(), 2);
+    }
+}
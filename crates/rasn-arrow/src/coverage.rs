@@ -0,0 +1,430 @@
+//! Address block coverage
+//!
+//! [`Coverage`] breaks an address block down by the table ranges that
+//! overlap it: which ASNs and countries hold how much of the block, and
+//! which stretches no range covers. It is returned by
+//! [`IpRangeTableV4::coverage`](crate::IpRangeTableV4::coverage) and
+//! [`IpRangeTableV6::coverage`](crate::IpRangeTableV6::coverage).
+//!
+//! The table is binary-searched for the first range ending inside the
+//! block and walked forward until a range starts past it, so the cost is
+//! O(log n + overlapping ranges): a /8 is analysed from its ranges, never
+//! from its 16 million addresses.
+//!
+//! [`CidrAnalysis`] is the report built from it, shared by the MCP
+//! `cidr_analyze` tool and `rasn cidr analyze` so both emit the same JSON.
+
+use crate::entry::RangeEntry;
+use crate::validate::serialize_u128;
+use rasn_cidr::IpCidr;
+use rasn_core::Asn;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+
+/// Share of a block held by one ASN
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AsnCoverage {
+    /// The ASN
+    pub asn: Asn,
+    /// Organization of the ASN's first range in the block
+    pub organization: String,
+    /// Distinct non-empty countries of its ranges in the block, sorted
+    pub countries: Vec<String>,
+    /// Number of its table ranges overlapping the block
+    pub ranges: usize,
+    /// Addresses of the block it holds
+    #[serde(serialize_with = "serialize_u128")]
+    pub addresses: u128,
+    /// Percentage of the block it holds
+    pub percent: f64,
+}
+
+/// Share of a block registered to one country
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CountryCoverage {
+    /// Country code, empty when unknown
+    pub country: String,
+    /// Number of ASNs holding addresses of the block in this country
+    pub asns: usize,
+    /// Addresses of the block in this country
+    #[serde(serialize_with = "serialize_u128")]
+    pub addresses: u128,
+    /// Percentage of the block in this country
+    pub percent: f64,
+}
+
+/// Stretch of a block no table range covers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CoverageGap {
+    /// First address
+    pub start: IpAddr,
+    /// Last address (inclusive)
+    pub end: IpAddr,
+    /// Number of addresses in the gap
+    #[serde(serialize_with = "serialize_u128")]
+    pub addresses: u128,
+}
+
+/// Breakdown of an address block by the table ranges overlapping it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Coverage {
+    /// First address of the block
+    pub start: IpAddr,
+    /// Last address of the block (inclusive)
+    pub end: IpAddr,
+    /// Addresses in the block (saturates at `u128::MAX` for `::/0`)
+    #[serde(serialize_with = "serialize_u128")]
+    pub total_addresses: u128,
+    /// Addresses of the block covered by at least one range
+    #[serde(serialize_with = "serialize_u128")]
+    pub covered_addresses: u128,
+    /// Percentage of the block covered
+    pub percent_covered: f64,
+    /// Number of table ranges overlapping the block
+    pub ranges: usize,
+    /// ASNs holding addresses of the block, largest share first
+    pub asns: Vec<AsnCoverage>,
+    /// Countries of the covered addresses, largest share first
+    pub countries: Vec<CountryCoverage>,
+    /// Uncovered stretches of the block, in address order
+    pub gaps: Vec<CoverageGap>,
+}
+
+/// Report on an address block: its arithmetic and coverage by a table
+///
+/// Addresses are strings and counts above `u64` are strings, so IPv4 and
+/// IPv6 blocks serialize the same way.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CidrAnalysis {
+    /// The block in canonical notation (host bits cleared)
+    pub cidr: String,
+    /// Address family, `"ipv4"` or `"ipv6"`
+    pub family: &'static str,
+    /// Prefix length
+    pub prefix_len: u8,
+    /// First address of the block
+    pub start: IpAddr,
+    /// Last address of the block (inclusive)
+    pub end: IpAddr,
+    /// Addresses in the block (saturates at `u128::MAX` for `::/0`)
+    #[serde(serialize_with = "serialize_u128")]
+    pub total_addresses: u128,
+    /// First host address, IPv4 only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_usable: Option<IpAddr>,
+    /// Last host address, IPv4 only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_usable: Option<IpAddr>,
+    /// Coverage by the family's range table, `None` when it is not loaded
+    pub coverage: Option<CoverageSummary>,
+}
+
+/// [`Coverage`] with its lists capped at a limit
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CoverageSummary {
+    /// Addresses of the block covered by at least one range
+    #[serde(serialize_with = "serialize_u128")]
+    pub covered_addresses: u128,
+    /// Percentage of the block covered
+    pub percent_covered: f64,
+    /// Number of table ranges overlapping the block
+    pub ranges: usize,
+    /// Number of ASNs holding addresses of the block
+    pub asn_count: usize,
+    /// Largest ASN shares, at most `limit`
+    pub asns: Vec<AsnCoverage>,
+    /// Number of countries of the covered addresses
+    pub country_count: usize,
+    /// Largest country shares, at most `limit`
+    pub countries: Vec<CountryCoverage>,
+    /// Number of uncovered stretches
+    pub gap_count: usize,
+    /// First uncovered stretches, at most `limit`
+    pub gaps: Vec<CoverageGap>,
+}
+
+impl CidrAnalysis {
+    /// Build the report of a block
+    ///
+    /// # Arguments
+    ///
+    /// * `cidr` - The analysed block
+    /// * `coverage` - Its coverage, `None` when no table of its family is loaded
+    /// * `limit` - Maximum number of ASNs, countries and gaps listed
+    pub fn new(cidr: &IpCidr, coverage: Option<Coverage>, limit: usize) -> Self {
+        let (first_usable, last_usable) = match cidr {
+            IpCidr::V4(block) => (
+                Some(IpAddr::from(block.first_usable().to_be_bytes())),
+                Some(IpAddr::from(block.last_usable().to_be_bytes())),
+            ),
+            IpCidr::V6(_) => (None, None),
+        };
+
+        Self {
+            cidr: cidr.to_string(),
+            family: if cidr.is_ipv6() { "ipv6" } else { "ipv4" },
+            prefix_len: cidr.prefix_len(),
+            start: cidr.first(),
+            end: cidr.last(),
+            total_addresses: cidr.size(),
+            first_usable,
+            last_usable,
+            coverage: coverage.map(|coverage| CoverageSummary {
+                covered_addresses: coverage.covered_addresses,
+                percent_covered: coverage.percent_covered,
+                ranges: coverage.ranges,
+                asn_count: coverage.asns.len(),
+                asns: coverage.asns.into_iter().take(limit).collect(),
+                country_count: coverage.countries.len(),
+                countries: coverage.countries.into_iter().take(limit).collect(),
+                gap_count: coverage.gaps.len(),
+                gaps: coverage.gaps.into_iter().take(limit).collect(),
+            }),
+        }
+    }
+}
+
+#[derive(Default)]
+struct AsnTotals {
+    organization: String,
+    countries: BTreeSet<String>,
+    ranges: usize,
+    addresses: u128,
+}
+
+/// Compute the coverage of the block `start..=end`
+///
+/// `entries` are the table rows overlapping the block in address order,
+/// with addresses widened to u128; `to_ip` converts back to the table's
+/// address family.
+pub(crate) fn block_coverage<'a>(
+    start: u128,
+    end: u128,
+    entries: impl Iterator<Item = RangeEntry<'a, u128>>,
+    to_ip: impl Fn(u128) -> IpAddr,
+) -> Coverage {
+    let total = (end - start).saturating_add(1);
+    let percent = |addresses: u128| 100.0 * addresses as f64 / total as f64;
+
+    let mut asns: BTreeMap<Asn, AsnTotals> = BTreeMap::new();
+    let mut countries: BTreeMap<&str, (BTreeSet<Asn>, u128)> = BTreeMap::new();
+    let mut gaps = Vec::new();
+    let mut ranges = 0;
+    let mut covered: u128 = 0;
+    // First address not yet covered; `None` once the end of the address
+    // space is covered
+    let mut next = Some(start);
+
+    for entry in entries {
+        let lo = entry.start.max(start);
+        let hi = entry.end.min(end);
+        if lo > hi {
+            continue;
+        }
+        ranges += 1;
+
+        if let Some(uncovered) = next {
+            if lo > uncovered {
+                gaps.push(CoverageGap {
+                    start: to_ip(uncovered),
+                    end: to_ip(lo - 1),
+                    addresses: lo - uncovered,
+                });
+            }
+            if hi >= uncovered {
+                covered = covered.saturating_add((hi - lo.max(uncovered)).saturating_add(1));
+                next = hi.checked_add(1);
+            }
+        }
+
+        let addresses = (hi - lo).saturating_add(1);
+        let totals = asns.entry(entry.asn).or_default();
+        if totals.ranges == 0 {
+            totals.organization = entry.organization.to_string();
+        }
+        if !entry.country.is_empty() {
+            totals.countries.insert(entry.country.to_string());
+        }
+        totals.ranges += 1;
+        totals.addresses = totals.addresses.saturating_add(addresses);
+
+        let country = countries.entry(entry.country).or_default();
+        country.0.insert(entry.asn);
+        country.1 = country.1.saturating_add(addresses);
+    }
+
+    if let Some(uncovered) = next.filter(|&uncovered| uncovered <= end) {
+        gaps.push(CoverageGap {
+            start: to_ip(uncovered),
+            end: to_ip(end),
+            addresses: (end - uncovered).saturating_add(1),
+        });
+    }
+
+    let mut asns: Vec<AsnCoverage> = asns
+        .into_iter()
+        .map(|(asn, totals)| AsnCoverage {
+            asn,
+            organization: totals.organization,
+            countries: totals.countries.into_iter().collect(),
+            ranges: totals.ranges,
+            addresses: totals.addresses,
+            percent: percent(totals.addresses),
+        })
+        .collect();
+    asns.sort_by(|a, b| b.addresses.cmp(&a.addresses).then(a.asn.cmp(&b.asn)));
+
+    let mut countries: Vec<CountryCoverage> = countries
+        .into_iter()
+        .map(|(country, (asns, addresses))| CountryCoverage {
+            country: country.to_string(),
+            asns: asns.len(),
+            addresses,
+            percent: percent(addresses),
+        })
+        .collect();
+    countries.sort_by(|a, b| {
+        b.addresses
+            .cmp(&a.addresses)
+            .then_with(|| a.country.cmp(&b.country))
+    });
+
+    Coverage {
+        start: to_ip(start),
+        end: to_ip(end),
+        total_addresses: total,
+        covered_addresses: covered,
+        percent_covered: percent(covered),
+        ranges,
+        asns,
+        countries,
+        gaps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn entry(start: u128, end: u128, asn: u32, country: &str) -> RangeEntry<'_, u128> {
+        RangeEntry {
+            start,
+            end,
+            asn: Asn(asn),
+            organization: if asn == 64500 { "FIRST" } else { "OTHER" },
+            country,
+        }
+    }
+
+    fn v4(ip: u128) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::from(ip as u32))
+    }
+
+    #[test]
+    fn test_block_coverage() {
+        // Block 0-255: 0-63 AS64500/US, 64-99 gap, 100-149 AS64501/NL,
+        // 150-199 AS64500/DE, 200-255 gap; the last row starts before the
+        // block end and runs past it
+        let entries = vec![
+            entry(0, 63, 64500, "US"),
+            entry(100, 149, 64501, "NL"),
+            entry(150, 199, 64500, "DE"),
+        ];
+        let coverage = block_coverage(0, 255, entries.into_iter(), v4);
+
+        assert_eq!(coverage.total_addresses, 256);
+        assert_eq!(coverage.covered_addresses, 164);
+        assert_eq!(coverage.ranges, 3);
+        assert!((coverage.percent_covered - 64.0625).abs() < 1e-9);
+
+        assert_eq!(coverage.asns.len(), 2);
+        assert_eq!(coverage.asns[0].asn, Asn(64500));
+        assert_eq!(coverage.asns[0].addresses, 114);
+        assert_eq!(coverage.asns[0].ranges, 2);
+        assert_eq!(coverage.asns[0].organization, "FIRST");
+        assert_eq!(coverage.asns[0].countries, vec!["DE", "US"]);
+        assert_eq!(coverage.asns[1].addresses, 50);
+
+        let countries: Vec<(&str, u128)> = coverage
+            .countries
+            .iter()
+            .map(|c| (c.country.as_str(), c.addresses))
+            .collect();
+        assert_eq!(countries, vec![("US", 64), ("DE", 50), ("NL", 50)]);
+
+        let gaps: Vec<(IpAddr, IpAddr, u128)> = coverage
+            .gaps
+            .iter()
+            .map(|g| (g.start, g.end, g.addresses))
+            .collect();
+        assert_eq!(gaps, vec![(v4(64), v4(99), 36), (v4(200), v4(255), 56)]);
+    }
+
+    #[test]
+    fn test_block_coverage_clipped() {
+        // Rows reaching outside the block only count their overlap
+        let entries = vec![entry(0, 1000, 64500, ""), entry(1001, 5000, 64501, "NL")];
+        let coverage = block_coverage(512, 1023, entries.into_iter(), v4);
+
+        assert_eq!(coverage.covered_addresses, 512);
+        assert!(coverage.gaps.is_empty());
+        assert_eq!(coverage.asns[0].addresses, 489);
+        assert!(coverage.asns[0].countries.is_empty());
+        assert_eq!(coverage.countries[0].country, "");
+
+        let empty = block_coverage(0, 255, std::iter::empty(), v4);
+        assert_eq!(empty.covered_addresses, 0);
+        assert_eq!(empty.percent_covered, 0.0);
+        assert_eq!(empty.gaps.len(), 1);
+        assert_eq!(empty.gaps[0].addresses, 256);
+    }
+
+    #[test]
+    fn test_cidr_analysis() {
+        let entries = vec![entry(0x0A000000, 0x0A00003F, 64500, "US")];
+        let cidr = IpCidr::parse("10.0.0.7/24").unwrap();
+        let coverage = block_coverage(0x0A000000, 0x0A0000FF, entries.into_iter(), v4);
+        let analysis = CidrAnalysis::new(&cidr, Some(coverage), 0);
+
+        assert_eq!(analysis.cidr, "10.0.0.0/24");
+        assert_eq!(analysis.family, "ipv4");
+        assert_eq!(analysis.start, v4(0x0A000000));
+        assert_eq!(analysis.end, v4(0x0A0000FF));
+        assert_eq!(analysis.first_usable, Some(v4(0x0A000001)));
+        assert_eq!(analysis.total_addresses, 256);
+
+        // Lists are capped; the counts are not
+        let summary = analysis.coverage.as_ref().unwrap();
+        assert_eq!(summary.covered_addresses, 64);
+        assert_eq!(summary.asn_count, 1);
+        assert!(summary.asns.is_empty());
+        assert_eq!(summary.gap_count, 1);
+
+        let cidr6 = IpCidr::parse("2001:DB8::1/32").unwrap();
+        let analysis = CidrAnalysis::new(&cidr6, None, 10);
+        let json = serde_json::to_value(&analysis).unwrap();
+        assert_eq!(json["cidr"], "2001:db8::/32");
+        assert_eq!(json["family"], "ipv6");
+        assert_eq!(json["end"], "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff");
+        assert_eq!(json["total_addresses"], "79228162514264337593543950336");
+        assert!(json.get("first_usable").is_none());
+        assert!(json["coverage"].is_null());
+    }
+
+    #[test]
+    fn test_block_coverage_full_ipv6() {
+        let to_ip = |ip: u128| IpAddr::V6(ip.into());
+        let entries = vec![
+            entry(0, u128::MAX - 1, 64500, "US"),
+            entry(u128::MAX, u128::MAX, 64501, "US"),
+        ];
+        let coverage = block_coverage(0, u128::MAX, entries.into_iter(), to_ip);
+
+        assert_eq!(coverage.total_addresses, u128::MAX);
+        assert_eq!(coverage.covered_addresses, u128::MAX);
+        assert!(coverage.gaps.is_empty());
+        assert_eq!(coverage.countries[0].asns, 2);
+    }
+}
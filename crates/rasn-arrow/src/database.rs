@@ -0,0 +1,926 @@
+//! Dual-stack lookup facade
+//!
+//! [`IpAsnDatabase`] owns the IPv4 and IPv6 range tables and dispatches
+//! lookups on the address family of a [`std::net::IpAddr`]. When an ASN
+//! metadata table is attached, results are joined against it. An
+//! [`AsnIndex`] per table answers the reverse question (ASN → prefixes).
+//!
+//! Range tables are opened from memory-mapped Arrow IPC files when present
+//! (see [`IpRangeTableV4::from_ipc`]), so a short-lived process such as a
+//! single CLI lookup does not pay for decoding the Parquet files.
+
+use crate::search::OrgCandidates;
+use crate::{
+    ArrowError, AsnIndex, AsnMetadata, AsnMetadataTable, CidrAnalysis, CountryIndex,
+    IpRangeTableV4, IpRangeTableV6, MemoryUsage, OrgMatch, OrgQuery, Result,
+};
+use rasn_cidr::IpCidr;
+use rasn_core::{Asn, AsnInfo, AsnLookup, LookupAnswer, LookupSource};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// File name of the IPv4 range table inside a data directory
+pub const IPV4_TABLE_FILE: &str = "ip2asn-v4.parquet";
+
+/// File name of the IPv6 range table inside a data directory
+pub const IPV6_TABLE_FILE: &str = "ip2asn-v6.parquet";
+
+/// File name of the memory-mappable IPv4 range table inside a data directory
+pub const IPV4_IPC_FILE: &str = "ip2asn-v4.arrow";
+
+/// File name of the memory-mappable IPv6 range table inside a data directory
+pub const IPV6_IPC_FILE: &str = "ip2asn-v6.arrow";
+
+/// File name of the ASN metadata table inside a data directory
+pub const ASN_METADATA_FILE: &str = "asn-metadata.parquet";
+
+/// File name of the country index inside a data directory
+pub const COUNTRY_INDEX_FILE: &str = "country-index.parquet";
+
+/// All ranges announced by a single ASN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsnPrefixes {
+    /// The ASN number
+    pub asn: Asn,
+    /// Organization name from the range tables, or the registry handle
+    pub organization: Option<String>,
+    /// Country code of the first announced range
+    pub country: Option<String>,
+    /// Registry handle, if the metadata table is loaded
+    pub handle: Option<String>,
+    /// Organization description, if the metadata table is loaded
+    pub description: Option<String>,
+    /// Inclusive IPv4 `(start, end)` ranges in address order
+    pub ipv4_ranges: Vec<(u32, u32)>,
+    /// Inclusive IPv6 `(start, end)` ranges in address order
+    pub ipv6_ranges: Vec<(u128, u128)>,
+}
+
+impl AsnPrefixes {
+    /// Get the number of IPv4 addresses covered by all ranges
+    pub fn ipv4_address_count(&self) -> u64 {
+        ipv4_address_count(&self.ipv4_ranges)
+    }
+
+    /// Get the number of IPv6 addresses covered by all ranges
+    ///
+    /// Saturates at `u128::MAX`.
+    pub fn ipv6_address_count(&self) -> u128 {
+        ipv6_address_count(&self.ipv6_ranges)
+    }
+}
+
+/// An ASN announcing ranges registered in a country
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CountryAsn {
+    /// The ASN number
+    pub asn: Asn,
+    /// Organization name of the ASN's first range in the country
+    pub name: String,
+    /// Number of IPv4 ranges in the country
+    pub ipv4_ranges: usize,
+    /// Number of IPv6 ranges in the country
+    pub ipv6_ranges: usize,
+}
+
+/// Everything registered in a single country
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountrySummary {
+    /// Upper-case ISO 3166-1 alpha-2 country code
+    pub country: String,
+    /// ASNs with ranges in the country, most ranges first
+    pub asns: Vec<CountryAsn>,
+    /// Inclusive IPv4 `(start, end)` ranges in address order
+    pub ipv4_ranges: Vec<(u32, u32)>,
+    /// Inclusive IPv6 `(start, end)` ranges in address order
+    pub ipv6_ranges: Vec<(u128, u128)>,
+    /// IPv4 ranges the country index geolocates to the country, if loaded
+    ///
+    /// The index is keyed by geolocation rather than registration, so these
+    /// need not match `ipv4_ranges` or the per-ASN counts.
+    pub geo_ipv4_ranges: Option<Vec<(u32, u32)>>,
+}
+
+impl CountrySummary {
+    /// Get the number of IPv4 addresses covered by all ranges
+    pub fn ipv4_address_count(&self) -> u64 {
+        ipv4_address_count(&self.ipv4_ranges)
+    }
+
+    /// Get the number of IPv4 addresses geolocated to the country
+    pub fn geo_ipv4_address_count(&self) -> Option<u64> {
+        self.geo_ipv4_ranges.as_deref().map(ipv4_address_count)
+    }
+
+    /// Get the number of IPv6 addresses covered by all ranges
+    ///
+    /// Saturates at `u128::MAX`.
+    pub fn ipv6_address_count(&self) -> u128 {
+        ipv6_address_count(&self.ipv6_ranges)
+    }
+}
+
+/// IP→ASN database covering both address families
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasn_arrow::IpAsnDatabase;
+/// use std::path::Path;
+///
+/// let db = IpAsnDatabase::open(Path::new("data/arrow"))?;
+/// if let Some(info) = db.lookup("2001:4860:4860::8888".parse()?) {
+///     println!("ASN: {}", info.asn);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct IpAsnDatabase {
+    v4: Option<IpRangeTableV4>,
+    v6: Option<IpRangeTableV6>,
+    metadata: Option<AsnMetadataTable>,
+    countries: Option<CountryIndex>,
+    v4_index: OnceLock<AsnIndex>,
+    v6_index: OnceLock<AsnIndex>,
+}
+
+impl IpAsnDatabase {
+    /// Create a database from already loaded tables
+    ///
+    /// The ASN reverse index of each table is built on first use.
+    pub fn new(v4: Option<IpRangeTableV4>, v6: Option<IpRangeTableV6>) -> Self {
+        Self {
+            v4,
+            v6,
+            metadata: None,
+            countries: None,
+            v4_index: OnceLock::new(),
+            v6_index: OnceLock::new(),
+        }
+    }
+
+    /// Attach an ASN metadata table used to enrich lookup results
+    pub fn with_metadata(mut self, metadata: AsnMetadataTable) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Attach a country index used by [`IpAsnDatabase::country_summary`]
+    pub fn with_country_index(mut self, countries: CountryIndex) -> Self {
+        self.countries = Some(countries);
+        self
+    }
+
+    /// Open the range tables found in a data directory
+    ///
+    /// Loads the IPv4 and IPv6 range tables; either may be missing, but not
+    /// both. Each table is memory-mapped from its `.arrow` file when that file
+    /// is at least as new as the `.parquet` file, and read from Parquet
+    /// otherwise. `asn-metadata.parquet` and `country-index.parquet` are
+    /// loaded when present.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory containing the table files (e.g. `data/arrow`)
+    pub fn open(dir: &Path) -> Result<Self> {
+        let v4_path = table_file(dir, IPV4_TABLE_FILE, IPV4_IPC_FILE);
+        let v6_path = table_file(dir, IPV6_TABLE_FILE, IPV6_IPC_FILE);
+
+        if v4_path.is_none() && v6_path.is_none() {
+            return Err(ArrowError::FileNotFound(format!(
+                "{} or {} in {}",
+                IPV4_TABLE_FILE,
+                IPV6_TABLE_FILE,
+                dir.display()
+            )));
+        }
+
+        let v4 = v4_path
+            .map(|path| IpRangeTableV4::open(&path))
+            .transpose()?;
+        let v6 = v6_path
+            .map(|path| IpRangeTableV6::open(&path))
+            .transpose()?;
+
+        let mut db = Self::new(v4, v6);
+
+        let metadata_path = dir.join(ASN_METADATA_FILE);
+        if metadata_path.exists() {
+            db.metadata = Some(AsnMetadataTable::from_parquet(&metadata_path)?);
+        }
+
+        let country_path = dir.join(COUNTRY_INDEX_FILE);
+        if country_path.exists() {
+            db.countries = Some(CountryIndex::from_parquet(&country_path)?);
+        }
+
+        Ok(db)
+    }
+
+    /// Load a database from a data directory or a single IPv4 table file
+    ///
+    /// Directories are opened with [`IpAsnDatabase::open`]; a file path is
+    /// loaded as an IPv4 range table (Arrow IPC or Parquet).
+    pub fn load(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            Self::open(path)
+        } else {
+            Ok(Self::new(Some(IpRangeTableV4::open(path)?), None))
+        }
+    }
+
+    /// Find ASN information for an IPv4 or IPv6 address
+    ///
+    /// IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`) are looked up in the
+    /// IPv4 table. `description` and `handle` are filled from the ASN
+    /// metadata table when one is attached.
+    pub fn lookup(&self, ip: IpAddr) -> Option<AsnInfo> {
+        let mut info = match ip.to_canonical() {
+            IpAddr::V4(ipv4) => self.v4.as_ref()?.find_ip(u32::from(ipv4)),
+            IpAddr::V6(ipv6) => self.v6.as_ref()?.find_ip(u128::from(ipv6)),
+        }?;
+
+        if let Some(ref metadata) = self.metadata {
+            metadata.enrich(&mut info);
+        }
+
+        Some(info)
+    }
+
+    /// Find ASN information for a batch of addresses
+    ///
+    /// Returns one result per address, in input order, with the same
+    /// results as [`IpAsnDatabase::lookup`]. Each family is looked up with
+    /// a single sorted pass over its range table.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rasn_arrow::IpAsnDatabase;
+    /// # use std::path::Path;
+    /// # let db = IpAsnDatabase::open(Path::new("data/arrow"))?;
+    /// let ips = ["8.8.8.8".parse()?, "2606:4700:4700::1111".parse()?];
+    /// for (ip, info) in ips.iter().zip(db.lookup_many(&ips)) {
+    ///     println!("{}: {:?}", ip, info.map(|info| info.asn));
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_many(&self, ips: &[IpAddr]) -> Vec<Option<AsnInfo>> {
+        let (mut v4_rows, mut v4_ips) = (Vec::new(), Vec::new());
+        let (mut v6_rows, mut v6_ips) = (Vec::new(), Vec::new());
+        for (row, ip) in ips.iter().enumerate() {
+            match ip.to_canonical() {
+                IpAddr::V4(ipv4) => {
+                    v4_rows.push(row);
+                    v4_ips.push(u32::from(ipv4));
+                }
+                IpAddr::V6(ipv6) => {
+                    v6_rows.push(row);
+                    v6_ips.push(u128::from(ipv6));
+                }
+            }
+        }
+
+        let mut results = vec![None; ips.len()];
+        if let Some(ref v4) = self.v4 {
+            for (row, info) in v4_rows.into_iter().zip(v4.find_many(&v4_ips)) {
+                results[row] = info;
+            }
+        }
+        if let Some(ref v6) = self.v6 {
+            for (row, info) in v6_rows.into_iter().zip(v6.find_many(&v6_ips)) {
+                results[row] = info;
+            }
+        }
+
+        if let Some(ref metadata) = self.metadata {
+            for info in results.iter_mut().flatten() {
+                metadata.enrich(info);
+            }
+        }
+
+        results
+    }
+
+    /// Analyse an address block against the table of its family
+    ///
+    /// The report's coverage is `None` when that table is not loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `cidr` - IPv4 or IPv6 block
+    /// * `limit` - Maximum number of ASNs, countries and gaps listed
+    pub fn analyze_cidr(&self, cidr: &IpCidr, limit: usize) -> CidrAnalysis {
+        let coverage = match cidr {
+            IpCidr::V4(block) => self
+                .v4()
+                .map(|table| table.coverage(block.network(), block.broadcast())),
+            IpCidr::V6(block) => self
+                .v6()
+                .map(|table| table.coverage(block.first(), block.last())),
+        };
+        CidrAnalysis::new(cidr, coverage, limit)
+    }
+
+    /// Get registry metadata for an ASN, if the metadata table is loaded
+    pub fn asn_metadata(&self, asn: Asn) -> Option<AsnMetadata> {
+        self.metadata.as_ref()?.get(asn)
+    }
+
+    /// Get every range announced by an ASN
+    ///
+    /// Uses the ASN reverse index, so the cost is proportional to the number
+    /// of ranges returned. Returns `None` when the ASN announces nothing and
+    /// has no metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rasn_arrow::IpAsnDatabase;
+    /// # use rasn_core::Asn;
+    /// # use std::path::Path;
+    /// # let db = IpAsnDatabase::open(Path::new("data/arrow"))?;
+    /// if let Some(prefixes) = db.asn_prefixes(Asn(15169)) {
+    ///     println!("{} IPv6 ranges", prefixes.ipv6_ranges.len());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn asn_prefixes(&self, asn: Asn) -> Option<AsnPrefixes> {
+        let v4_rows = self.v4_index().rows(asn);
+        let v6_rows = self.v6_index().rows(asn);
+        let metadata = self.asn_metadata(asn);
+
+        if v4_rows.is_empty() && v6_rows.is_empty() && metadata.is_none() {
+            return None;
+        }
+
+        let ipv4_ranges = self.v4.as_ref().map_or_else(Vec::new, |t| {
+            v4_rows
+                .iter()
+                .filter_map(|&row| t.range_at(row as usize))
+                .collect()
+        });
+        let ipv6_ranges = self.v6.as_ref().map_or_else(Vec::new, |t| {
+            v6_rows
+                .iter()
+                .filter_map(|&row| t.range_at(row as usize))
+                .collect()
+        });
+
+        // Organization and country come from the first announced range
+        let first = v4_rows
+            .first()
+            .and_then(|&row| self.v4.as_ref()?.info_at(row as usize))
+            .or_else(|| {
+                v6_rows
+                    .first()
+                    .and_then(|&row| self.v6.as_ref()?.info_at(row as usize))
+            });
+
+        let (organization, country) = match first {
+            Some(info) => (Some(info.organization), info.country),
+            None => (metadata.as_ref().map(|m| m.handle.clone()), None),
+        };
+        let (handle, description) = match metadata {
+            Some(meta) => (non_empty(meta.handle), non_empty(meta.description)),
+            None => (None, None),
+        };
+
+        Some(AsnPrefixes {
+            asn,
+            organization,
+            country,
+            handle,
+            description,
+            ipv4_ranges,
+            ipv6_ranges,
+        })
+    }
+
+    /// Search ASNs by organization name
+    ///
+    /// Matches organization names of both range tables and, when loaded,
+    /// the handles and descriptions of the ASN metadata table. Results are
+    /// one entry per ASN, best match first.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rasn_arrow::{IpAsnDatabase, OrgQuery};
+    /// # use std::path::Path;
+    /// # let db = IpAsnDatabase::open(Path::new("data/arrow"))?;
+    /// for m in db.search_org(&OrgQuery::new("cloudflare").with_limit(5)) {
+    ///     println!("{} {} ({} ranges)", m.asn, m.name, m.ip_range_count);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn search_org(&self, query: &OrgQuery) -> Vec<OrgMatch> {
+        let mut candidates = OrgCandidates::default();
+
+        for (asn, org, country) in self.v4.iter().flat_map(|t| t.org_rows()) {
+            candidates.add_range(asn, org, country);
+        }
+        for (asn, org, country) in self.v6.iter().flat_map(|t| t.org_rows()) {
+            candidates.add_range(asn, org, country);
+        }
+        for (asn, handle, description) in self.metadata.iter().flat_map(|t| t.rows()) {
+            candidates.add_metadata(asn, handle, description);
+        }
+
+        candidates.search(query)
+    }
+
+    /// Summarize the ASNs, ranges and address space of a country
+    ///
+    /// The ASN list and both range lists come from the `countries` column
+    /// of the range tables, so the per-ASN counts add up to the totals. The
+    /// country index, when loaded, is reported separately as
+    /// [`CountrySummary::geo_ipv4_ranges`]. Returns `None` when nothing is
+    /// registered or geolocated in the country.
+    ///
+    /// # Arguments
+    ///
+    /// * `country` - ISO 3166-1 alpha-2 code (case-insensitive)
+    pub fn country_summary(&self, country: &str) -> Option<CountrySummary> {
+        let country = country.trim().to_uppercase();
+
+        let mut asns: HashMap<u32, CountryAsn> = HashMap::new();
+        let mut ipv4_ranges = Vec::new();
+        let mut ipv6_ranges = Vec::new();
+
+        if let Some(ref table) = self.v4 {
+            for (row, (asn, org, cc)) in table.org_rows().enumerate() {
+                if !cc.eq_ignore_ascii_case(&country) {
+                    continue;
+                }
+                country_asn(&mut asns, asn, org).ipv4_ranges += 1;
+                ipv4_ranges.extend(table.range_at(row));
+            }
+        }
+        if let Some(ref table) = self.v6 {
+            for (row, (asn, org, cc)) in table.org_rows().enumerate() {
+                if !cc.eq_ignore_ascii_case(&country) {
+                    continue;
+                }
+                country_asn(&mut asns, asn, org).ipv6_ranges += 1;
+                ipv6_ranges.extend(table.range_at(row));
+            }
+        }
+
+        let geo_ipv4_ranges = self
+            .countries
+            .as_ref()
+            .map(|index| index.ranges(&country).to_vec());
+
+        if asns.is_empty()
+            && ipv4_ranges.is_empty()
+            && ipv6_ranges.is_empty()
+            && geo_ipv4_ranges.as_ref().map_or(true, Vec::is_empty)
+        {
+            return None;
+        }
+
+        let mut asns: Vec<CountryAsn> = asns.into_values().collect();
+        asns.sort_by(|a, b| {
+            (b.ipv4_ranges + b.ipv6_ranges)
+                .cmp(&(a.ipv4_ranges + a.ipv6_ranges))
+                .then(a.asn.cmp(&b.asn))
+        });
+
+        Some(CountrySummary {
+            country,
+            asns,
+            ipv4_ranges,
+            ipv6_ranges,
+            geo_ipv4_ranges,
+        })
+    }
+
+    /// Get the memory used by the IPv4 and IPv6 range tables combined
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let v4 = self.v4.as_ref().map(IpRangeTableV4::memory_usage);
+        let v6 = self.v6.as_ref().map(IpRangeTableV6::memory_usage);
+        v4.unwrap_or_default() + v6.unwrap_or_default()
+    }
+
+    /// Get the country index, if loaded
+    pub fn country_index(&self) -> Option<&CountryIndex> {
+        self.countries.as_ref()
+    }
+
+    /// Get the ASN reverse index of the IPv4 table
+    pub fn v4_index(&self) -> &AsnIndex {
+        self.v4_index.get_or_init(|| {
+            self.v4
+                .as_ref()
+                .map(|t| AsnIndex::build(t.asn_values().iter().copied()))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Get the ASN reverse index of the IPv6 table
+    pub fn v6_index(&self) -> &AsnIndex {
+        self.v6_index.get_or_init(|| {
+            self.v6
+                .as_ref()
+                .map(|t| AsnIndex::build(t.asn_values().iter().copied()))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Get the IPv4 range table, if loaded
+    pub fn v4(&self) -> Option<&IpRangeTableV4> {
+        self.v4.as_ref()
+    }
+
+    /// Get the IPv6 range table, if loaded
+    pub fn v6(&self) -> Option<&IpRangeTableV6> {
+        self.v6.as_ref()
+    }
+
+    /// Get the ASN metadata table, if loaded
+    pub fn metadata(&self) -> Option<&AsnMetadataTable> {
+        self.metadata.as_ref()
+    }
+
+    /// Get the total number of IP ranges across both tables
+    pub fn len(&self) -> usize {
+        self.v4.as_ref().map_or(0, |t| t.len()) + self.v6.as_ref().map_or(0, |t| t.len())
+    }
+
+    /// Check if the database has no ranges
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Get the per-country entry of an ASN, created on first sight
+fn country_asn<'a>(
+    asns: &'a mut HashMap<u32, CountryAsn>,
+    asn: u32,
+    org: &str,
+) -> &'a mut CountryAsn {
+    asns.entry(asn).or_insert_with(|| CountryAsn {
+        asn: Asn(asn),
+        name: org.to_string(),
+        ipv4_ranges: 0,
+        ipv6_ranges: 0,
+    })
+}
+
+/// Pick a table's Arrow IPC file unless it is missing or older than its Parquet file
+fn table_file(dir: &Path, parquet_file: &str, ipc_file: &str) -> Option<PathBuf> {
+    let parquet = dir.join(parquet_file);
+    let ipc = dir.join(ipc_file);
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    match (modified(&ipc), modified(&parquet)) {
+        (Some(ipc_time), Some(parquet_time)) if ipc_time >= parquet_time => Some(ipc),
+        (Some(_), None) => Some(ipc),
+        (_, Some(_)) => Some(parquet),
+        (None, None) => None,
+    }
+}
+
+// Tables are not guaranteed to pass `validate()`; inverted rows hold no
+// addresses and are skipped
+fn ipv4_address_count(ranges: &[(u32, u32)]) -> u64 {
+    ranges
+        .iter()
+        .filter(|&&(start, end)| start <= end)
+        .map(|&(start, end)| u64::from(end - start) + 1)
+        .sum()
+}
+
+fn ipv6_address_count(ranges: &[(u128, u128)]) -> u128 {
+    ranges
+        .iter()
+        .filter(|&&(start, end)| start <= end)
+        .fold(0u128, |total, &(start, end)| {
+            total.saturating_add((end - start).saturating_add(1))
+        })
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+impl AsnLookup for IpAsnDatabase {
+    fn source(&self) -> LookupSource {
+        LookupSource::Arrow
+    }
+
+    fn lookup(&self, ip: IpAddr) -> rasn_core::Result<Option<LookupAnswer>> {
+        Ok(
+            IpAsnDatabase::lookup(self, ip)
+                .map(|info| LookupAnswer::new(info, LookupSource::Arrow)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::google_v4;
+    use std::net::Ipv6Addr;
+
+    fn test_database() -> IpAsnDatabase {
+        let v4 = google_v4(15169);
+
+        let start: Ipv6Addr = "2606:4700::".parse().unwrap();
+        let end: Ipv6Addr = "2606:4700:ffff:ffff:ffff:ffff:ffff:ffff".parse().unwrap();
+        let v6 = IpRangeTableV6::from_vecs(
+            vec![u128::from(start)],
+            vec![u128::from(end)],
+            vec![13335],
+            vec!["US".to_string()],
+            vec!["CLOUDFLARENET".to_string()],
+        )
+        .unwrap();
+
+        IpAsnDatabase::new(Some(v4), Some(v6))
+    }
+
+    #[test]
+    fn test_open_prefers_fresh_ipc() {
+        use std::time::{Duration, SystemTime};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let ipc = dir.path().join(IPV4_IPC_FILE);
+        test_database().v4().unwrap().write_ipc(&ipc).unwrap();
+
+        let db = IpAsnDatabase::open(dir.path()).unwrap();
+        let info = db.lookup("8.8.8.8".parse().unwrap()).unwrap();
+        assert_eq!(info.asn, Asn(15169));
+        assert_eq!(info.organization, "GOOGLE");
+        assert!(db.v6().is_none());
+
+        // A Parquet file rebuilt after the IPC file wins
+        let parquet = dir.path().join(IPV4_TABLE_FILE);
+        fs::write(&parquet, b"").unwrap();
+        let old = SystemTime::now() - Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(&ipc)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        assert_eq!(
+            table_file(dir.path(), IPV4_TABLE_FILE, IPV4_IPC_FILE),
+            Some(parquet)
+        );
+        assert_eq!(table_file(dir.path(), IPV6_TABLE_FILE, IPV6_IPC_FILE), None);
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let usage = test_database().memory_usage();
+        assert_eq!(usage.rows, 2);
+        assert_eq!(usage.addresses, 2 * 4 + 2 * 16);
+        assert_eq!(usage.distinct_orgs, 2);
+    }
+
+    #[test]
+    fn test_lookup_both_families() {
+        let db = test_database();
+
+        let info = db.lookup("8.8.8.8".parse().unwrap()).unwrap();
+        assert_eq!(info.asn, Asn(15169));
+
+        let info = db.lookup("2606:4700:4700::1111".parse().unwrap()).unwrap();
+        assert_eq!(info.asn, Asn(13335));
+
+        assert!(db.lookup("1.1.1.1".parse().unwrap()).is_none());
+        assert!(db.lookup("2001:db8::1".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_lookup_many() {
+        let db = test_database();
+        let ips: Vec<IpAddr> = ["2606:4700::1", "1.1.1.1", "::ffff:8.8.8.8", "8.8.8.8"]
+            .iter()
+            .map(|ip| ip.parse().unwrap())
+            .collect();
+
+        let results = db.lookup_many(&ips);
+        let asns: Vec<_> = results
+            .iter()
+            .map(|info| info.as_ref().map(|i| i.asn))
+            .collect();
+        assert_eq!(
+            asns,
+            vec![Some(Asn(13335)), None, Some(Asn(15169)), Some(Asn(15169))]
+        );
+        assert!(IpAsnDatabase::new(None, None).lookup_many(&ips)[0].is_none());
+    }
+
+    #[test]
+    fn test_asn_lookup_trait() {
+        let backend: &dyn AsnLookup = &test_database();
+        assert_eq!(backend.source(), LookupSource::Arrow);
+
+        let answer = backend.lookup("8.8.8.8".parse().unwrap()).unwrap().unwrap();
+        assert_eq!(answer.info.asn, Asn(15169));
+        assert_eq!(answer.source, LookupSource::Arrow);
+        assert!(backend
+            .lookup("1.1.1.1".parse().unwrap())
+            .unwrap()
+            .is_none());
+
+        let db = test_database();
+        let v4: &dyn AsnLookup = db.v4().unwrap();
+        assert!(v4
+            .lookup("::ffff:8.8.8.8".parse().unwrap())
+            .unwrap()
+            .is_some());
+        assert!(v4
+            .lookup("2606:4700::1".parse().unwrap())
+            .unwrap()
+            .is_none());
+        let v6: &dyn AsnLookup = db.v6().unwrap();
+        assert!(v6
+            .lookup("2606:4700::1".parse().unwrap())
+            .unwrap()
+            .is_some());
+        assert!(v6.lookup("8.8.8.8".parse().unwrap()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_lookup_ipv4_mapped() {
+        let db = test_database();
+        let info = db.lookup("::ffff:8.8.8.8".parse().unwrap()).unwrap();
+        assert_eq!(info.asn, Asn(15169));
+    }
+
+    #[test]
+    fn test_lookup_joins_metadata() {
+        let metadata = AsnMetadataTable::from_vecs(
+            vec![15169],
+            vec!["GOOGLE".to_string()],
+            vec!["Google LLC".to_string()],
+        )
+        .unwrap();
+        let db = test_database().with_metadata(metadata);
+
+        let info = db.lookup("8.8.8.8".parse().unwrap()).unwrap();
+        assert_eq!(info.description, Some("Google LLC".to_string()));
+        assert_eq!(info.handle, Some("GOOGLE".to_string()));
+
+        // ASNs without metadata keep empty fields
+        let info = db.lookup("2606:4700:4700::1111".parse().unwrap()).unwrap();
+        assert_eq!(info.description, None);
+
+        assert_eq!(db.asn_metadata(Asn(15169)).unwrap().handle, "GOOGLE");
+        assert!(db.asn_metadata(Asn(13335)).is_none());
+    }
+
+    #[test]
+    fn test_asn_prefixes() {
+        let v4 = IpRangeTableV4::from_vecs(
+            vec![0x08080800, 0x08080900, 0x08080A00],
+            vec![0x080808FF, 0x080809FF, 0x08080AFF],
+            vec![15169, 64496, 15169],
+            vec!["US".to_string(), "ZZ".to_string(), "US".to_string()],
+            vec![
+                "GOOGLE".to_string(),
+                "DOC".to_string(),
+                "GOOGLE".to_string(),
+            ],
+        )
+        .unwrap();
+        let v6 = test_database().v6;
+        let metadata = AsnMetadataTable::from_vecs(
+            vec![15169, 65000],
+            vec!["GOOGLE".to_string(), "PRIVATE".to_string()],
+            vec!["Google LLC".to_string(), String::new()],
+        )
+        .unwrap();
+        let db = IpAsnDatabase::new(Some(v4), v6).with_metadata(metadata);
+
+        let google = db.asn_prefixes(Asn(15169)).unwrap();
+        assert_eq!(google.organization, Some("GOOGLE".to_string()));
+        assert_eq!(google.country, Some("US".to_string()));
+        assert_eq!(google.description, Some("Google LLC".to_string()));
+        assert_eq!(
+            google.ipv4_ranges,
+            vec![(0x08080800, 0x080808FF), (0x08080A00, 0x08080AFF)]
+        );
+        assert!(google.ipv6_ranges.is_empty());
+        assert_eq!(google.ipv4_address_count(), 512);
+
+        let cloudflare = db.asn_prefixes(Asn(13335)).unwrap();
+        assert_eq!(cloudflare.ipv6_ranges.len(), 1);
+        assert_eq!(cloudflare.ipv6_address_count(), 1u128 << 96);
+
+        // Metadata-only ASNs are found without ranges
+        let private = db.asn_prefixes(Asn(65000)).unwrap();
+        assert_eq!(private.organization, Some("PRIVATE".to_string()));
+        assert_eq!(private.description, None);
+        assert!(private.ipv4_ranges.is_empty());
+
+        assert!(db.asn_prefixes(Asn(64512)).is_none());
+    }
+
+    #[test]
+    fn test_address_count_skips_inverted_rows() {
+        assert_eq!(ipv4_address_count(&[(10, 5), (0, 255)]), 256);
+        assert_eq!(ipv4_address_count(&[(0, u32::MAX)]), 1 << 32);
+        assert_eq!(ipv6_address_count(&[(10, 5), (0, 15)]), 16);
+        assert_eq!(ipv6_address_count(&[(0, u128::MAX), (0, 1)]), u128::MAX);
+    }
+
+    #[test]
+    fn test_search_org() {
+        let metadata = AsnMetadataTable::from_vecs(
+            vec![15169],
+            vec!["GOOGLE".to_string()],
+            vec!["Google LLC".to_string()],
+        )
+        .unwrap();
+        let db = test_database().with_metadata(metadata);
+
+        let matches = db.search_org(&OrgQuery::new("google llc"));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].asn, Asn(15169));
+        assert_eq!(matches[0].ip_range_count, 1);
+
+        // IPv6-only organizations are found too
+        let matches = db.search_org(&OrgQuery::new("cloudflarenet"));
+        assert_eq!(matches[0].asn, Asn(13335));
+    }
+
+    #[test]
+    fn test_country_summary() {
+        let v4 = IpRangeTableV4::from_vecs(
+            vec![0x08080800, 0x08080900, 0x68100000],
+            vec![0x080808FF, 0x080809FF, 0x6810FFFF],
+            vec![15169, 15169, 13335],
+            vec!["US".to_string(), "US".to_string(), "US".to_string()],
+            vec![
+                "GOOGLE".to_string(),
+                "GOOGLE".to_string(),
+                "CLOUDFLARENET".to_string(),
+            ],
+        )
+        .unwrap();
+        let db = IpAsnDatabase::new(Some(v4), test_database().v6);
+
+        let summary = db.country_summary("us").unwrap();
+        assert_eq!(summary.country, "US");
+        assert!(summary.geo_ipv4_ranges.is_none());
+        assert_eq!(summary.asns.len(), 2);
+        // Ties on range count are broken by ASN
+        assert_eq!(summary.asns[0].asn, Asn(13335));
+        assert_eq!(summary.asns[0].ipv4_ranges, 1);
+        assert_eq!(summary.asns[0].ipv6_ranges, 1);
+        assert_eq!(summary.asns[1].asn, Asn(15169));
+        assert_eq!(summary.asns[1].ipv4_ranges, 2);
+        assert_eq!(summary.ipv4_address_count(), 512 + 65536);
+        assert_eq!(summary.ipv6_address_count(), 1u128 << 96);
+
+        assert!(db.country_summary("NL").is_none());
+    }
+
+    #[test]
+    fn test_country_summary_uses_index() {
+        let index = CountryIndex::from_rows([("NL", 0x05000000, 0x050000FF)]);
+        let db = test_database().with_country_index(index);
+
+        let summary = db.country_summary("nl").unwrap();
+        assert!(summary.ipv4_ranges.is_empty());
+        assert!(summary.asns.is_empty());
+        assert_eq!(
+            summary.geo_ipv4_ranges,
+            Some(vec![(0x05000000, 0x050000FF)])
+        );
+        assert_eq!(summary.geo_ipv4_address_count(), Some(256));
+
+        // Registration ranges still come from the IPv4 table and match the
+        // per-ASN counts
+        let summary = db.country_summary("US").unwrap();
+        let per_asn: usize = summary.asns.iter().map(|a| a.ipv4_ranges).sum();
+        assert_eq!(summary.ipv4_ranges.len(), per_asn);
+        assert_eq!(summary.geo_ipv4_ranges, Some(vec![]));
+    }
+
+    #[test]
+    fn test_missing_family() {
+        let db = IpAsnDatabase::new(None, None);
+        assert!(db.is_empty());
+        assert!(db.lookup("8.8.8.8".parse().unwrap()).is_none());
+        assert!(db.lookup("2001:db8::1".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_open_missing_dir() {
+        let result = IpAsnDatabase::open(Path::new("does/not/exist"));
+        assert!(matches!(result, Err(ArrowError::FileNotFound(_))));
+    }
+}
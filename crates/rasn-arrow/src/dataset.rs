@@ -0,0 +1,154 @@
+//! Dataset build metadata
+//!
+//! The database builder records where each table came from in the Parquet
+//! key-value metadata (and the Arrow IPC schema metadata) under `rasn.*`
+//! keys: build time, source file, URL and SHA-256, row count and schema
+//! version. [`DatasetInfo`] is that record, read back when a table is
+//! loaded, so a lookup can be traced to the dataset build that answered it.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version of the table layout written by the current builder
+pub const SCHEMA_VERSION: u32 = 1;
+
+const KEY_SCHEMA_VERSION: &str = "rasn.schema_version";
+const KEY_BUILD_TIME: &str = "rasn.build_time";
+const KEY_SOURCE_FILE: &str = "rasn.source_file";
+const KEY_SOURCE_URL: &str = "rasn.source_url";
+const KEY_SOURCE_SHA256: &str = "rasn.source_sha256";
+const KEY_ROW_COUNT: &str = "rasn.row_count";
+
+/// Provenance of a table file
+///
+/// Every field is optional: files written by older builders (or by hand)
+/// carry none of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DatasetInfo {
+    /// Table layout version, see [`SCHEMA_VERSION`]
+    pub schema_version: Option<u32>,
+    /// Build time in RFC 3339 format (UTC)
+    pub build_time: Option<String>,
+    /// File name of the reference data the table was built from
+    pub source_file: Option<String>,
+    /// Download URL of the reference data
+    pub source_url: Option<String>,
+    /// Hex-encoded SHA-256 of the reference data file
+    pub source_sha256: Option<String>,
+    /// Number of rows written by the builder
+    pub row_count: Option<usize>,
+}
+
+impl DatasetInfo {
+    /// Read the `rasn.*` entries of a file's key-value metadata
+    ///
+    /// Returns `None` when the file has none of them.
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Option<Self> {
+        let get = |key: &str| metadata.get(key).cloned();
+
+        let info = Self {
+            schema_version: get(KEY_SCHEMA_VERSION).and_then(|v| v.parse().ok()),
+            build_time: get(KEY_BUILD_TIME),
+            source_file: get(KEY_SOURCE_FILE),
+            source_url: get(KEY_SOURCE_URL),
+            source_sha256: get(KEY_SOURCE_SHA256),
+            row_count: get(KEY_ROW_COUNT).and_then(|v| v.parse().ok()),
+        };
+
+        (info != Self::default()).then_some(info)
+    }
+
+    /// Convert to `rasn.*` key-value metadata entries
+    pub fn to_metadata(&self) -> HashMap<String, String> {
+        [
+            (
+                KEY_SCHEMA_VERSION,
+                self.schema_version.map(|v| v.to_string()),
+            ),
+            (KEY_BUILD_TIME, self.build_time.clone()),
+            (KEY_SOURCE_FILE, self.source_file.clone()),
+            (KEY_SOURCE_URL, self.source_url.clone()),
+            (KEY_SOURCE_SHA256, self.source_sha256.clone()),
+            (KEY_ROW_COUNT, self.row_count.map(|v| v.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value?)))
+        .collect()
+    }
+}
+
+/// Get the current time in RFC 3339 format (UTC, whole seconds)
+pub(crate) fn now_rfc3339() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    format_rfc3339(secs)
+}
+
+/// Format seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ`
+pub(crate) fn format_rfc3339(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) civil date
+///
+/// Howard Hinnant's `civil_from_days` algorithm.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let info = DatasetInfo {
+            schema_version: Some(SCHEMA_VERSION),
+            build_time: Some("2026-03-01T12:00:00Z".to_string()),
+            source_file: Some("ip2asn-v4.tsv.gz".to_string()),
+            source_url: Some("https://iptoasn.com/data/ip2asn-v4.tsv.gz".to_string()),
+            source_sha256: Some("ab".repeat(32)),
+            row_count: Some(500_000),
+        };
+
+        let mut metadata = info.to_metadata();
+        assert_eq!(metadata.len(), 6);
+        metadata.insert("ARROW:schema".to_string(), "ignored".to_string());
+        assert_eq!(DatasetInfo::from_metadata(&metadata), Some(info));
+
+        assert_eq!(DatasetInfo::from_metadata(&HashMap::new()), None);
+        assert!(DatasetInfo::default().to_metadata().is_empty());
+    }
+
+    #[test]
+    fn test_format_rfc3339() {
+        assert_eq!(format_rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_rfc3339(1_772_366_399), "2026-03-01T11:59:59Z");
+        assert!(now_rfc3339().ends_with('Z'));
+    }
+}
@@ -0,0 +1,429 @@
+//! Differences between two builds of a range table
+//!
+//! [`TableDiff`] compares an older and a newer table of the same address
+//! family, as returned by
+//! [`IpRangeTableV4::diff`](crate::IpRangeTableV4::diff) and
+//! [`IpRangeTableV6::diff`](crate::IpRangeTableV6::diff).
+//!
+//! Ranges are compared by address, not by row: the tables are swept
+//! together and every stretch of address space whose owner differs is
+//! reported, so a range that was split, merged or resized only shows up
+//! where the ASN actually changed. Adjacent stretches with the same change
+//! are merged into one [`RangeChange`].
+
+use crate::validate::serialize_u128;
+use rasn_core::Asn;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::net::IpAddr;
+
+/// Kind of address space change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The addresses are announced by a different ASN
+    AsnChanged,
+    /// The addresses are only in the newer table
+    Added,
+    /// The addresses are only in the older table
+    Withdrawn,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ChangeKind::AsnChanged => "asn_changed",
+            ChangeKind::Added => "added",
+            ChangeKind::Withdrawn => "withdrawn",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A stretch of address space whose owner changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RangeChange {
+    /// Kind of change
+    pub kind: ChangeKind,
+    /// First address
+    pub start: IpAddr,
+    /// Last address (inclusive)
+    pub end: IpAddr,
+    /// Number of addresses in the range
+    #[serde(serialize_with = "serialize_u128")]
+    pub addresses: u128,
+    /// ASN in the older table, `None` for added ranges
+    pub old_asn: Option<Asn>,
+    /// ASN in the newer table, `None` for withdrawn ranges
+    pub new_asn: Option<Asn>,
+}
+
+/// An ASN whose organization or countries changed
+///
+/// The organization is taken from the ASN's lowest range; the countries are
+/// the distinct, non-empty countries of all its ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AsnChange {
+    /// The ASN
+    pub asn: Asn,
+    /// Organization in the older table
+    pub old_organization: String,
+    /// Organization in the newer table
+    pub new_organization: String,
+    /// Countries in the older table, sorted
+    pub old_countries: Vec<String>,
+    /// Countries in the newer table, sorted
+    pub new_countries: Vec<String>,
+}
+
+/// Aggregate counts of a [`TableDiff`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DiffSummary {
+    /// Rows in the older table
+    pub old_ranges: usize,
+    /// Rows in the newer table
+    pub new_ranges: usize,
+    /// Number of [`ChangeKind::AsnChanged`] ranges
+    pub asn_changed: usize,
+    /// Number of [`ChangeKind::Added`] ranges
+    pub added: usize,
+    /// Number of [`ChangeKind::Withdrawn`] ranges
+    pub withdrawn: usize,
+    /// Addresses that moved to a different ASN
+    #[serde(serialize_with = "serialize_u128")]
+    pub asn_changed_addresses: u128,
+    /// Addresses only in the newer table
+    #[serde(serialize_with = "serialize_u128")]
+    pub added_addresses: u128,
+    /// Addresses only in the older table
+    #[serde(serialize_with = "serialize_u128")]
+    pub withdrawn_addresses: u128,
+    /// ASNs only in the newer table
+    pub new_asns: usize,
+    /// ASNs only in the older table
+    pub removed_asns: usize,
+    /// ASNs in both tables whose organization or countries changed
+    pub changed_asns: usize,
+}
+
+/// Differences between an older and a newer range table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TableDiff {
+    /// Aggregate counts
+    pub summary: DiffSummary,
+    /// Changed address ranges, in address order
+    pub ranges: Vec<RangeChange>,
+    /// ASNs whose organization or countries changed, in ASN order
+    pub asns: Vec<AsnChange>,
+}
+
+impl TableDiff {
+    /// Check if the tables assign every address to the same ASN and no
+    /// ASN changed organization or countries
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty() && self.asns.is_empty()
+    }
+}
+
+/// Compare two tables of the same address family
+///
+/// Ranges are `(start, end, asn)` sorted by start; rows are the
+/// `(asn, organization, country)` of every row. `to_ip` converts an address
+/// of the family to an [`IpAddr`].
+pub(crate) fn diff_tables<'a, 'b>(
+    old_ranges: &[(u128, u128, u32)],
+    new_ranges: &[(u128, u128, u32)],
+    old_rows: impl Iterator<Item = (u32, &'a str, &'a str)>,
+    new_rows: impl Iterator<Item = (u32, &'b str, &'b str)>,
+    to_ip: impl Fn(u128) -> IpAddr,
+) -> TableDiff {
+    let mut summary = DiffSummary {
+        old_ranges: old_ranges.len(),
+        new_ranges: new_ranges.len(),
+        ..Default::default()
+    };
+
+    let ranges: Vec<RangeChange> = diff_ranges(old_ranges, new_ranges)
+        .into_iter()
+        .map(|(start, end, old_asn, new_asn)| {
+            let kind = match (old_asn, new_asn) {
+                (Some(_), Some(_)) => ChangeKind::AsnChanged,
+                (None, _) => ChangeKind::Added,
+                (_, None) => ChangeKind::Withdrawn,
+            };
+            let addresses = (end - start).saturating_add(1);
+            let (count, total) = match kind {
+                ChangeKind::AsnChanged => {
+                    (&mut summary.asn_changed, &mut summary.asn_changed_addresses)
+                }
+                ChangeKind::Added => (&mut summary.added, &mut summary.added_addresses),
+                ChangeKind::Withdrawn => (&mut summary.withdrawn, &mut summary.withdrawn_addresses),
+            };
+            *count += 1;
+            *total = total.saturating_add(addresses);
+
+            RangeChange {
+                kind,
+                start: to_ip(start),
+                end: to_ip(end),
+                addresses,
+                old_asn: old_asn.map(Asn),
+                new_asn: new_asn.map(Asn),
+            }
+        })
+        .collect();
+
+    let old_asns = asn_profiles(old_rows);
+    let new_asns = asn_profiles(new_rows);
+    summary.new_asns = new_asns
+        .keys()
+        .filter(|asn| !old_asns.contains_key(asn))
+        .count();
+    summary.removed_asns = old_asns
+        .keys()
+        .filter(|asn| !new_asns.contains_key(asn))
+        .count();
+
+    let asns: Vec<AsnChange> = old_asns
+        .iter()
+        .filter_map(|(&asn, old)| {
+            let new = new_asns.get(&asn)?;
+            (old != new).then(|| AsnChange {
+                asn: Asn(asn),
+                old_organization: old.0.to_string(),
+                new_organization: new.0.to_string(),
+                old_countries: old.1.iter().map(|c| c.to_string()).collect(),
+                new_countries: new.1.iter().map(|c| c.to_string()).collect(),
+            })
+        })
+        .collect();
+    summary.changed_asns = asns.len();
+
+    TableDiff {
+        summary,
+        ranges,
+        asns,
+    }
+}
+
+/// Sweep two sorted, non-overlapping range lists and return the stretches
+/// whose ASN differs as `(start, end, old_asn, new_asn)`
+fn diff_ranges(
+    old: &[(u128, u128, u32)],
+    new: &[(u128, u128, u32)],
+) -> Vec<(u128, u128, Option<u32>, Option<u32>)> {
+    let mut changes: Vec<(u128, u128, Option<u32>, Option<u32>)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut pos = 0u128;
+
+    loop {
+        while old.get(i).is_some_and(|r| r.1 < pos) {
+            i += 1;
+        }
+        while new.get(j).is_some_and(|r| r.1 < pos) {
+            j += 1;
+        }
+        let (next_old, next_new) = (old.get(i), new.get(j));
+
+        // Ranges covering `pos`
+        let old_range = next_old.filter(|r| r.0 <= pos);
+        let new_range = next_new.filter(|r| r.0 <= pos);
+        if old_range.is_none() && new_range.is_none() {
+            match next_old.into_iter().chain(next_new).map(|r| r.0).min() {
+                Some(start) => {
+                    pos = start;
+                    continue;
+                }
+                None => break,
+            }
+        }
+
+        // The stretch ends where a covering range ends or the other starts
+        let end = [next_old, next_new]
+            .into_iter()
+            .flatten()
+            .map(|r| if r.0 <= pos { r.1 } else { r.0 - 1 })
+            .min()
+            .unwrap_or(pos);
+
+        let (old_asn, new_asn) = (old_range.map(|r| r.2), new_range.map(|r| r.2));
+        if old_asn != new_asn {
+            match changes.last_mut() {
+                Some(last) if last.1 + 1 == pos && (last.2, last.3) == (old_asn, new_asn) => {
+                    last.1 = end;
+                }
+                _ => changes.push((pos, end, old_asn, new_asn)),
+            }
+        }
+
+        match end.checked_add(1) {
+            Some(next) => pos = next,
+            None => break,
+        }
+    }
+
+    changes
+}
+
+/// Organization (of the lowest range) and distinct countries per ASN
+fn asn_profiles<'a>(
+    rows: impl Iterator<Item = (u32, &'a str, &'a str)>,
+) -> BTreeMap<u32, (&'a str, BTreeSet<&'a str>)> {
+    let mut profiles: BTreeMap<u32, (&str, BTreeSet<&str>)> = BTreeMap::new();
+    for (asn, org, country) in rows {
+        let profile = profiles.entry(asn).or_insert((org, BTreeSet::new()));
+        if !country.is_empty() {
+            profile.1.insert(country);
+        }
+    }
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{IpRangeTableV4, IpRangeTableV6};
+    use std::net::Ipv4Addr;
+
+    fn table(rows: &[(u32, u32, u32, &str, &str)]) -> IpRangeTableV4 {
+        IpRangeTableV4::from_vecs(
+            rows.iter().map(|r| r.0).collect(),
+            rows.iter().map(|r| r.1).collect(),
+            rows.iter().map(|r| r.2).collect(),
+            rows.iter().map(|r| r.3.to_string()).collect(),
+            rows.iter().map(|r| r.4.to_string()).collect(),
+        )
+        .unwrap()
+    }
+
+    fn ip(value: u32) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::from(value))
+    }
+
+    #[test]
+    fn test_identical_tables() {
+        let rows = [(100, 199, 1, "US", "A"), (300, 399, 2, "DE", "B")];
+        let diff = table(&rows).diff(&table(&rows));
+        assert!(diff.is_empty());
+        assert_eq!(diff.summary.old_ranges, 2);
+        assert_eq!(diff.summary.asn_changed_addresses, 0);
+    }
+
+    #[test]
+    fn test_range_changes() {
+        let old = table(&[
+            (100, 199, 1, "US", "A"),
+            (200, 299, 2, "US", "B"),
+            (400, 499, 4, "US", "D"),
+        ]);
+        // 100-199 is split and half moves to AS3, 200-299 is withdrawn,
+        // 500-599 is new, 400-499 is unchanged
+        let new = table(&[
+            (100, 149, 1, "US", "A"),
+            (150, 199, 3, "US", "C"),
+            (400, 449, 4, "US", "D"),
+            (450, 499, 4, "US", "D"),
+            (500, 599, 5, "US", "E"),
+        ]);
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.ranges,
+            vec![
+                RangeChange {
+                    kind: ChangeKind::AsnChanged,
+                    start: ip(150),
+                    end: ip(199),
+                    addresses: 50,
+                    old_asn: Some(Asn(1)),
+                    new_asn: Some(Asn(3)),
+                },
+                RangeChange {
+                    kind: ChangeKind::Withdrawn,
+                    start: ip(200),
+                    end: ip(299),
+                    addresses: 100,
+                    old_asn: Some(Asn(2)),
+                    new_asn: None,
+                },
+                RangeChange {
+                    kind: ChangeKind::Added,
+                    start: ip(500),
+                    end: ip(599),
+                    addresses: 100,
+                    old_asn: None,
+                    new_asn: Some(Asn(5)),
+                },
+            ]
+        );
+
+        let summary = &diff.summary;
+        assert_eq!(
+            (summary.asn_changed, summary.added, summary.withdrawn),
+            (1, 1, 1)
+        );
+        assert_eq!(summary.withdrawn_addresses, 100);
+        assert_eq!((summary.new_asns, summary.removed_asns), (2, 1));
+    }
+
+    #[test]
+    fn test_adjacent_changes_merged() {
+        let old = table(&[(0, 99, 1, "US", "A"), (100, 199, 1, "US", "A")]);
+        let new = table(&[(0, 49, 2, "US", "B"), (50, 199, 2, "US", "B")]);
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.ranges.len(), 1);
+        assert_eq!((diff.ranges[0].start, diff.ranges[0].end), (ip(0), ip(199)));
+        assert_eq!(diff.summary.asn_changed_addresses, 200);
+
+        // The reverse direction sees the same stretch
+        let diff = new.diff(&old);
+        assert_eq!(diff.ranges.len(), 1);
+        assert_eq!(diff.ranges[0].old_asn, Some(Asn(2)));
+    }
+
+    #[test]
+    fn test_asn_changes() {
+        let old = table(&[(100, 199, 1, "US", "Old Name"), (200, 299, 2, "US", "B")]);
+        let new = table(&[
+            (100, 199, 1, "US", "New Name"),
+            (200, 299, 2, "US", "B"),
+            (300, 399, 2, "CA", "B"),
+        ]);
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.summary.changed_asns, 2);
+        assert_eq!(diff.asns[0].asn, Asn(1));
+        assert_eq!(diff.asns[0].old_organization, "Old Name");
+        assert_eq!(diff.asns[0].new_organization, "New Name");
+        assert_eq!(diff.asns[1].old_countries, vec!["US"]);
+        assert_eq!(diff.asns[1].new_countries, vec!["CA", "US"]);
+    }
+
+    #[test]
+    fn test_ipv6_full_space() {
+        let old = IpRangeTableV6::from_vecs(
+            vec![0],
+            vec![u128::MAX],
+            vec![1],
+            vec!["US".to_string()],
+            vec!["A".to_string()],
+        )
+        .unwrap();
+        let new = IpRangeTableV6::from_vecs(
+            vec![0, 1 << 127],
+            vec![(1 << 127) - 1, u128::MAX],
+            vec![1, 2],
+            vec!["US".to_string(), "US".to_string()],
+            vec!["A".to_string(), "B".to_string()],
+        )
+        .unwrap();
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.ranges.len(), 1);
+        assert_eq!(diff.ranges[0].start, "8000::".parse::<IpAddr>().unwrap());
+        assert_eq!(diff.ranges[0].addresses, 1 << 127);
+        assert!(new.diff(&new).is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+//! Borrowed range table rows
+//!
+//! [`RangeEntry`] points into the dictionary-encoded string columns of a
+//! range table, so hot lookup paths can read the organization and country
+//! without allocating. Convert to an owned [`AsnInfo`] only when the result
+//! has to outlive the table.
+
+use rasn_core::{Asn, AsnInfo};
+
+/// A borrowed view of one range table row
+///
+/// `A` is the address type: `u32` for IPv4 and `u128` for IPv6 tables.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasn_arrow::IpRangeTableV4;
+/// use std::path::Path;
+///
+/// let table = IpRangeTableV4::from_parquet(Path::new("data/arrow/ip2asn-v4.parquet"))?;
+/// if let Some(entry) = table.find_entry(0x08080808) {  // 8.8.8.8
+///     println!("{} {} ({})", entry.asn, entry.organization, entry.country);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeEntry<'a, A> {
+    /// First address of the range
+    pub start: A,
+    /// Last address of the range (inclusive)
+    pub end: A,
+    /// The ASN announcing the range
+    pub asn: Asn,
+    /// Organization name
+    pub organization: &'a str,
+    /// Country code, empty when unknown
+    pub country: &'a str,
+}
+
+impl<A> RangeEntry<'_, A> {
+    /// Convert to owned ASN information
+    pub fn to_asn_info(&self) -> AsnInfo {
+        AsnInfo {
+            asn: self.asn,
+            organization: self.organization.to_string(),
+            country: Some(self.country.to_string()),
+            description: None,
+            handle: None,
+        }
+    }
+}
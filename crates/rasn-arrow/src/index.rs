@@ -0,0 +1,78 @@
+//! ASN reverse index
+//!
+//! Maps each ASN to the row ids of the ranges it announces, so that
+//! "which prefixes does ASxxx own" is answered without scanning the table.
+
+use rasn_core::Asn;
+use std::collections::HashMap;
+
+/// ASN → range row ids index
+///
+/// Row ids are stored in ascending order, which is also ascending IP order
+/// for a sorted range table.
+#[derive(Debug, Default)]
+pub struct AsnIndex {
+    rows: HashMap<u32, Vec<u32>>,
+}
+
+impl AsnIndex {
+    /// Build the index from the ASN column of a range table
+    pub fn build(asns: impl IntoIterator<Item = u32>) -> Self {
+        let mut rows: HashMap<u32, Vec<u32>> = HashMap::new();
+
+        for (row, asn) in asns.into_iter().enumerate() {
+            rows.entry(asn).or_default().push(row as u32);
+        }
+
+        Self { rows }
+    }
+
+    /// Get the row ids of all ranges announced by an ASN
+    pub fn rows(&self, asn: Asn) -> &[u32] {
+        self.rows.get(&asn.0).map_or(&[], Vec::as_slice)
+    }
+
+    /// Check if an ASN announces any range
+    pub fn contains(&self, asn: Asn) -> bool {
+        self.rows.contains_key(&asn.0)
+    }
+
+    /// Iterate over all indexed ASNs (in no particular order)
+    pub fn asns(&self) -> impl Iterator<Item = Asn> + '_ {
+        self.rows.keys().map(|&asn| Asn(asn))
+    }
+
+    /// Get the number of distinct ASNs
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Check if the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_and_query() {
+        let index = AsnIndex::build([15169, 13335, 15169, 0, 15169]);
+
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.rows(Asn(15169)), &[0, 2, 4]);
+        assert_eq!(index.rows(Asn(13335)), &[1]);
+        assert!(index.rows(Asn(64512)).is_empty());
+        assert!(index.contains(Asn(0)));
+        assert!(!index.contains(Asn(64512)));
+    }
+
+    #[test]
+    fn test_empty() {
+        let index = AsnIndex::build(std::iter::empty());
+        assert!(index.is_empty());
+        assert_eq!(index.asns().count(), 0);
+    }
+}
@@ -0,0 +1,480 @@
+//! IPv6 range table
+//!
+//! Mirrors [`IpRangeTableV4`](crate::IpRangeTableV4) for 128-bit addresses.
+//! Start and end addresses are stored as `u128` (host order), converted from
+//! the `FixedSizeBinary(16)` big-endian columns written by the database builder.
+//! When memory-mapping an Arrow IPC file, only these two columns are decoded;
+//! the ASN and string columns are used in place.
+
+use crate::column::StringColumn;
+use crate::coverage::{self, Coverage};
+use crate::diff::{self, TableDiff};
+use crate::loader::{self, LoadedTable};
+use crate::merge;
+use crate::search::OrgCandidates;
+use crate::validate::{self, ValidationReport};
+use crate::{
+    dict_field, is_ipc_file, DatasetInfo, MemoryUsage, OrgMatch, OrgQuery, RangeEntry, Result,
+};
+use arrow::array::{FixedSizeBinaryArray, UInt32Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use rasn_core::{Asn, AsnInfo, AsnLookup, LookupAnswer, LookupSource};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+/// IPv6 range table for IP→ASN lookups
+///
+/// Stores sorted, non-overlapping IPv6 ranges with binary search capability.
+pub struct IpRangeTableV6 {
+    start_ips: Vec<u128>,
+    end_ips: Vec<u128>,
+    asns: Arc<UInt32Array>,
+    countries: StringColumn,
+    orgs: StringColumn,
+    len: usize,
+    dataset: Option<DatasetInfo>,
+}
+
+impl IpRangeTableV6 {
+    /// Create IPv6 range table from vectors
+    pub fn from_vecs(
+        start_ips: Vec<u128>,
+        end_ips: Vec<u128>,
+        asns: Vec<u32>,
+        countries: Vec<String>,
+        orgs: Vec<String>,
+    ) -> Result<Self> {
+        let len = start_ips.len();
+
+        Ok(Self {
+            start_ips,
+            end_ips,
+            asns: Arc::new(UInt32Array::from(asns)),
+            countries: StringColumn::from_strings(countries),
+            orgs: StringColumn::from_strings(orgs),
+            len,
+            dataset: None,
+        })
+    }
+
+    /// Load IPv6 range table from Parquet file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rasn_arrow::IpRangeTableV6;
+    /// use std::path::Path;
+    ///
+    /// let table = IpRangeTableV6::from_parquet(
+    ///     Path::new("data/arrow/ip2asn-v6.parquet")
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_parquet(path: &Path) -> Result<Self> {
+        Self::from_loaded(&LoadedTable::read_parquet(path)?)
+    }
+
+    /// Memory-map IPv6 range table from Arrow IPC file
+    ///
+    /// See [`IpRangeTableV4::from_ipc`](crate::IpRangeTableV4::from_ipc).
+    pub fn from_ipc(path: &Path) -> Result<Self> {
+        Self::from_loaded(&LoadedTable::read_ipc(path)?)
+    }
+
+    /// Load IPv6 range table from an Arrow IPC (`.arrow`) or Parquet file
+    pub fn open(path: &Path) -> Result<Self> {
+        if is_ipc_file(path) {
+            Self::from_ipc(path)
+        } else {
+            Self::from_parquet(path)
+        }
+    }
+
+    /// Build the table from a loaded file, resolving columns by name
+    pub(crate) fn from_loaded(table: &LoadedTable) -> Result<Self> {
+        let start_ips = table.ipv6_column("start_ip")?;
+        let end_ips = table.ipv6_column("end_ip")?;
+        let asns = table.u32_column("asn")?;
+        let countries = table.dict_column("country")?;
+        let orgs = table.dict_column("org")?;
+
+        let len = table.num_rows();
+
+        Ok(Self {
+            start_ips,
+            end_ips,
+            asns: Arc::new(asns),
+            countries,
+            orgs,
+            len,
+            dataset: table.dataset_info(),
+        })
+    }
+
+    /// Write the table as an Arrow IPC file for [`IpRangeTableV6::from_ipc`]
+    ///
+    /// The table's [`DatasetInfo`] is kept in the schema metadata.
+    pub fn write_ipc(&self, path: &Path) -> Result<()> {
+        let metadata = self
+            .dataset
+            .as_ref()
+            .map(DatasetInfo::to_metadata)
+            .unwrap_or_default();
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new_with_metadata(
+                vec![
+                    Field::new("start_ip", DataType::FixedSizeBinary(16), false),
+                    Field::new("end_ip", DataType::FixedSizeBinary(16), false),
+                    Field::new("asn", DataType::UInt32, false),
+                    dict_field("country", &self.countries, 0),
+                    dict_field("org", &self.orgs, 1),
+                ],
+                metadata,
+            )),
+            vec![
+                Arc::new(FixedSizeBinaryArray::try_from_iter(
+                    self.start_ips.iter().map(|ip| ip.to_be_bytes()),
+                )?),
+                Arc::new(FixedSizeBinaryArray::try_from_iter(
+                    self.end_ips.iter().map(|ip| ip.to_be_bytes()),
+                )?),
+                self.asns.clone(),
+                self.countries.to_array(),
+                self.orgs.to_array(),
+            ],
+        )?;
+
+        loader::write_ipc(path, &batch)
+    }
+
+    /// Find ASN information for an IPv6 address
+    ///
+    /// Time complexity: O(log n)
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - IPv6 address as u128 (e.g. `u128::from(Ipv6Addr)`)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rasn_arrow::IpRangeTableV6;
+    /// # use std::net::Ipv6Addr;
+    /// # use std::path::Path;
+    /// # let table = IpRangeTableV6::from_parquet(Path::new("data/arrow/ip2asn-v6.parquet"))?;
+    /// let ip: Ipv6Addr = "2001:4860:4860::8888".parse()?;
+    /// if let Some(info) = table.find_ip(u128::from(ip)) {
+    ///     assert_eq!(info.asn.0, 15169);  // Google
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn find_ip(&self, ip: u128) -> Option<AsnInfo> {
+        let idx = self.binary_search(ip)?;
+        self.info_at(idx)
+    }
+
+    /// Find ASN information for a batch of IPv6 addresses
+    ///
+    /// Returns one result per address, in input order, matching the sorted
+    /// batch against the ranges in a single forward pass.
+    pub fn find_many(&self, ips: &[u128]) -> Vec<Option<AsnInfo>> {
+        merge::find_rows(&self.start_ips, &self.end_ips, ips)
+            .into_iter()
+            .map(|row| row.and_then(|row| self.info_at(row)))
+            .collect()
+    }
+
+    /// Binary search for IP in sorted ranges
+    fn binary_search(&self, ip: u128) -> Option<usize> {
+        let mut left = 0;
+        let mut right = self.len;
+
+        while left < right {
+            let mid = left + (right - left) / 2;
+
+            if ip < self.start_ips[mid] {
+                right = mid;
+            } else if ip > self.end_ips[mid] {
+                left = mid + 1;
+            } else {
+                return Some(mid); // Found!
+            }
+        }
+
+        None
+    }
+
+    /// Get the `(start, end)` addresses of the range at a row
+    pub fn range_at(&self, row: usize) -> Option<(u128, u128)> {
+        if row >= self.len {
+            return None;
+        }
+        Some((self.start_ips[row], self.end_ips[row]))
+    }
+
+    /// Get the ASN column, one value per row
+    pub(crate) fn asn_values(&self) -> &[u32] {
+        self.asns.values()
+    }
+
+    /// Iterate over `(asn, organization, country)` of every row
+    pub(crate) fn org_rows(&self) -> impl Iterator<Item = (u32, &str, &str)> {
+        self.asn_values()
+            .iter()
+            .zip(self.orgs.iter())
+            .zip(self.countries.iter())
+            .map(|((&asn, org), country)| (asn, org, country))
+    }
+
+    /// Search ASNs by organization name
+    ///
+    /// See [`OrgQuery`] for the matching rules.
+    pub fn search_org(&self, query: &OrgQuery) -> Vec<OrgMatch> {
+        let mut candidates = OrgCandidates::default();
+        for (asn, org, country) in self.org_rows() {
+            candidates.add_range(asn, org, country);
+        }
+        candidates.search(query)
+    }
+
+    /// Get the ASN information stored at a row
+    pub fn info_at(&self, row: usize) -> Option<AsnInfo> {
+        self.entry_at(row).map(|entry| entry.to_asn_info())
+    }
+
+    /// Get a borrowed view of a row
+    pub fn entry_at(&self, row: usize) -> Option<RangeEntry<'_, u128>> {
+        let (start, end) = self.range_at(row)?;
+        Some(RangeEntry {
+            start,
+            end,
+            asn: Asn(*self.asns.values().get(row)?),
+            organization: self.orgs.get(row)?,
+            country: self.countries.get(row)?,
+        })
+    }
+
+    /// Find the row containing an IPv6 address, without allocating
+    pub fn find_entry(&self, ip: u128) -> Option<RangeEntry<'_, u128>> {
+        self.entry_at(self.binary_search(ip)?)
+    }
+
+    /// Get the memory used by each column
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            rows: self.len,
+            addresses: std::mem::size_of_val(self.start_ips.as_slice())
+                + std::mem::size_of_val(self.end_ips.as_slice()),
+            asns: self.asns.len() * 4,
+            countries: self.countries.memory_size(),
+            orgs: self.orgs.memory_size(),
+            distinct_countries: self.countries.distinct(),
+            distinct_orgs: self.orgs.distinct(),
+        }
+    }
+
+    /// Check the table for integrity violations
+    ///
+    /// See [`crate::IpRangeTableV4::validate`].
+    pub fn validate(&self) -> ValidationReport {
+        validate::validate_ranges(
+            &self.start_ips,
+            &self.end_ips,
+            self.len,
+            &[
+                ("asn", self.asns.len()),
+                ("country", self.countries.len()),
+                ("org", self.orgs.len()),
+            ],
+            |ip| std::net::Ipv6Addr::from(ip).to_string(),
+        )
+    }
+
+    /// Compare with a newer build of the table
+    ///
+    /// See [`TableDiff`] for what is reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `newer` - Newer build of the IPv6 table
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rasn_arrow::IpRangeTableV6;
+    /// use std::path::Path;
+    ///
+    /// let old = IpRangeTableV6::open(Path::new("old/ip2asn-v6.parquet"))?;
+    /// let new = IpRangeTableV6::open(Path::new("new/ip2asn-v6.parquet"))?;
+    /// let diff = old.diff(&new);
+    /// println!("{} ranges changed ASN", diff.summary.asn_changed);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn diff(&self, newer: &IpRangeTableV6) -> TableDiff {
+        diff::diff_tables(
+            &self.diff_ranges(),
+            &newer.diff_ranges(),
+            self.org_rows(),
+            newer.org_rows(),
+            |ip| IpAddr::V6(std::net::Ipv6Addr::from(ip)),
+        )
+    }
+
+    /// Break the block `start..=end` down by the ranges overlapping it
+    ///
+    /// Only the overlapping rows are visited, see [`Coverage`].
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - First address of the block as u128
+    /// * `end` - Last address of the block as u128
+    pub fn coverage(&self, start: u128, end: u128) -> Coverage {
+        let first = self.end_ips.partition_point(|&e| e < start);
+        let entries = (first..self.len)
+            .map_while(|row| self.entry_at(row))
+            .take_while(|entry| entry.start <= end);
+
+        coverage::block_coverage(start, end, entries, |ip| {
+            IpAddr::V6(std::net::Ipv6Addr::from(ip))
+        })
+    }
+
+    /// Get `(start, end, asn)` of every row for [`IpRangeTableV6::diff`]
+    fn diff_ranges(&self) -> Vec<(u128, u128, u32)> {
+        (0..self.len)
+            .filter_map(|row| self.range_at(row))
+            .zip(self.asn_values())
+            .map(|((start, end), &asn)| (start, end, asn))
+            .collect()
+    }
+
+    /// Get the number of IP ranges in the table
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the table is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the build metadata of the file the table was loaded from
+    ///
+    /// `None` for tables built in memory or loaded from files written
+    /// before the builder recorded metadata.
+    pub fn dataset_info(&self) -> Option<&DatasetInfo> {
+        self.dataset.as_ref()
+    }
+
+    /// Attach build metadata, written along with [`IpRangeTableV6::write_ipc`]
+    pub fn with_dataset_info(mut self, info: DatasetInfo) -> Self {
+        self.dataset = Some(info);
+        self
+    }
+}
+
+/// IPv4 addresses are never found
+impl AsnLookup for IpRangeTableV6 {
+    fn source(&self) -> LookupSource {
+        LookupSource::Arrow
+    }
+
+    fn lookup(&self, ip: IpAddr) -> rasn_core::Result<Option<LookupAnswer>> {
+        let info = match ip {
+            IpAddr::V4(_) => None,
+            IpAddr::V6(ipv6) => self.find_ip(u128::from(ipv6)),
+        };
+        Ok(info.map(|info| LookupAnswer::new(info, LookupSource::Arrow)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv6Addr;
+
+    fn ip(s: &str) -> u128 {
+        u128::from(s.parse::<Ipv6Addr>().unwrap())
+    }
+
+    fn test_table() -> IpRangeTableV6 {
+        IpRangeTableV6::from_vecs(
+            vec![ip("2001:db8::"), ip("2001:4860::"), ip("2606:4700::")],
+            vec![
+                ip("2001:db8:ffff:ffff:ffff:ffff:ffff:ffff"),
+                ip("2001:4860:ffff:ffff:ffff:ffff:ffff:ffff"),
+                ip("2606:4700:ffff:ffff:ffff:ffff:ffff:ffff"),
+            ],
+            vec![64496, 15169, 13335],
+            vec!["ZZ".to_string(), "US".to_string(), "US".to_string()],
+            vec![
+                "DOC".to_string(),
+                "GOOGLE".to_string(),
+                "CLOUDFLARENET".to_string(),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_binary_search_basic() {
+        let table = test_table();
+
+        // Test hits (boundaries included)
+        assert_eq!(table.binary_search(ip("2001:db8::")), Some(0));
+        assert_eq!(table.binary_search(ip("2001:db8::1")), Some(0));
+        assert_eq!(table.binary_search(ip("2001:4860:4860::8888")), Some(1));
+        assert_eq!(
+            table.binary_search(ip("2606:4700:ffff:ffff:ffff:ffff:ffff:ffff")),
+            Some(2)
+        );
+
+        // Test misses
+        assert_eq!(table.binary_search(ip("::1")), None);
+        assert_eq!(table.binary_search(ip("2002::")), None);
+        assert_eq!(table.binary_search(ip("ffff::")), None);
+    }
+
+    #[test]
+    fn test_find_ip() {
+        let table = test_table();
+
+        let info = table.find_ip(ip("2606:4700:4700::1111")).unwrap();
+        assert_eq!(info.asn, Asn(13335));
+        assert_eq!(info.organization, "CLOUDFLARENET");
+        assert_eq!(info.country, Some("US".to_string()));
+
+        assert!(table.find_ip(ip("fe80::1")).is_none());
+    }
+
+    #[test]
+    fn test_find_many() {
+        let table = test_table();
+        let ips = [
+            ip("2606:4700:4700::1111"),
+            ip("fe80::1"),
+            ip("2001:db8::1"),
+            ip("2606:4700:4700::1111"),
+        ];
+
+        let results = table.find_many(&ips);
+        assert_eq!(results.len(), 4);
+        for (&ip, info) in ips.iter().zip(&results) {
+            assert_eq!(info, &table.find_ip(ip));
+        }
+        assert!(results[1].is_none());
+    }
+
+    #[test]
+    fn test_table_properties() {
+        let table = test_table();
+        assert_eq!(table.len(), 3);
+        assert!(!table.is_empty());
+
+        let empty = IpRangeTableV6::from_vecs(vec![], vec![], vec![], vec![], vec![]).unwrap();
+        assert!(empty.is_empty());
+        assert!(empty.find_ip(ip("2001:db8::1")).is_none());
+    }
+}
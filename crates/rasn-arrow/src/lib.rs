@@ -5,10 +5,23 @@
 //!
 //! # Features
 //!
-//! - Memory-mapped Parquet file loading
-//! - Binary search over sorted IP ranges
+//! - Parquet file loading and memory-mapped Arrow IPC tables (`.arrow`)
+//! - IPv4 ([`IpRangeTableV4`]) and IPv6 ([`IpRangeTableV6`]) range tables
+//! - Dual-stack lookups through [`IpAsnDatabase`]
+//! - ASN metadata index ([`AsnMetadataTable`]) joined into lookup results
+//! - ASN → prefixes reverse index ([`AsnIndex`])
+//! - Organization name search ([`OrgQuery`])
+//! - Country summaries backed by a country index ([`CountryIndex`])
+//! - Native Parquet database builder ([`builder::DatabaseBuilder`])
+//! - Range table integrity checks ([`ValidationReport`])
+//! - Differences between two builds of a table ([`TableDiff`])
+//! - Address block breakdown by ASN, country and gaps ([`Coverage`])
+//! - Cache-friendly B+ tree search over sorted IP ranges (AVX2 when available)
+//! - Longest-prefix-match trie over overlapping prefixes ([`PrefixTrieV4`])
+//! - Point-in-time lookups over dated snapshots ([`SnapshotStore`])
 //! - Sub-microsecond lookup performance
-//! - Zero-copy data access
+//! - Zero-copy data access with dictionary-encoded string columns
+//!   ([`RangeEntry`] borrowed lookups, [`MemoryUsage`] reports)
 //!
 //! # Examples
 //!
@@ -23,15 +36,79 @@
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 
-use arrow::array::{Array, AsArray, UInt32Array};
-use arrow::datatypes::UInt32Type;
-use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
-use rasn_core::{Asn, AsnInfo};
-use std::fs::File;
+use arrow::array::UInt32Array;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use rasn_core::{Asn, AsnInfo, AsnLookup, LookupAnswer, LookupSource};
+use std::net::IpAddr;
 use std::path::Path;
 use std::sync::Arc;
 use thiserror::Error;
 
+pub mod builder;
+mod column;
+pub mod country;
+pub mod coverage;
+pub mod database;
+pub mod dataset;
+pub mod diff;
+pub mod entry;
+pub mod index;
+pub mod ipv6;
+mod loader;
+pub mod lookup;
+pub mod memory;
+mod merge;
+pub mod metadata;
+pub mod reload;
+pub mod search;
+pub mod snapshot;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+mod tree;
+pub mod trie;
+pub mod validate;
+
+pub use country::CountryIndex;
+pub use coverage::{
+    AsnCoverage, CidrAnalysis, CountryCoverage, Coverage, CoverageGap, CoverageSummary,
+};
+pub use database::{AsnPrefixes, CountryAsn, CountrySummary, IpAsnDatabase};
+pub use dataset::DatasetInfo;
+pub use diff::{AsnChange, ChangeKind, DiffSummary, RangeChange, TableDiff};
+pub use entry::RangeEntry;
+pub use index::AsnIndex;
+pub use ipv6::IpRangeTableV6;
+pub use lookup::Ipv4Lookup;
+pub use memory::MemoryUsage;
+pub use metadata::{AsnMetadata, AsnMetadataTable};
+pub use reload::{DatabaseHandle, ReloadReport};
+pub use search::{MatchKind, OrgMatch, OrgQuery};
+pub use snapshot::{SnapshotAnswer, SnapshotDate, SnapshotStore};
+pub use trie::PrefixTrieV4;
+pub use validate::{IssueKind, ValidationIssue, ValidationReport};
+
+use column::StringColumn;
+use loader::LoadedTable;
+use search::OrgCandidates;
+use tree::SearchTree;
+
+/// Check if a path names an Arrow IPC file (`.arrow`)
+pub(crate) fn is_ipc_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "arrow")
+}
+
+/// Dictionary field for a string column, with a unique IPC dictionary id
+pub(crate) fn dict_field(name: &str, column: &StringColumn, dict_id: i64) -> Field {
+    Field::new_dict(
+        name,
+        column.to_array().data_type().clone(),
+        false,
+        dict_id,
+        false,
+    )
+}
+
 /// Errors that can occur when working with Arrow tables
 #[derive(Error, Debug)]
 pub enum ArrowError {
@@ -39,6 +116,10 @@ pub enum ArrowError {
     #[error("Failed to load Parquet file: {0}")]
     ParquetLoad(String),
 
+    /// Failed to load Arrow IPC file
+    #[error("Failed to load Arrow IPC file: {0}")]
+    IpcLoad(String),
+
     /// Invalid schema
     #[error("Invalid Arrow schema: {0}")]
     InvalidSchema(String),
@@ -47,6 +128,10 @@ pub enum ArrowError {
     #[error("File not found: {0}")]
     FileNotFound(String),
 
+    /// Invalid input data (e.g. overlapping ranges)
+    #[error("Invalid data: {0}")]
+    InvalidData(String),
+
     /// I/O error
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -64,15 +149,19 @@ pub type Result<T> = std::result::Result<T, ArrowError>;
 
 /// IPv4 range table for IP→ASN lookups
 ///
-/// Stores IP ranges in columnar format with binary search capability.
+/// Stores IP ranges in columnar format, with a static B+ tree over the
+/// start addresses for lookups. Ranges must be sorted and non-overlapping
+/// (see [`IpRangeTableV4::validate`]).
 /// Optimized for sub-microsecond lookup performance.
 pub struct IpRangeTableV4 {
     start_ips: Arc<UInt32Array>,
     end_ips: Arc<UInt32Array>,
     asns: Arc<UInt32Array>,
-    countries: Vec<String>,
-    orgs: Vec<String>,
+    countries: StringColumn,
+    orgs: StringColumn,
+    tree: SearchTree,
     len: usize,
+    dataset: Option<DatasetInfo>,
 }
 
 impl IpRangeTableV4 {
@@ -89,12 +178,14 @@ impl IpRangeTableV4 {
         use arrow::array::UInt32Array;
 
         Ok(Self {
+            tree: SearchTree::new(&start_ips),
             start_ips: Arc::new(UInt32Array::from(start_ips)),
             end_ips: Arc::new(UInt32Array::from(end_ips)),
             asns: Arc::new(UInt32Array::from(asns)),
-            countries,
-            orgs,
+            countries: StringColumn::from_strings(countries),
+            orgs: StringColumn::from_strings(orgs),
             len,
+            dataset: None,
         })
     }
 
@@ -112,45 +203,95 @@ impl IpRangeTableV4 {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn from_parquet(path: &Path) -> Result<Self> {
-        if !path.exists() {
-            return Err(ArrowError::FileNotFound(path.display().to_string()));
-        }
-
-        let file = File::open(path)?;
-        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
-        let mut reader = builder.build()?;
-
-        // Read all batches
-        let batch = reader
-            .next()
-            .ok_or_else(|| ArrowError::ParquetLoad("No record batches found".to_string()))??;
-
-        // Extract columns
-        let start_ips = batch.column(0).as_primitive::<UInt32Type>().clone();
+        Self::from_loaded(&LoadedTable::read_parquet(path)?)
+    }
 
-        let end_ips = batch.column(1).as_primitive::<UInt32Type>().clone();
+    /// Memory-map IPv4 range table from Arrow IPC file
+    ///
+    /// The table queries the mapped file directly: address and ASN columns
+    /// are not copied and string columns keep their dictionary encoding.
+    /// Write the file with [`IpRangeTableV4::write_ipc`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rasn_arrow::IpRangeTableV4;
+    /// use std::path::Path;
+    ///
+    /// let table = IpRangeTableV4::from_ipc(Path::new("data/arrow/ip2asn-v4.arrow"))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_ipc(path: &Path) -> Result<Self> {
+        Self::from_loaded(&LoadedTable::read_ipc(path)?)
+    }
 
-        let asns = batch.column(2).as_primitive::<UInt32Type>().clone();
+    /// Load IPv4 range table from an Arrow IPC (`.arrow`) or Parquet file
+    pub fn open(path: &Path) -> Result<Self> {
+        if is_ipc_file(path) {
+            Self::from_ipc(path)
+        } else {
+            Self::from_parquet(path)
+        }
+    }
 
-        // Extract string columns (countries and orgs)
-        let countries = extract_string_column(batch.column(3))?;
-        let orgs = extract_string_column(batch.column(4))?;
+    /// Build the table from a loaded file, resolving columns by name
+    pub(crate) fn from_loaded(table: &LoadedTable) -> Result<Self> {
+        let start_ips = table.u32_column("start_ip")?;
+        let end_ips = table.u32_column("end_ip")?;
+        let asns = table.u32_column("asn")?;
+        let countries = table.dict_column("country")?;
+        let orgs = table.dict_column("org")?;
 
-        let len = start_ips.len();
+        let len = table.num_rows();
 
         Ok(Self {
+            tree: SearchTree::new(start_ips.values()),
             start_ips: Arc::new(start_ips),
             end_ips: Arc::new(end_ips),
             asns: Arc::new(asns),
             countries,
             orgs,
             len,
+            dataset: table.dataset_info(),
         })
     }
 
+    /// Write the table as an Arrow IPC file for [`IpRangeTableV4::from_ipc`]
+    ///
+    /// The table's [`DatasetInfo`] is kept in the schema metadata.
+    pub fn write_ipc(&self, path: &Path) -> Result<()> {
+        let metadata = self
+            .dataset
+            .as_ref()
+            .map(DatasetInfo::to_metadata)
+            .unwrap_or_default();
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new_with_metadata(
+                vec![
+                    Field::new("start_ip", DataType::UInt32, false),
+                    Field::new("end_ip", DataType::UInt32, false),
+                    Field::new("asn", DataType::UInt32, false),
+                    dict_field("country", &self.countries, 0),
+                    dict_field("org", &self.orgs, 1),
+                ],
+                metadata,
+            )),
+            vec![
+                self.start_ips.clone(),
+                self.end_ips.clone(),
+                self.asns.clone(),
+                self.countries.to_array(),
+                self.orgs.to_array(),
+            ],
+        )?;
+
+        loader::write_ipc(path, &batch)
+    }
+
     /// Find ASN information for an IPv4 address
     ///
-    /// Automatically uses SIMD (AVX2) if available, otherwise falls back to scalar binary search.
+    /// Searches the B+ tree over the range start addresses, comparing 16
+    /// keys at a time with AVX2 when available.
     /// Time complexity: O(log n)
     ///
     /// # Arguments
@@ -170,100 +311,53 @@ impl IpRangeTableV4 {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn find_ip(&self, ip: u32) -> Option<AsnInfo> {
-        #[cfg(target_arch = "x86_64")]
-        {
-            if is_x86_feature_detected!("avx2") {
-                // SAFETY: We've checked AVX2 is available
-                return unsafe { self.find_ip_simd(ip) };
-            }
-        }
+        let idx = self.tree_search(ip)?;
+        self.info_at(idx)
+    }
 
-        self.find_ip_scalar(ip)
+    /// Find ASN information for a batch of IPv4 addresses
+    ///
+    /// Returns one result per address, in input order. The batch is sorted
+    /// and matched against the ranges in a single forward pass, which is
+    /// much cheaper than calling [`IpRangeTableV4::find_ip`] per address for
+    /// large batches.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rasn_arrow::IpRangeTableV4;
+    /// # use std::path::Path;
+    /// # let table = IpRangeTableV4::from_parquet(Path::new("data/arrow/ip2asn-v4.parquet"))?;
+    /// // 8.8.8.8 and 1.1.1.1
+    /// let results = table.find_many(&[0x08080808, 0x01010101]);
+    /// assert_eq!(results.len(), 2);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn find_many(&self, ips: &[u32]) -> Vec<Option<AsnInfo>> {
+        merge::find_rows(self.start_ips.values(), self.end_ips.values(), ips)
+            .into_iter()
+            .map(|row| row.and_then(|row| self.info_at(row)))
+            .collect()
     }
 
-    /// Find IP using scalar binary search (fallback)
+    /// Find IP using scalar binary search (reference implementation)
     #[inline]
     pub fn find_ip_scalar(&self, ip: u32) -> Option<AsnInfo> {
         let idx = self.binary_search(ip)?;
-
-        Some(AsnInfo {
-            asn: Asn(self.asns.value(idx)),
-            organization: self.orgs.get(idx)?.clone(),
-            country: Some(self.countries.get(idx)?.clone()),
-            description: None,
-        })
+        self.info_at(idx)
     }
 
-    /// Find IP using SIMD AVX2 (x86_64 only)
-    #[cfg(target_arch = "x86_64")]
-    #[target_feature(enable = "avx2")]
-    unsafe fn find_ip_simd(&self, ip: u32) -> Option<AsnInfo> {
-        #[cfg(target_arch = "x86_64")]
-        use std::arch::x86_64::*;
-
-        let search_ip = _mm256_set1_epi32(ip as i32);
-
-        // Process 8 ranges at a time
-        let chunks = self.len / 8;
-        for i in 0..chunks {
-            let base = i * 8;
-
-            // Load 8 start IPs and 8 end IPs
-            let starts =
-                _mm256_loadu_si256(self.start_ips.values().as_ptr().add(base) as *const __m256i);
-            let ends =
-                _mm256_loadu_si256(self.end_ips.values().as_ptr().add(base) as *const __m256i);
-
-            // Compare: ip >= start
-            let ge_start = _mm256_cmpgt_epi32(
-                _mm256_or_si256(search_ip, _mm256_cmpeq_epi32(search_ip, starts)),
-                starts,
-            );
-
-            // Compare: ip <= end
-            let le_end = _mm256_cmpgt_epi32(
-                _mm256_or_si256(ends, _mm256_cmpeq_epi32(search_ip, ends)),
-                search_ip,
-            );
-
-            // Both conditions must be true
-            let in_range = _mm256_and_si256(ge_start, le_end);
-
-            // Check if any lane matched
-            let mask = _mm256_movemask_epi8(in_range);
-            if mask != 0 {
-                // Find which lane matched (each i32 is 4 bytes)
-                let lane = (mask.trailing_zeros() / 4) as usize;
-                let idx = base + lane;
-
-                return Some(AsnInfo {
-                    asn: Asn(self.asns.value(idx)),
-                    organization: self.orgs.get(idx)?.clone(),
-                    country: Some(self.countries.get(idx)?.clone()),
-                    description: None,
-                });
-            }
-        }
-
-        // Handle remainder with scalar search
-        self.binary_search_range(ip, chunks * 8, self.len)
-            .map(|idx| AsnInfo {
-                asn: Asn(self.asns.value(idx)),
-                organization: self.orgs.get(idx).unwrap().clone(),
-                country: Some(self.countries.get(idx).unwrap().clone()),
-                description: None,
-            })
+    /// Find the row containing an IP with the B+ tree
+    #[inline]
+    fn tree_search(&self, ip: u32) -> Option<usize> {
+        let idx = self.tree.find(self.start_ips.values(), ip)?;
+        (ip <= self.end_ips.value(idx)).then_some(idx)
     }
 
     /// Binary search for IP in sorted ranges
     fn binary_search(&self, ip: u32) -> Option<usize> {
-        self.binary_search_range(ip, 0, self.len)
-    }
-
-    /// Binary search within a specific range
-    fn binary_search_range(&self, ip: u32, start: usize, end: usize) -> Option<usize> {
-        let mut left = start;
-        let mut right = end;
+        let mut left = 0;
+        let mut right = self.len;
 
         while left < right {
             let mid = left + (right - left) / 2;
@@ -282,6 +376,189 @@ impl IpRangeTableV4 {
         None
     }
 
+    /// Get the `(start, end)` addresses of the range at a row
+    pub fn range_at(&self, row: usize) -> Option<(u32, u32)> {
+        if row >= self.len {
+            return None;
+        }
+        Some((self.start_ips.value(row), self.end_ips.value(row)))
+    }
+
+    /// Get the ASN column, one value per row
+    pub(crate) fn asn_values(&self) -> &[u32] {
+        self.asns.values()
+    }
+
+    /// Iterate over `(asn, organization, country)` of every row
+    pub(crate) fn org_rows(&self) -> impl Iterator<Item = (u32, &str, &str)> {
+        self.asn_values()
+            .iter()
+            .zip(self.orgs.iter())
+            .zip(self.countries.iter())
+            .map(|((&asn, org), country)| (asn, org, country))
+    }
+
+    /// Search ASNs by organization name
+    ///
+    /// See [`OrgQuery`] for the matching rules.
+    pub fn search_org(&self, query: &OrgQuery) -> Vec<OrgMatch> {
+        let mut candidates = OrgCandidates::default();
+        for (asn, org, country) in self.org_rows() {
+            candidates.add_range(asn, org, country);
+        }
+        candidates.search(query)
+    }
+
+    /// Get the ASN information stored at a row
+    pub fn info_at(&self, row: usize) -> Option<AsnInfo> {
+        self.entry_at(row).map(|entry| entry.to_asn_info())
+    }
+
+    /// Get a borrowed view of a row
+    pub fn entry_at(&self, row: usize) -> Option<RangeEntry<'_, u32>> {
+        let (start, end) = self.range_at(row)?;
+        Some(RangeEntry {
+            start,
+            end,
+            asn: Asn(*self.asns.values().get(row)?),
+            organization: self.orgs.get(row)?,
+            country: self.countries.get(row)?,
+        })
+    }
+
+    /// Find the row containing an IPv4 address, without allocating
+    ///
+    /// Uses the static B+ tree over the start addresses.
+    pub fn find_entry(&self, ip: u32) -> Option<RangeEntry<'_, u32>> {
+        self.entry_at(self.tree_search(ip)?)
+    }
+
+    /// Get the memory used by each column
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            rows: self.len,
+            addresses: (self.start_ips.len() + self.end_ips.len()) * 4,
+            asns: self.asns.len() * 4,
+            countries: self.countries.memory_size(),
+            orgs: self.orgs.memory_size(),
+            distinct_countries: self.countries.distinct(),
+            distinct_orgs: self.orgs.distinct(),
+        }
+    }
+
+    /// Check the table for integrity violations
+    ///
+    /// Lookups assume ranges sorted by start address, non-overlapping and
+    /// with `start <= end`; this reports every row breaking those rules,
+    /// column length mismatches and the gaps between ranges.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rasn_arrow::IpRangeTableV4;
+    /// use std::path::Path;
+    ///
+    /// let table = IpRangeTableV4::from_parquet(Path::new("data/arrow/ip2asn-v4.parquet"))?;
+    /// let report = table.validate();
+    /// if !report.is_valid() {
+    ///     eprintln!("{} integrity violations", report.issue_count());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn validate(&self) -> ValidationReport {
+        validate::validate_ranges(
+            self.start_ips.values(),
+            self.end_ips.values(),
+            self.len,
+            &[
+                ("asn", self.asns.len()),
+                ("country", self.countries.len()),
+                ("org", self.orgs.len()),
+            ],
+            |ip| std::net::Ipv4Addr::from(ip).to_string(),
+        )
+    }
+
+    /// Compare with a newer build of the table
+    ///
+    /// See [`TableDiff`] for what is reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `newer` - Newer build of the IPv4 table
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rasn_arrow::IpRangeTableV4;
+    /// use std::path::Path;
+    ///
+    /// let old = IpRangeTableV4::open(Path::new("old/ip2asn-v4.parquet"))?;
+    /// let new = IpRangeTableV4::open(Path::new("new/ip2asn-v4.parquet"))?;
+    /// let diff = old.diff(&new);
+    /// println!("{} ranges changed ASN", diff.summary.asn_changed);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn diff(&self, newer: &IpRangeTableV4) -> TableDiff {
+        diff::diff_tables(
+            &self.diff_ranges(),
+            &newer.diff_ranges(),
+            self.org_rows(),
+            newer.org_rows(),
+            |ip| IpAddr::V4(std::net::Ipv4Addr::from(ip as u32)),
+        )
+    }
+
+    /// Break the block `start..=end` down by the ranges overlapping it
+    ///
+    /// Only the overlapping rows are visited, see [`Coverage`].
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - First address of the block as u32
+    /// * `end` - Last address of the block as u32
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rasn_arrow::IpRangeTableV4;
+    /// use std::path::Path;
+    ///
+    /// let table = IpRangeTableV4::open(Path::new("data/arrow/ip2asn-v4.parquet"))?;
+    /// // 8.0.0.0/8
+    /// let coverage = table.coverage(0x08000000, 0x08FFFFFF);
+    /// for asn in coverage.asns.iter().take(5) {
+    ///     println!("{} {:.2}%", asn.asn, asn.percent);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn coverage(&self, start: u32, end: u32) -> Coverage {
+        let first = self.end_ips.values().partition_point(|&e| e < start);
+        let entries = (first..self.len)
+            .map_while(|row| self.entry_at(row))
+            .take_while(|entry| entry.start <= end)
+            .map(|entry| RangeEntry {
+                start: entry.start.into(),
+                end: entry.end.into(),
+                asn: entry.asn,
+                organization: entry.organization,
+                country: entry.country,
+            });
+
+        coverage::block_coverage(start.into(), end.into(), entries, |ip| {
+            IpAddr::V4(std::net::Ipv4Addr::from(ip as u32))
+        })
+    }
+
+    /// Get `(start, end, asn)` of every row for [`IpRangeTableV4::diff`]
+    fn diff_ranges(&self) -> Vec<(u128, u128, u32)> {
+        (0..self.len)
+            .filter_map(|row| self.range_at(row))
+            .zip(self.asn_values())
+            .map(|((start, end), &asn)| (start.into(), end.into(), asn))
+            .collect()
+    }
+
     /// Get the number of IP ranges in the table
     pub fn len(&self) -> usize {
         self.len
@@ -291,33 +568,45 @@ impl IpRangeTableV4 {
     pub fn is_empty(&self) -> bool {
         self.len == 0
     }
+
+    /// Get the build metadata of the file the table was loaded from
+    ///
+    /// `None` for tables built in memory or loaded from files written
+    /// before the builder recorded metadata.
+    pub fn dataset_info(&self) -> Option<&DatasetInfo> {
+        self.dataset.as_ref()
+    }
+
+    /// Attach build metadata, written along with [`IpRangeTableV4::write_ipc`]
+    pub fn with_dataset_info(mut self, info: DatasetInfo) -> Self {
+        self.dataset = Some(info);
+        self
+    }
 }
 
-/// Helper function to extract string data from Arrow column
-fn extract_string_column(column: &Arc<dyn Array>) -> Result<Vec<String>> {
-    let dict_array = column
-        .as_any()
-        .downcast_ref::<arrow::array::DictionaryArray<arrow::datatypes::UInt8Type>>()
-        .ok_or_else(|| ArrowError::InvalidSchema("Expected dictionary column".to_string()))?;
-
-    let values = dict_array
-        .values()
-        .as_any()
-        .downcast_ref::<arrow::array::StringArray>()
-        .ok_or_else(|| ArrowError::InvalidSchema("Expected string values".to_string()))?;
-
-    let result: Vec<String> = (0..dict_array.len())
-        .map(|i| {
-            if dict_array.is_null(i) {
-                String::new()
-            } else {
-                let key = dict_array.keys().value(i) as usize;
-                values.value(key).to_string()
-            }
-        })
-        .collect();
+impl Ipv4Lookup for IpRangeTableV4 {
+    fn find_ip(&self, ip: u32) -> Option<AsnInfo> {
+        IpRangeTableV4::find_ip(self, ip)
+    }
 
-    Ok(result)
+    fn find_many(&self, ips: &[u32]) -> Vec<Option<AsnInfo>> {
+        IpRangeTableV4::find_many(self, ips)
+    }
+}
+
+/// IPv6 addresses other than IPv4-mapped ones are never found
+impl AsnLookup for IpRangeTableV4 {
+    fn source(&self) -> LookupSource {
+        LookupSource::Arrow
+    }
+
+    fn lookup(&self, ip: IpAddr) -> rasn_core::Result<Option<LookupAnswer>> {
+        let info = match ip.to_canonical() {
+            IpAddr::V4(ipv4) => self.find_ip(u32::from(ipv4)),
+            IpAddr::V6(_) => None,
+        };
+        Ok(info.map(|info| LookupAnswer::new(info, LookupSource::Arrow)))
+    }
 }
 
 #[cfg(test)]
@@ -335,9 +624,11 @@ mod tests {
             start_ips: Arc::new(start_ips),
             end_ips: Arc::new(end_ips),
             asns: Arc::new(asns),
-            countries: vec!["US".to_string(), "GB".to_string(), "DE".to_string()],
-            orgs: vec!["Org1".to_string(), "Org2".to_string(), "Org3".to_string()],
+            countries: StringColumn::from_strings(["US", "GB", "DE"]),
+            orgs: StringColumn::from_strings(["Org1", "Org2", "Org3"]),
+            tree: SearchTree::new(&[100, 200, 300]),
             len: 3,
+            dataset: None,
         };
 
         // Test hits
@@ -359,12 +650,250 @@ mod tests {
             start_ips: Arc::new(UInt32Array::from(vec![100])),
             end_ips: Arc::new(UInt32Array::from(vec![200])),
             asns: Arc::new(UInt32Array::from(vec![15169])),
-            countries: vec!["US".to_string()],
-            orgs: vec!["Google".to_string()],
+            countries: StringColumn::from_strings(["US"]),
+            orgs: StringColumn::from_strings(["Google"]),
+            tree: SearchTree::new(&[100]),
             len: 1,
+            dataset: None,
         };
 
         assert_eq!(table.len(), 1);
         assert!(!table.is_empty());
+        assert!(table.dataset_info().is_none());
+    }
+
+    #[test]
+    fn test_entries_and_memory_usage() {
+        let table = IpRangeTableV4::from_vecs(
+            vec![100, 200, 300],
+            vec![150, 250, 350],
+            vec![15169, 15169, 13335],
+            vec!["US".to_string(); 3],
+            vec![
+                "GOOGLE".to_string(),
+                "GOOGLE".to_string(),
+                "CLOUDFLARENET".to_string(),
+            ],
+        )
+        .unwrap();
+
+        let entry = table.find_entry(225).unwrap();
+        assert_eq!((entry.start, entry.end), (200, 250));
+        assert_eq!(entry.asn, Asn(15169));
+        assert_eq!(entry.organization, "GOOGLE");
+        assert_eq!(entry.to_asn_info(), table.find_ip_scalar(225).unwrap());
+        assert!(table.find_entry(175).is_none());
+        assert!(table.entry_at(3).is_none());
+
+        let usage = table.memory_usage();
+        assert_eq!(usage.rows, 3);
+        assert_eq!(usage.addresses, 24);
+        assert_eq!(usage.asns, 12);
+        assert_eq!(usage.distinct_countries, 1);
+        assert_eq!(usage.distinct_orgs, 2);
+        // 3 u32 keys, "GOOGLECLOUDFLARENET" and 3 i32 offsets
+        assert_eq!(usage.orgs, 12 + 19 + 12);
+        assert_eq!(usage.total(), 24 + 12 + usage.countries + usage.orgs);
+    }
+
+    /// Sorted, non-overlapping ranges from (gap, length) pairs, stopping
+    /// before the address space overflows
+    fn ranges_from_gaps(first: u32, gaps: &[(u32, u32)]) -> IpRangeTableV4 {
+        let (mut starts, mut ends) = (Vec::new(), Vec::new());
+        let mut next = Some(first);
+        for &(gap, length) in gaps {
+            let Some(start) = next.and_then(|n| n.checked_add(gap)) else {
+                break;
+            };
+            let Some(end) = start.checked_add(length) else {
+                break;
+            };
+            starts.push(start);
+            ends.push(end);
+            next = end.checked_add(1);
+        }
+
+        let rows = starts.len();
+        IpRangeTableV4::from_vecs(
+            starts,
+            ends,
+            (0..rows as u32).collect(),
+            vec!["US".to_string(); rows],
+            vec!["Org".to_string(); rows],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_find_ip_upper_half() {
+        let table = ranges_from_gaps(0x7fff_ff00, &[(0, 0xff), (0, 0xff), (0xff, 0x7fff_fe00)]);
+        assert_eq!(table.len(), 3);
+
+        assert_eq!(table.find_ip(0x7fff_ffff).unwrap().asn, Asn(0));
+        assert_eq!(table.find_ip(0x8000_0000).unwrap().asn, Asn(1));
+        assert_eq!(table.find_ip(0x8000_0100), None);
+        assert_eq!(table.find_ip(u32::MAX).unwrap().asn, Asn(2));
+        assert_eq!(table.find_entry(0xc000_0201).unwrap().start, 0x8000_01ff);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_find_ip_matches_scalar(
+            first in proptest::prelude::any::<u32>(),
+            gaps in proptest::collection::vec((0u32..1 << 24, 0u32..1 << 24), 0..3000),
+            ips in proptest::collection::vec(proptest::prelude::any::<u32>(), 256),
+        ) {
+            let table = ranges_from_gaps(first, &gaps);
+            let edges = (0..table.len()).flat_map(|row| {
+                let (start, end) = table.range_at(row).unwrap();
+                [start.wrapping_sub(1), start, end, end.wrapping_add(1)]
+            });
+
+            let ips: Vec<u32> = edges.chain(ips).chain([0, 0x8000_0000, u32::MAX]).collect();
+            for &ip in &ips {
+                proptest::prop_assert_eq!(table.find_ip(ip), table.find_ip_scalar(ip));
+            }
+
+            let batch = table.find_many(&ips);
+            for (&ip, info) in ips.iter().zip(batch) {
+                proptest::prop_assert_eq!(info, table.find_ip_scalar(ip));
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let table = IpRangeTableV4::from_vecs(
+            vec![100, 200, 240, 300],
+            vec![150, 250, 260, 299],
+            vec![1, 2, 3, 4],
+            vec!["US".to_string(); 4],
+            vec!["Org".to_string(); 3],
+        )
+        .unwrap();
+
+        let report = table.validate();
+        assert!(!report.is_valid());
+        assert_eq!(report.overlaps, 1);
+        assert_eq!(report.inverted, 1);
+        assert_eq!(report.column_mismatches, 1);
+        // The inverted last row covers nothing, so no gap runs up to it
+        assert_eq!(report.gaps, 1);
+        assert_eq!(report.largest_gap, Some((151, 199)));
+        assert!(report.issues[1].message.contains("0.0.0.240-0.0.1.4"));
+    }
+
+    /// Write a Parquet file with small row groups so it spans many batches
+    fn write_parquet(path: &Path, columns: Vec<(&str, arrow::array::ArrayRef)>) {
+        use arrow::record_batch::RecordBatch;
+        use parquet::arrow::ArrowWriter;
+        use parquet::file::properties::WriterProperties;
+
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(500)
+            .build();
+        let file = std::fs::File::create(path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_from_parquet_all_batches_by_name() {
+        use arrow::array::StringArray;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("v4.parquet");
+        let rows = 3000u32;
+
+        // Columns in a different order than the builder writes them, with
+        // plain Utf8 country values
+        write_parquet(
+            &path,
+            vec![
+                (
+                    "org",
+                    Arc::new(StringArray::from_iter_values(
+                        (0..rows).map(|i| format!("ORG-{}", i)),
+                    )),
+                ),
+                (
+                    "asn",
+                    Arc::new(UInt32Array::from_iter_values((0..rows).map(|i| 64512 + i))),
+                ),
+                (
+                    "end_ip",
+                    Arc::new(UInt32Array::from_iter_values(
+                        (0..rows).map(|i| i * 256 + 255),
+                    )),
+                ),
+                (
+                    "country",
+                    Arc::new(StringArray::from_iter_values((0..rows).map(|_| "NL"))),
+                ),
+                (
+                    "start_ip",
+                    Arc::new(UInt32Array::from_iter_values((0..rows).map(|i| i * 256))),
+                ),
+            ],
+        );
+
+        let table = IpRangeTableV4::from_parquet(&path).unwrap();
+        assert_eq!(table.len(), rows as usize);
+
+        let info = table.find_ip_scalar((rows - 1) * 256 + 1).unwrap();
+        assert_eq!(info.asn, Asn(64512 + rows - 1));
+        assert_eq!(info.organization, format!("ORG-{}", rows - 1));
+        assert_eq!(info.country, Some("NL".to_string()));
+    }
+
+    #[test]
+    fn test_coverage() {
+        let table = IpRangeTableV4::from_vecs(
+            vec![100, 200, 300],
+            vec![150, 250, 350],
+            vec![1, 2, 1],
+            vec!["US".to_string(), "GB".to_string(), "US".to_string()],
+            vec!["Org1".to_string(), "Org2".to_string(), "Org1".to_string()],
+        )
+        .unwrap();
+
+        // 128-255 overlaps the first two rows only
+        let coverage = table.coverage(128, 255);
+        assert_eq!(coverage.ranges, 2);
+        assert_eq!(coverage.covered_addresses, 23 + 51);
+        assert_eq!(coverage.asns.len(), 2);
+        assert_eq!(coverage.asns[0].asn, Asn(2));
+        assert_eq!(coverage.gaps.len(), 2);
+        assert_eq!(coverage.start, IpAddr::from([0, 0, 0, 128]));
+
+        assert_eq!(table.coverage(0, u32::MAX).ranges, 3);
+        assert_eq!(table.coverage(400, 500).ranges, 0);
+    }
+
+    #[test]
+    fn test_from_parquet_schema_mismatch() {
+        use arrow::array::{Int64Array, StringArray};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("bad.parquet");
+        write_parquet(
+            &path,
+            vec![
+                ("start_ip", Arc::new(UInt32Array::from(vec![0]))),
+                ("end_ip", Arc::new(UInt32Array::from(vec![255]))),
+                ("asn", Arc::new(Int64Array::from(vec![1]))),
+                ("country", Arc::new(StringArray::from(vec!["US"]))),
+                ("org", Arc::new(StringArray::from(vec!["ORG"]))),
+            ],
+        );
+
+        match IpRangeTableV4::from_parquet(&path) {
+            Err(ArrowError::InvalidSchema(msg)) => {
+                assert!(msg.contains("column 'asn' has type Int64, expected UInt32"));
+            }
+            other => panic!("expected InvalidSchema, got {:?}", other.err()),
+        }
     }
 }
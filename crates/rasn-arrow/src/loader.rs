@@ -0,0 +1,394 @@
+//! Shared table loading
+//!
+//! Reads every row group / record batch of a Parquet file into a single
+//! batch and resolves columns by name, so that larger files and reordered
+//! schemas load correctly. Type mismatches are reported as
+//! [`ArrowError::InvalidSchema`] naming the file, column and types.
+//!
+//! Arrow IPC files are memory-mapped instead: the arrays of the loaded batch
+//! point straight into the mapping, so opening a table costs a few page
+//! faults rather than a full decode, and processes mapping the same file
+//! share the page cache.
+
+use crate::column::StringColumn;
+use crate::{ArrowError, DatasetInfo, Result};
+use arrow::array::{Array, ArrayRef, AsArray, FixedSizeBinaryArray, UInt32Array};
+use arrow::buffer::Buffer;
+use arrow::compute::{cast, concat_batches};
+use arrow::datatypes::DataType;
+use arrow::ipc::convert::fb_to_schema;
+use arrow::ipc::reader::{read_footer_length, FileDecoder};
+use arrow::ipc::root_as_footer;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use memmap2::Mmap;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::fs::{self, File};
+use std::path::Path;
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+/// Size of the Arrow IPC file trailer (footer length + `ARROW1` magic)
+const IPC_TRAILER_LEN: usize = 10;
+
+/// A fully loaded Parquet or Arrow IPC file
+pub(crate) struct LoadedTable {
+    path: String,
+    batch: RecordBatch,
+}
+
+impl LoadedTable {
+    /// Read all record batches of a Parquet file
+    pub(crate) fn read_parquet(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(ArrowError::FileNotFound(path.display().to_string()));
+        }
+
+        let file = File::open(path)?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+        let schema = builder.schema().clone();
+        let reader = builder.build()?;
+
+        // Read all batches
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>()?;
+        if batches.is_empty() {
+            return Err(ArrowError::ParquetLoad(format!(
+                "No record batches found in {}",
+                path.display()
+            )));
+        }
+        let batch = concat_batches(&schema, &batches)?;
+
+        Ok(Self::from_batch(path, batch))
+    }
+
+    /// Memory-map an Arrow IPC file
+    ///
+    /// Files with a single record batch (as written by [`write_ipc`]) are
+    /// not copied; multiple batches are concatenated into owned memory.
+    pub(crate) fn read_ipc(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(ArrowError::FileNotFound(path.display().to_string()));
+        }
+
+        let invalid = |reason: &str| ArrowError::IpcLoad(format!("{}: {}", path.display(), reason));
+
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only; table files are replaced by
+        // renaming (see `write_ipc`), never modified in place
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() < IPC_TRAILER_LEN {
+            return Err(invalid("file too short"));
+        }
+
+        let ptr = NonNull::new(mmap.as_ptr() as *mut u8).ok_or_else(|| invalid("empty mapping"))?;
+        let len = mmap.len();
+        // SAFETY: `ptr` and `len` describe the mapping, which the buffer keeps
+        // alive through the `Arc` owner
+        let buffer = unsafe { Buffer::from_custom_allocation(ptr, len, Arc::new(mmap)) };
+
+        let trailer_start = len - IPC_TRAILER_LEN;
+        let trailer: [u8; IPC_TRAILER_LEN] = buffer[trailer_start..]
+            .try_into()
+            .map_err(|_| invalid("truncated trailer"))?;
+        let footer_len = read_footer_length(trailer)?;
+        let footer_start = trailer_start
+            .checked_sub(footer_len)
+            .ok_or_else(|| invalid("footer out of bounds"))?;
+        let footer = root_as_footer(&buffer[footer_start..trailer_start])
+            .map_err(|e| invalid(&e.to_string()))?;
+
+        let schema = Arc::new(fb_to_schema(
+            footer.schema().ok_or_else(|| invalid("missing schema"))?,
+        ));
+        let mut decoder = FileDecoder::new(schema.clone(), footer.version());
+
+        let block_data = |offset: i64, meta_len: i32, body_len: i64| {
+            let start = usize::try_from(offset).ok()?;
+            let block_len = usize::try_from(meta_len).ok()? + usize::try_from(body_len).ok()?;
+            (start + block_len <= footer_start).then(|| buffer.slice_with_length(start, block_len))
+        };
+
+        for block in footer.dictionaries().iter().flatten() {
+            let data = block_data(block.offset(), block.metaDataLength(), block.bodyLength())
+                .ok_or_else(|| invalid("dictionary block out of bounds"))?;
+            decoder.read_dictionary(block, &data)?;
+        }
+
+        let mut batches = Vec::new();
+        for block in footer.recordBatches().iter().flatten() {
+            let data = block_data(block.offset(), block.metaDataLength(), block.bodyLength())
+                .ok_or_else(|| invalid("record batch block out of bounds"))?;
+            if let Some(batch) = decoder.read_record_batch(block, &data)? {
+                batches.push(batch);
+            }
+        }
+
+        let batch = match batches.len() {
+            0 => RecordBatch::new_empty(schema),
+            1 => batches.remove(0),
+            _ => concat_batches(&schema, &batches)?,
+        };
+
+        Ok(Self::from_batch(path, batch))
+    }
+
+    /// Wrap an already loaded record batch
+    pub(crate) fn from_batch(path: &Path, batch: RecordBatch) -> Self {
+        Self {
+            path: path.display().to_string(),
+            batch,
+        }
+    }
+
+    /// Get the number of rows
+    pub(crate) fn num_rows(&self) -> usize {
+        self.batch.num_rows()
+    }
+
+    /// Get the build metadata stored in the file's schema, if any
+    pub(crate) fn dataset_info(&self) -> Option<DatasetInfo> {
+        DatasetInfo::from_metadata(self.batch.schema_ref().metadata())
+    }
+
+    /// Get a column by name
+    pub(crate) fn column(&self, name: &str) -> Result<&ArrayRef> {
+        self.batch.column_by_name(name).ok_or_else(|| {
+            let found: Vec<&str> = self
+                .batch
+                .schema_ref()
+                .fields()
+                .iter()
+                .map(|f| f.name().as_str())
+                .collect();
+            ArrowError::InvalidSchema(format!(
+                "{}: missing column '{}' (found: {})",
+                self.path,
+                name,
+                found.join(", ")
+            ))
+        })
+    }
+
+    /// Get a `UInt32` column by name
+    pub(crate) fn u32_column(&self, name: &str) -> Result<UInt32Array> {
+        let column = self.column(name)?;
+        column
+            .as_primitive_opt()
+            .cloned()
+            .ok_or_else(|| self.type_mismatch(name, column.data_type(), "UInt32"))
+    }
+
+    /// Get a string column by name as owned strings
+    ///
+    /// Accepts `Utf8`, `LargeUtf8` and dictionary-encoded string columns with
+    /// any integer key type. Null values become empty strings.
+    pub(crate) fn string_column(&self, name: &str) -> Result<Vec<String>> {
+        let column = self.column(name)?;
+
+        let is_string = |t: &DataType| matches!(t, DataType::Utf8 | DataType::LargeUtf8);
+        let supported = match column.data_type() {
+            DataType::Dictionary(key, value) => key.is_integer() && is_string(value),
+            other => is_string(other),
+        };
+        if !supported {
+            return Err(self.type_mismatch(
+                name,
+                column.data_type(),
+                "Utf8, LargeUtf8 or Dictionary(_, Utf8)",
+            ));
+        }
+
+        let strings = cast(column, &DataType::Utf8)?;
+        let strings = strings.as_string::<i32>();
+
+        Ok((0..strings.len())
+            .map(|i| {
+                if strings.is_null(i) {
+                    String::new()
+                } else {
+                    strings.value(i).to_string()
+                }
+            })
+            .collect())
+    }
+
+    /// Get a string column by name, keeping dictionary encoding
+    ///
+    /// Accepts the same types as [`LoadedTable::string_column`].
+    pub(crate) fn dict_column(&self, name: &str) -> Result<StringColumn> {
+        let column = self.column(name)?;
+        StringColumn::from_array(column).ok_or_else(|| {
+            self.type_mismatch(
+                name,
+                column.data_type(),
+                "Utf8, LargeUtf8 or Dictionary(_, Utf8)",
+            )
+        })
+    }
+
+    /// Get a `FixedSizeBinary(16)` IPv6 column by name as u128 addresses
+    pub(crate) fn ipv6_column(&self, name: &str) -> Result<Vec<u128>> {
+        let column = self.column(name)?;
+        let binary = column
+            .as_any()
+            .downcast_ref::<FixedSizeBinaryArray>()
+            .filter(|array| array.value_length() == 16)
+            .ok_or_else(|| self.type_mismatch(name, column.data_type(), "FixedSizeBinary(16)"))?;
+
+        Ok((0..binary.len())
+            .map(|i| {
+                let bytes: [u8; 16] = binary.value(i).try_into().unwrap_or([0; 16]);
+                u128::from_be_bytes(bytes)
+            })
+            .collect())
+    }
+
+    fn type_mismatch(&self, name: &str, found: &DataType, expected: &str) -> ArrowError {
+        ArrowError::InvalidSchema(format!(
+            "{}: column '{}' has type {}, expected {}",
+            self.path, name, found, expected
+        ))
+    }
+}
+
+/// Write a record batch as an uncompressed Arrow IPC file
+///
+/// The file is written next to `path` and renamed into place, so processes
+/// that still map the previous file keep reading consistent data.
+pub(crate) fn write_ipc(path: &Path, batch: &RecordBatch) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp_path = path.with_extension("arrow.tmp");
+    let file = File::create(&tmp_path)?;
+    let mut writer = FileWriter::try_new(file, &batch.schema())?;
+    writer.write(batch)?;
+    writer.finish()?;
+    drop(writer);
+
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{DictionaryArray, Int64Array, LargeStringArray, StringArray};
+    use arrow::datatypes::{Int32Type, UInt8Type};
+    use std::sync::Arc;
+
+    fn table(columns: Vec<(&str, ArrayRef)>) -> LoadedTable {
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        LoadedTable::from_batch(Path::new("test.parquet"), batch)
+    }
+
+    #[test]
+    fn test_string_column_encodings() {
+        let dict: DictionaryArray<UInt8Type> = vec!["US", "NL", "US"].into_iter().collect();
+        let dict32: DictionaryArray<Int32Type> = vec!["a", "b", "a"].into_iter().collect();
+        let table = table(vec![
+            ("dict", Arc::new(dict) as ArrayRef),
+            ("dict32", Arc::new(dict32)),
+            (
+                "plain",
+                Arc::new(StringArray::from(vec![Some("x"), None, Some("z")])),
+            ),
+            (
+                "large",
+                Arc::new(LargeStringArray::from(vec!["l1", "l2", "l3"])),
+            ),
+        ]);
+
+        assert_eq!(table.string_column("dict").unwrap(), vec!["US", "NL", "US"]);
+        assert_eq!(table.string_column("dict32").unwrap(), vec!["a", "b", "a"]);
+        assert_eq!(table.string_column("plain").unwrap(), vec!["x", "", "z"]);
+        assert_eq!(
+            table.string_column("large").unwrap(),
+            vec!["l1", "l2", "l3"]
+        );
+    }
+
+    #[test]
+    fn test_schema_errors() {
+        let table = table(vec![
+            ("asn", Arc::new(Int64Array::from(vec![1i64])) as ArrayRef),
+            ("org", Arc::new(StringArray::from(vec!["A"]))),
+        ]);
+
+        let err = table.u32_column("asn").unwrap_err().to_string();
+        assert!(
+            err.contains("column 'asn' has type Int64, expected UInt32"),
+            "{err}"
+        );
+
+        let err = table.u32_column("start_ip").unwrap_err().to_string();
+        assert!(
+            err.contains("missing column 'start_ip' (found: asn, org)"),
+            "{err}"
+        );
+
+        let err = table.string_column("asn").unwrap_err();
+        assert!(matches!(err, ArrowError::InvalidSchema(_)));
+
+        let err = table.ipv6_column("org").unwrap_err().to_string();
+        assert!(err.contains("expected FixedSizeBinary(16)"), "{err}");
+    }
+
+    #[test]
+    fn test_ipc_roundtrip() {
+        use arrow::datatypes::{Field, Schema};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("table.arrow");
+
+        let country: DictionaryArray<UInt8Type> = vec!["US", "NL", "US"].into_iter().collect();
+        let org: DictionaryArray<Int32Type> = vec!["A", "B", "B"].into_iter().collect();
+        let schema = Schema::new(vec![
+            Field::new("start_ip", DataType::UInt32, false),
+            Field::new_dict("country", country.data_type().clone(), false, 0, false),
+            Field::new_dict("org", org.data_type().clone(), false, 1, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(UInt32Array::from(vec![1, 2, 3])),
+                Arc::new(country),
+                Arc::new(org),
+            ],
+        )
+        .unwrap();
+
+        write_ipc(&path, &batch).unwrap();
+        let table = LoadedTable::read_ipc(&path).unwrap();
+
+        assert_eq!(table.num_rows(), 3);
+        assert_eq!(table.u32_column("start_ip").unwrap().values(), &[1, 2, 3]);
+        let country = table.dict_column("country").unwrap();
+        assert_eq!(country.iter().collect::<Vec<_>>(), vec!["US", "NL", "US"]);
+        assert_eq!(table.string_column("org").unwrap(), vec!["A", "B", "B"]);
+        assert!(!path.with_extension("arrow.tmp").exists());
+    }
+
+    #[test]
+    fn test_ipc_invalid_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("garbage.arrow");
+        std::fs::write(&path, b"definitely not an arrow file").unwrap();
+
+        assert!(LoadedTable::read_ipc(&path).is_err());
+
+        // Decode failures are not reported as Parquet failures
+        std::fs::write(&path, b"ARROW1").unwrap();
+        assert!(matches!(
+            LoadedTable::read_ipc(&path),
+            Err(ArrowError::IpcLoad(_))
+        ));
+
+        assert!(matches!(
+            LoadedTable::read_ipc(&dir.path().join("missing.arrow")),
+            Err(ArrowError::FileNotFound(_))
+        ));
+    }
+}
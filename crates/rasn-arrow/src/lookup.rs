@@ -0,0 +1,35 @@
+//! Common interface of the IPv4 lookup backends
+//!
+//! [`IpRangeTableV4`](crate::IpRangeTableV4) searches flattened ranges and
+//! [`PrefixTrieV4`](crate::PrefixTrieV4) searches announced prefixes. Code
+//! that only needs answers, such as benchmarks, can take either through
+//! [`Ipv4Lookup`].
+
+use rasn_core::AsnInfo;
+
+/// IPv4 → ASN lookup backend
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasn_arrow::{IpRangeTableV4, Ipv4Lookup, PrefixTrieV4};
+/// use std::path::Path;
+///
+/// fn google<L: Ipv4Lookup>(backend: &L) -> Option<u32> {
+///     backend.find_ip(0x08080808).map(|info| info.asn.0)  // 8.8.8.8
+/// }
+///
+/// let table = IpRangeTableV4::from_parquet(Path::new("data/arrow/ip2asn-v4.parquet"))?;
+/// let trie = PrefixTrieV4::from_ranges(&table)?;
+/// assert_eq!(google(&table), google(&trie));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub trait Ipv4Lookup {
+    /// Find ASN information for an IPv4 address
+    fn find_ip(&self, ip: u32) -> Option<AsnInfo>;
+
+    /// Find ASN information for a batch of IPv4 addresses, in input order
+    fn find_many(&self, ips: &[u32]) -> Vec<Option<AsnInfo>> {
+        ips.iter().map(|&ip| self.find_ip(ip)).collect()
+    }
+}
@@ -0,0 +1,67 @@
+//! Range table memory accounting
+//!
+//! [`MemoryUsage`] breaks the size of a range table down per column. String
+//! columns are dictionary-encoded, so their size is the per-row keys plus
+//! one copy of each distinct value.
+
+use serde::Serialize;
+use std::ops::Add;
+
+/// Memory used by a range table, in bytes per column
+///
+/// Sizes are the logical sizes of the column data. For tables opened with
+/// [`IpRangeTableV4::from_ipc`](crate::IpRangeTableV4::from_ipc) the data
+/// lives in file-backed pages shared with other processes mapping the same
+/// file, rather than in private heap memory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasn_arrow::IpRangeTableV4;
+/// use std::path::Path;
+///
+/// let table = IpRangeTableV4::from_parquet(Path::new("data/arrow/ip2asn-v4.parquet"))?;
+/// let usage = table.memory_usage();
+/// println!("{} rows, {} bytes", usage.rows, usage.total());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MemoryUsage {
+    /// Number of rows
+    pub rows: usize,
+    /// Start and end address columns
+    pub addresses: usize,
+    /// ASN column
+    pub asns: usize,
+    /// Country column (keys and distinct values)
+    pub countries: usize,
+    /// Organization column (keys and distinct values)
+    pub orgs: usize,
+    /// Number of distinct country values
+    pub distinct_countries: usize,
+    /// Number of distinct organization values
+    pub distinct_orgs: usize,
+}
+
+impl MemoryUsage {
+    /// Get the total number of bytes over all columns
+    pub fn total(&self) -> usize {
+        self.addresses + self.asns + self.countries + self.orgs
+    }
+}
+
+impl Add for MemoryUsage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            rows: self.rows + other.rows,
+            addresses: self.addresses + other.addresses,
+            asns: self.asns + other.asns,
+            countries: self.countries + other.countries,
+            orgs: self.orgs + other.orgs,
+            distinct_countries: self.distinct_countries + other.distinct_countries,
+            distinct_orgs: self.distinct_orgs + other.distinct_orgs,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Sorted-merge batch lookups
+//!
+//! Looking up a large batch one address at a time pays a tree or binary
+//! search per address, with cache misses on every one. [`find_rows`] sorts
+//! the batch instead and walks the ranges once, galloping forward from the
+//! previous match, so nearby and repeated addresses hit ranges that are
+//! already cached.
+
+/// Find the row containing each address, in input order
+///
+/// `starts` and `ends` are the range columns of a table sorted by start
+/// address with non-overlapping ranges.
+pub(crate) fn find_rows<A: Copy + Ord>(starts: &[A], ends: &[A], ips: &[A]) -> Vec<Option<usize>> {
+    let mut order: Vec<(A, usize)> = ips.iter().copied().zip(0..).collect();
+    order.sort_unstable();
+
+    let mut rows = vec![None; ips.len()];
+    // Number of starts <= the previous (smaller or equal) address
+    let mut seen = 0;
+    for (ip, index) in order {
+        seen = gallop(starts, seen, ip);
+        rows[index] = seen.checked_sub(1).filter(|&row| ip <= ends[row]);
+    }
+
+    rows
+}
+
+/// Count the starts `<= ip`, given that the first `from` of them are
+///
+/// Probes 1, 2, 4, ... rows ahead before binary searching the last step,
+/// so the cost grows with the distance to the answer rather than the
+/// table size.
+fn gallop<A: Copy + Ord>(starts: &[A], from: usize, ip: A) -> usize {
+    let mut low = from;
+    let mut high = from;
+    let mut step = 1;
+
+    while high < starts.len() && starts[high] <= ip {
+        low = high + 1;
+        high += step;
+        step *= 2;
+    }
+
+    let high = high.min(starts.len());
+    low + starts[low..high].partition_point(|&start| start <= ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_rows_input_order() {
+        let starts = [10u32, 20, 30, 0x8000_0000];
+        let ends = [15u32, 25, 35, u32::MAX];
+        let ips = [u32::MAX, 12, 5, 22, 12, 17, 30, 0x8000_0000];
+
+        assert_eq!(
+            find_rows(&starts, &ends, &ips),
+            vec![
+                Some(3),
+                Some(0),
+                None,
+                Some(1),
+                Some(0),
+                None,
+                Some(2),
+                Some(3)
+            ]
+        );
+        assert!(find_rows(&starts, &ends, &[]).is_empty());
+        assert_eq!(find_rows::<u32>(&[], &[], &[1]), vec![None]);
+    }
+
+    #[test]
+    fn test_gallop() {
+        let starts: Vec<u32> = (0..100).map(|i| i * 10).collect();
+        for from in [0, 1, 50] {
+            for ip in [500, 505, 990, 5000] {
+                let expected = starts.partition_point(|&start| start <= ip);
+                if from <= expected {
+                    assert_eq!(gallop(&starts, from, ip), expected);
+                }
+            }
+        }
+    }
+}
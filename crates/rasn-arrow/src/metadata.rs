@@ -0,0 +1,247 @@
+//! ASN metadata index
+//!
+//! Loads `asn-metadata.parquet` (ASN → handle/description) into a table
+//! sorted by ASN so that metadata lookups are O(log n) and need no IP scan.
+
+use crate::loader::LoadedTable;
+use crate::{ArrowError, Result};
+use arrow::array::UInt32Array;
+use rasn_core::{Asn, AsnInfo};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Registry metadata for a single ASN
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AsnMetadata {
+    /// The ASN number
+    pub asn: Asn,
+    /// Registry handle (e.g., "GOOGLE")
+    pub handle: String,
+    /// Organization description (e.g., "Google LLC")
+    pub description: String,
+}
+
+impl From<AsnMetadata> for AsnInfo {
+    fn from(meta: AsnMetadata) -> Self {
+        AsnInfo {
+            asn: meta.asn,
+            organization: meta.handle.clone(),
+            country: None,
+            description: non_empty(meta.description),
+            handle: non_empty(meta.handle),
+        }
+    }
+}
+
+/// ASN metadata table keyed by ASN
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasn_arrow::AsnMetadataTable;
+/// use rasn_core::Asn;
+/// use std::path::Path;
+///
+/// let table = AsnMetadataTable::from_parquet(Path::new("data/arrow/asn-metadata.parquet"))?;
+/// if let Some(meta) = table.get(Asn(15169)) {
+///     println!("{}: {}", meta.handle, meta.description);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct AsnMetadataTable {
+    asns: Arc<UInt32Array>,
+    handles: Vec<String>,
+    descriptions: Vec<String>,
+    len: usize,
+}
+
+impl AsnMetadataTable {
+    /// Create ASN metadata table from vectors
+    ///
+    /// Rows are sorted by ASN if they are not already.
+    pub fn from_vecs(
+        asns: Vec<u32>,
+        handles: Vec<String>,
+        descriptions: Vec<String>,
+    ) -> Result<Self> {
+        if handles.len() != asns.len() || descriptions.len() != asns.len() {
+            return Err(ArrowError::InvalidSchema(format!(
+                "Column length mismatch: {} asns, {} handles, {} descriptions",
+                asns.len(),
+                handles.len(),
+                descriptions.len()
+            )));
+        }
+
+        Ok(Self::sorted(asns, handles, descriptions))
+    }
+
+    /// Load ASN metadata table from Parquet file
+    pub fn from_parquet(path: &Path) -> Result<Self> {
+        let table = LoadedTable::read_parquet(path)?;
+
+        // Resolve columns by name
+        let asns = table.u32_column("asn")?.values().to_vec();
+        let handles = table.string_column("handle")?;
+        let descriptions = table.string_column("description")?;
+
+        Self::from_vecs(asns, handles, descriptions)
+    }
+
+    /// Get metadata for an ASN
+    ///
+    /// Time complexity: O(log n)
+    pub fn get(&self, asn: Asn) -> Option<AsnMetadata> {
+        let idx = self.asns.values().binary_search(&asn.0).ok()?;
+
+        Some(AsnMetadata {
+            asn,
+            handle: self.handles[idx].clone(),
+            description: self.descriptions[idx].clone(),
+        })
+    }
+
+    /// Fill missing `description` and `handle` fields of a lookup result
+    pub fn enrich(&self, info: &mut AsnInfo) {
+        let Some(meta) = self.get(info.asn) else {
+            return;
+        };
+
+        if info.description.is_none() {
+            info.description = non_empty(meta.description);
+        }
+        if info.handle.is_none() {
+            info.handle = non_empty(meta.handle);
+        }
+    }
+
+    /// Iterate over `(asn, handle, description)` of every row
+    pub(crate) fn rows(&self) -> impl Iterator<Item = (u32, &str, &str)> {
+        self.asns
+            .values()
+            .iter()
+            .zip(&self.handles)
+            .zip(&self.descriptions)
+            .map(|((&asn, handle), description)| (asn, handle.as_str(), description.as_str()))
+    }
+
+    /// Get the number of ASNs in the table
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the table is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Build the table, sorting rows by ASN when needed
+    fn sorted(asns: Vec<u32>, handles: Vec<String>, descriptions: Vec<String>) -> Self {
+        let len = asns.len();
+
+        if asns.windows(2).all(|w| w[0] <= w[1]) {
+            return Self {
+                asns: Arc::new(UInt32Array::from(asns)),
+                handles,
+                descriptions,
+                len,
+            };
+        }
+
+        let mut rows: Vec<_> = asns
+            .into_iter()
+            .zip(handles)
+            .zip(descriptions)
+            .map(|((asn, handle), description)| (asn, handle, description))
+            .collect();
+        rows.sort_by_key(|row| row.0);
+
+        let mut asns = Vec::with_capacity(len);
+        let mut handles = Vec::with_capacity(len);
+        let mut descriptions = Vec::with_capacity(len);
+        for (asn, handle, description) in rows {
+            asns.push(asn);
+            handles.push(handle);
+            descriptions.push(description);
+        }
+
+        Self {
+            asns: Arc::new(UInt32Array::from(asns)),
+            handles,
+            descriptions,
+            len,
+        }
+    }
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_table() -> AsnMetadataTable {
+        AsnMetadataTable::from_vecs(
+            vec![15169, 1, 13335],
+            vec![
+                "GOOGLE".to_string(),
+                "LVLT-1".to_string(),
+                "CLOUDFLARENET".to_string(),
+            ],
+            vec![
+                "Google LLC".to_string(),
+                "Level 3 Parent LLC".to_string(),
+                String::new(),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_get_unsorted_input() {
+        let table = test_table();
+        assert_eq!(table.len(), 3);
+
+        let meta = table.get(Asn(15169)).unwrap();
+        assert_eq!(meta.handle, "GOOGLE");
+        assert_eq!(meta.description, "Google LLC");
+
+        assert_eq!(table.get(Asn(1)).unwrap().handle, "LVLT-1");
+        assert!(table.get(Asn(64512)).is_none());
+    }
+
+    #[test]
+    fn test_enrich() {
+        let table = test_table();
+
+        let mut info = AsnInfo {
+            asn: Asn(15169),
+            organization: "GOOGLE".to_string(),
+            country: Some("US".to_string()),
+            description: None,
+            handle: None,
+        };
+        table.enrich(&mut info);
+        assert_eq!(info.description, Some("Google LLC".to_string()));
+        assert_eq!(info.handle, Some("GOOGLE".to_string()));
+
+        // Empty descriptions stay None
+        let mut info = AsnInfo::from(table.get(Asn(13335)).unwrap());
+        table.enrich(&mut info);
+        assert_eq!(info.description, None);
+        assert_eq!(info.organization, "CLOUDFLARENET");
+    }
+
+    #[test]
+    fn test_length_mismatch() {
+        let result = AsnMetadataTable::from_vecs(vec![1, 2], vec![String::new()], vec![]);
+        assert!(matches!(result, Err(ArrowError::InvalidSchema(_))));
+    }
+}
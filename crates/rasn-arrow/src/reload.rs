@@ -0,0 +1,266 @@
+//! Atomically swappable database handle
+//!
+//! A long-running process (the MCP server) keeps serving lookups while the
+//! daily data files are replaced. [`DatabaseHandle`] holds the current
+//! [`IpAsnDatabase`] behind an [`ArcSwap`]: readers take a cheap snapshot
+//! with [`DatabaseHandle::load`], and [`DatabaseHandle::reload`] loads and
+//! validates the new files before swapping them in, so a broken file never
+//! replaces a working database. Lookups in flight finish on the snapshot
+//! they started with.
+
+use crate::database::{
+    ASN_METADATA_FILE, COUNTRY_INDEX_FILE, IPV4_IPC_FILE, IPV4_TABLE_FILE, IPV6_IPC_FILE,
+    IPV6_TABLE_FILE,
+};
+use crate::{ArrowError, IpAsnDatabase, Result};
+use arc_swap::ArcSwap;
+use rasn_core::{AsnLookup, LookupAnswer, LookupSource};
+use serde::Serialize;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Outcome of a successful [`DatabaseHandle::reload`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReloadReport {
+    /// Data directory or IPv4 table file that was loaded
+    pub path: PathBuf,
+    /// Number of IPv4 ranges now served
+    pub ipv4_ranges: usize,
+    /// Number of IPv6 ranges now served
+    pub ipv6_ranges: usize,
+    /// Number of successful reloads since the handle was created
+    pub generation: u64,
+}
+
+/// Shared, reloadable [`IpAsnDatabase`]
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasn_arrow::DatabaseHandle;
+/// use std::path::Path;
+///
+/// let handle = DatabaseHandle::open(Path::new("data/arrow"))?;
+/// let before = handle.load();
+///
+/// // ... the files in data/arrow are replaced ...
+/// let report = handle.reload()?;
+/// println!("{} IPv4 ranges", report.ipv4_ranges);
+///
+/// // Snapshots taken earlier keep the old tables alive
+/// assert!(!std::sync::Arc::ptr_eq(&before, &handle.load()));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct DatabaseHandle {
+    current: ArcSwap<IpAsnDatabase>,
+    path: Option<PathBuf>,
+    generation: AtomicU64,
+}
+
+impl DatabaseHandle {
+    /// Wrap an already loaded database
+    ///
+    /// Without a path the handle cannot be reloaded.
+    pub fn new(database: IpAsnDatabase) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(database),
+            path: None,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Load and validate a database from a data directory or IPv4 table file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path accepted by [`IpAsnDatabase::load`], reused by
+    ///   [`DatabaseHandle::reload`]
+    pub fn open(path: &Path) -> Result<Self> {
+        let database = load_validated(path)?;
+        Ok(Self::new(database).with_path(path))
+    }
+
+    /// Set the path that [`DatabaseHandle::reload`] loads from
+    pub fn with_path(mut self, path: &Path) -> Self {
+        self.path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Get the path that [`DatabaseHandle::reload`] loads from
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Get a snapshot of the current database
+    pub fn load(&self) -> Arc<IpAsnDatabase> {
+        self.current.load_full()
+    }
+
+    /// Replace the current database
+    pub fn store(&self, database: IpAsnDatabase) {
+        self.current.store(Arc::new(database));
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the number of times the database was replaced
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Reload the database from its path
+    ///
+    /// The new files are loaded and checked with the range table
+    /// validation first; on any error the current database stays in place.
+    pub fn reload(&self) -> Result<ReloadReport> {
+        let path = self.path.as_deref().ok_or_else(|| {
+            ArrowError::FileNotFound("database was not loaded from a path".to_string())
+        })?;
+
+        let database = load_validated(path)?;
+        let ipv4_ranges = database.v4().map_or(0, |t| t.len());
+        let ipv6_ranges = database.v6().map_or(0, |t| t.len());
+        self.store(database);
+
+        Ok(ReloadReport {
+            path: path.to_path_buf(),
+            ipv4_ranges,
+            ipv6_ranges,
+            generation: self.generation(),
+        })
+    }
+
+    /// Get the newest modification time of the data files
+    ///
+    /// Used to poll for replaced files. Returns `None` when the handle has
+    /// no path or none of the files exist.
+    pub fn modified(&self) -> Option<SystemTime> {
+        let path = self.path.as_deref()?;
+        let files = if path.is_dir() {
+            [
+                IPV4_TABLE_FILE,
+                IPV4_IPC_FILE,
+                IPV6_TABLE_FILE,
+                IPV6_IPC_FILE,
+                ASN_METADATA_FILE,
+                COUNTRY_INDEX_FILE,
+            ]
+            .iter()
+            .map(|name| path.join(name))
+            .collect()
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        files
+            .iter()
+            .filter_map(|file| file.metadata().and_then(|m| m.modified()).ok())
+            .max()
+    }
+}
+
+impl AsnLookup for DatabaseHandle {
+    fn source(&self) -> LookupSource {
+        LookupSource::Arrow
+    }
+
+    fn lookup(&self, ip: IpAddr) -> rasn_core::Result<Option<LookupAnswer>> {
+        AsnLookup::lookup(self.current.load().as_ref(), ip)
+    }
+}
+
+/// Load a database and reject tables that fail validation
+fn load_validated(path: &Path) -> Result<IpAsnDatabase> {
+    let database = IpAsnDatabase::load(path)?;
+
+    let reports = [
+        ("IPv4", database.v4().map(|t| t.validate())),
+        ("IPv6", database.v6().map(|t| t.validate())),
+    ];
+    for (family, report) in reports {
+        if let Some(report) = report.filter(|r| !r.is_valid()) {
+            return Err(ArrowError::InvalidData(format!(
+                "{} table in {} has {} integrity violations",
+                family,
+                path.display(),
+                report.issue_count()
+            )));
+        }
+    }
+
+    Ok(database)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::write_google_v4;
+    use crate::IpRangeTableV4;
+    use tempfile::TempDir;
+
+    fn asn(database: &IpAsnDatabase, ip: IpAddr) -> u32 {
+        database.lookup(ip).unwrap().asn.0
+    }
+
+    #[test]
+    fn test_reload_swaps_database() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(IPV4_IPC_FILE);
+        write_google_v4(&path, 15169);
+
+        let handle = DatabaseHandle::open(dir.path()).unwrap();
+        let ip: IpAddr = "8.8.8.8".parse().unwrap();
+        let before = handle.load();
+        assert_eq!(asn(&before, ip), 15169);
+        assert!(handle.modified().is_some());
+
+        // Replace the file instead of rewriting the mapped one in place
+        std::fs::remove_file(&path).unwrap();
+        write_google_v4(&path, 64512);
+        let report = handle.reload().unwrap();
+        assert_eq!(report.ipv4_ranges, 1);
+        assert_eq!(report.generation, 1);
+
+        let answer = AsnLookup::lookup(&handle, ip).unwrap().unwrap();
+        assert_eq!(answer.info.asn.0, 64512);
+        // The old snapshot is still usable
+        assert_eq!(asn(&before, ip), 15169);
+    }
+
+    #[test]
+    fn test_reload_keeps_database_on_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(IPV4_IPC_FILE);
+        write_google_v4(&path, 15169);
+        let handle = DatabaseHandle::open(dir.path()).unwrap();
+        let ip: IpAddr = "8.8.8.8".parse().unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::write(&path, b"not arrow").unwrap();
+        assert!(handle.reload().is_err());
+
+        // Overlapping ranges load fine but fail validation
+        std::fs::remove_file(&path).unwrap();
+        IpRangeTableV4::from_vecs(
+            vec![0x08080800, 0x08080880],
+            vec![0x080808FF, 0x080809FF],
+            vec![64512; 2],
+            vec!["US".to_string(); 2],
+            vec!["GOOGLE".to_string(); 2],
+        )
+        .unwrap()
+        .write_ipc(&path)
+        .unwrap();
+        let err = handle.reload().unwrap_err();
+        assert!(err.to_string().contains("integrity violations"));
+
+        assert_eq!(handle.generation(), 0);
+        assert_eq!(asn(&handle.load(), ip), 15169);
+
+        let in_memory = DatabaseHandle::new(IpAsnDatabase::new(None, None));
+        assert!(in_memory.reload().is_err());
+        assert!(in_memory.modified().is_none());
+    }
+}
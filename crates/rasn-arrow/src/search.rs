@@ -0,0 +1,349 @@
+//! Organization name search
+//!
+//! Case-insensitive search over the organization names of the range tables
+//! and the handles/descriptions of the ASN metadata table. Results are
+//! grouped per ASN and ranked by match quality: exact, prefix, whole token,
+//! substring and finally fuzzy (edit distance) matches.
+
+use rasn_core::Asn;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How an organization name matched a query, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    /// Whole name equals the query
+    Exact,
+    /// Name starts with the query
+    Prefix,
+    /// Every query word is a word of the name
+    Token,
+    /// Name contains the query
+    Substring,
+    /// Every query word is within a small edit distance of a name word
+    Fuzzy,
+}
+
+/// Organization search query
+///
+/// # Examples
+///
+/// ```
+/// use rasn_arrow::OrgQuery;
+///
+/// let query = OrgQuery::new("google").with_country("US").with_limit(10);
+/// assert_eq!(query.text(), "google");
+/// ```
+#[derive(Debug, Clone)]
+pub struct OrgQuery {
+    text: String,
+    country: Option<String>,
+    exact: bool,
+    limit: Option<usize>,
+}
+
+impl OrgQuery {
+    /// Create a query matching organization names case-insensitively
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.trim().to_string(),
+            country: None,
+            exact: false,
+            limit: None,
+        }
+    }
+
+    /// Only return ASNs whose ranges are registered in a country
+    pub fn with_country(mut self, country: &str) -> Self {
+        self.country = Some(country.trim().to_uppercase());
+        self
+    }
+
+    /// Only return exact (case-insensitive) name matches
+    pub fn exact(mut self, exact: bool) -> Self {
+        self.exact = exact;
+        self
+    }
+
+    /// Return at most `limit` matches
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Get the query text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// A single ASN matching an organization search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrgMatch {
+    /// The ASN number
+    pub asn: Asn,
+    /// Organization name (from the range tables, or the registry handle)
+    pub name: String,
+    /// Country code of the ASN's first range
+    pub country: Option<String>,
+    /// Registry handle, if the metadata table is loaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    /// Organization description, if the metadata table is loaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Number of IPv4 and IPv6 ranges announced by the ASN
+    pub ip_range_count: usize,
+    /// How the best field of this ASN matched
+    #[serde(rename = "match")]
+    pub kind: MatchKind,
+}
+
+/// Per-ASN search candidate, borrowing strings from the tables
+#[derive(Default)]
+struct Candidate<'a> {
+    name: Option<&'a str>,
+    country: Option<&'a str>,
+    handle: Option<&'a str>,
+    description: Option<&'a str>,
+    ranges: usize,
+}
+
+/// Collects ASN candidates from range rows and metadata rows
+#[derive(Default)]
+pub(crate) struct OrgCandidates<'a> {
+    by_asn: HashMap<u32, Candidate<'a>>,
+}
+
+impl<'a> OrgCandidates<'a> {
+    /// Add a range row; the first row of an ASN provides name and country
+    pub(crate) fn add_range(&mut self, asn: u32, org: &'a str, country: &'a str) {
+        let candidate = self.by_asn.entry(asn).or_default();
+        candidate.name.get_or_insert(org);
+        candidate.country.get_or_insert(country);
+        candidate.ranges += 1;
+    }
+
+    /// Add a metadata row
+    pub(crate) fn add_metadata(&mut self, asn: u32, handle: &'a str, description: &'a str) {
+        let candidate = self.by_asn.entry(asn).or_default();
+        candidate.handle = Some(handle).filter(|s| !s.is_empty());
+        candidate.description = Some(description).filter(|s| !s.is_empty());
+    }
+
+    /// Match all candidates against a query and rank the results
+    pub(crate) fn search(self, query: &OrgQuery) -> Vec<OrgMatch> {
+        let matcher = Matcher::new(&query.text);
+        if matcher.query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut ranked: Vec<(MatchKind, usize, OrgMatch)> = self
+            .by_asn
+            .into_iter()
+            .filter(|(_, c)| match query.country {
+                Some(ref country) => c.country.is_some_and(|cc| cc.eq_ignore_ascii_case(country)),
+                None => true,
+            })
+            .filter_map(|(asn, c)| {
+                let (kind, distance) = [c.name, c.handle, c.description]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|field| matcher.classify(field))
+                    .filter(|&(kind, _)| !query.exact || kind == MatchKind::Exact)
+                    .min()?;
+
+                let name = c.name.or(c.handle).unwrap_or_default().to_string();
+                Some((
+                    kind,
+                    distance,
+                    OrgMatch {
+                        asn: Asn(asn),
+                        name,
+                        country: c.country.map(str::to_string),
+                        handle: c.handle.map(str::to_string),
+                        description: c.description.map(str::to_string),
+                        ip_range_count: c.ranges,
+                        kind,
+                    },
+                ))
+            })
+            .collect();
+
+        // Best match first, then larger footprint, then lower ASN
+        ranked.sort_by(|a, b| {
+            (a.0, a.1)
+                .cmp(&(b.0, b.1))
+                .then(b.2.ip_range_count.cmp(&a.2.ip_range_count))
+                .then(a.2.asn.cmp(&b.2.asn))
+        });
+
+        let limit = query.limit.unwrap_or(usize::MAX);
+        ranked.into_iter().take(limit).map(|(_, _, m)| m).collect()
+    }
+}
+
+/// Lower-cased query split into words
+struct Matcher {
+    query: String,
+    tokens: Vec<String>,
+}
+
+impl Matcher {
+    fn new(query: &str) -> Self {
+        let query = query.trim().to_lowercase();
+        let tokens = tokenize(&query).map(str::to_string).collect();
+        Self { query, tokens }
+    }
+
+    /// Classify how `field` matches, with an edit distance for fuzzy matches
+    fn classify(&self, field: &str) -> Option<(MatchKind, usize)> {
+        let field = field.to_lowercase();
+
+        if field == self.query {
+            return Some((MatchKind::Exact, 0));
+        }
+        if field.starts_with(&self.query) {
+            return Some((MatchKind::Prefix, 0));
+        }
+
+        let words: Vec<&str> = tokenize(&field).collect();
+        if !self.tokens.is_empty() && self.tokens.iter().all(|t| words.contains(&t.as_str())) {
+            return Some((MatchKind::Token, 0));
+        }
+        if field.contains(&self.query) {
+            return Some((MatchKind::Substring, 0));
+        }
+
+        // Every query word must be close to some word of the field
+        let mut total = 0;
+        for token in &self.tokens {
+            let max_distance = max_edit_distance(token);
+            if max_distance == 0 {
+                return None;
+            }
+            let distance = words
+                .iter()
+                .map(|word| levenshtein(token, word))
+                .min()
+                .filter(|&d| d <= max_distance)?;
+            total += distance;
+        }
+
+        (!self.tokens.is_empty()).then_some((MatchKind::Fuzzy, total))
+    }
+}
+
+/// Split a name into alphanumeric words
+fn tokenize(s: &str) -> impl Iterator<Item = &str> {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+/// Allowed edit distance for a query word; short words must match exactly
+fn max_edit_distance(token: &str) -> usize {
+    match token.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein edit distance between two strings
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> OrgCandidates<'static> {
+        let mut candidates = OrgCandidates::default();
+        candidates.add_range(15169, "GOOGLE", "US");
+        candidates.add_range(15169, "GOOGLE", "US");
+        candidates.add_range(396982, "GOOGLE-CLOUD-PLATFORM", "US");
+        candidates.add_range(36040, "YOUTUBE", "US");
+        candidates.add_range(13335, "CLOUDFLARENET", "US");
+        candidates.add_range(209242, "CLOUDFLARESPECTRUM Cloudflare", "GB");
+        candidates.add_metadata(36040, "YOUTUBE", "Google LLC");
+        candidates.add_metadata(65000, "EXAMPLE-NET", "");
+        candidates
+    }
+
+    #[test]
+    fn test_ranking() {
+        let matches = candidates().search(&OrgQuery::new("google"));
+        let asns: Vec<u32> = matches.iter().map(|m| m.asn.0).collect();
+        assert_eq!(asns, vec![15169, 36040, 396982]);
+
+        assert_eq!(matches[0].kind, MatchKind::Exact);
+        assert_eq!(matches[0].ip_range_count, 2);
+        // Matched through the metadata description
+        assert_eq!(matches[1].kind, MatchKind::Prefix);
+        assert_eq!(matches[1].description, Some("Google LLC".to_string()));
+        assert_eq!(matches[2].kind, MatchKind::Prefix);
+    }
+
+    #[test]
+    fn test_token_substring_and_fuzzy() {
+        let matches = candidates().search(&OrgQuery::new("Cloudflare"));
+        assert_eq!(matches[0].asn, Asn(13335));
+        assert_eq!(matches[0].kind, MatchKind::Prefix);
+        assert_eq!(matches[1].asn, Asn(209242));
+        assert_eq!(matches[1].kind, MatchKind::Prefix);
+
+        let matches = candidates().search(&OrgQuery::new("platform"));
+        assert_eq!(matches[0].kind, MatchKind::Token);
+
+        let matches = candidates().search(&OrgQuery::new("flare"));
+        assert!(matches.iter().all(|m| m.kind == MatchKind::Substring));
+
+        let matches = candidates().search(&OrgQuery::new("gogle"));
+        assert_eq!(matches[0].asn, Asn(15169));
+        assert_eq!(matches[0].kind, MatchKind::Fuzzy);
+    }
+
+    #[test]
+    fn test_filters() {
+        let query = OrgQuery::new("cloudflare").with_country("gb");
+        let matches = candidates().search(&query);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].asn, Asn(209242));
+
+        let matches = candidates().search(&OrgQuery::new("google").exact(true));
+        assert_eq!(matches.len(), 1);
+
+        let matches = candidates().search(&OrgQuery::new("google").with_limit(2));
+        assert_eq!(matches.len(), 2);
+
+        // Metadata-only ASNs are found through their handle
+        let matches = candidates().search(&OrgQuery::new("example-net"));
+        assert_eq!(matches[0].name, "EXAMPLE-NET");
+        assert_eq!(matches[0].ip_range_count, 0);
+
+        assert!(candidates().search(&OrgQuery::new("  ")).is_empty());
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("google", "google"), 0);
+        assert_eq!(levenshtein("gogle", "google"), 1);
+        assert_eq!(levenshtein("amazon", "amazin"), 1);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+}
@@ -0,0 +1,365 @@
+//! Historical snapshots
+//!
+//! A snapshot store is a directory of dated datasets, one per day the data
+//! was archived:
+//!
+//! ```text
+//! snapshots/
+//! ├── 2026-02-01/          data directory (tables directly or in arrow/)
+//! ├── 2026-03-01/arrow/    as written by `rasn build-db -d snapshots/2026-03-01`
+//! └── 2026-03-15.arrow     single IPv4 range table
+//! ```
+//!
+//! [`SnapshotStore::lookup_at`] answers from the newest snapshot taken on or
+//! before the requested date, i.e. the ownership that was valid on that
+//! day as far as the archive knows. Snapshots are loaded on first use and
+//! kept open afterwards.
+
+use crate::dataset::civil_from_days;
+use crate::{ArrowError, IpAsnDatabase, Result};
+use rasn_core::AsnInfo;
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Calendar date of a snapshot (`YYYY-MM-DD`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SnapshotDate {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+impl SnapshotDate {
+    /// Create a date, checking that the day exists
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasn_arrow::SnapshotDate;
+    ///
+    /// assert!(SnapshotDate::new(2024, 2, 29).is_some());
+    /// assert!(SnapshotDate::new(2026, 2, 29).is_none());
+    /// ```
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+
+        (1..=days_in_month)
+            .contains(&day)
+            .then_some(Self { year, month, day })
+    }
+
+    /// Get the current date (UTC)
+    pub fn today() -> Self {
+        let days = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() / 86_400);
+        let (year, month, day) = civil_from_days(days as i64);
+
+        Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+
+    /// Get the year
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// Get the month (1-12)
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Get the day of the month (1-31)
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+impl FromStr for SnapshotDate {
+    type Err = ArrowError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid =
+            || ArrowError::InvalidData(format!("invalid date '{}', expected YYYY-MM-DD", s));
+
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = |len: usize| {
+            parts
+                .next()
+                .filter(|part| part.len() == len && part.bytes().all(|b| b.is_ascii_digit()))
+                .ok_or_else(invalid)
+        };
+        let year = next(4)?.parse().map_err(|_| invalid())?;
+        let month = next(2)?.parse().map_err(|_| invalid())?;
+        let day = next(2)?.parse().map_err(|_| invalid())?;
+
+        Self::new(year, month, day).ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for SnapshotDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Serialize for SnapshotDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// ASN information as recorded in a snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnapshotAnswer {
+    /// The ASN information
+    #[serde(flatten)]
+    pub info: AsnInfo,
+    /// Date of the snapshot that answered
+    pub snapshot: SnapshotDate,
+}
+
+/// Directory of dated datasets for point-in-time lookups
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasn_arrow::{SnapshotDate, SnapshotStore};
+/// use std::path::Path;
+///
+/// let store = SnapshotStore::open(Path::new("data/snapshots"))?;
+/// let date: SnapshotDate = "2026-03-01".parse()?;
+///
+/// if let Some(answer) = store.lookup_at("8.8.8.8".parse()?, date)? {
+///     println!("AS{} (snapshot {})", answer.info.asn.0, answer.snapshot);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct SnapshotStore {
+    root: PathBuf,
+    snapshots: Vec<(SnapshotDate, PathBuf)>,
+    loaded: Mutex<HashMap<SnapshotDate, Arc<IpAsnDatabase>>>,
+}
+
+impl SnapshotStore {
+    /// Scan a snapshot directory
+    ///
+    /// Entries whose name (or file stem) is not a `YYYY-MM-DD` date are
+    /// ignored. Nothing is loaded until a snapshot is first queried.
+    pub fn open(root: &Path) -> Result<Self> {
+        if !root.is_dir() {
+            return Err(ArrowError::FileNotFound(format!(
+                "snapshot directory {}",
+                root.display()
+            )));
+        }
+
+        let mut snapshots = Vec::new();
+        for entry in fs::read_dir(root)? {
+            let path = entry?.path();
+            let name = if path.is_dir() {
+                path.file_name()
+            } else {
+                path.file_stem()
+            };
+            let Some(date) = name.and_then(|n| n.to_str()?.parse::<SnapshotDate>().ok()) else {
+                continue;
+            };
+
+            let arrow_dir = path.join("arrow");
+            let path = if arrow_dir.is_dir() { arrow_dir } else { path };
+            snapshots.push((date, path));
+        }
+        snapshots.sort();
+        snapshots.dedup_by_key(|(date, _)| *date);
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            snapshots,
+            loaded: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Get the snapshot directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Get the dates of all snapshots, oldest first
+    pub fn dates(&self) -> Vec<SnapshotDate> {
+        self.snapshots.iter().map(|(date, _)| *date).collect()
+    }
+
+    /// Get the number of snapshots
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Check if the store has no snapshots
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Get the date of the snapshot valid at `date`
+    ///
+    /// That is the newest snapshot taken on or before `date`; `None` if
+    /// every snapshot is newer.
+    pub fn snapshot_at(&self, date: SnapshotDate) -> Option<SnapshotDate> {
+        self.entry_at(date).map(|(snapshot, _)| *snapshot)
+    }
+
+    /// Find the newest snapshot taken on or before `date`
+    fn entry_at(&self, date: SnapshotDate) -> Option<&(SnapshotDate, PathBuf)> {
+        let index = self.snapshots.partition_point(|(d, _)| *d <= date);
+        index.checked_sub(1).map(|i| &self.snapshots[i])
+    }
+
+    /// Get the database of the snapshot valid at `date`
+    ///
+    /// Fails with [`ArrowError::FileNotFound`] when there is no snapshot on
+    /// or before `date`.
+    pub fn database_at(&self, date: SnapshotDate) -> Result<(SnapshotDate, Arc<IpAsnDatabase>)> {
+        let Some((snapshot, path)) = self.entry_at(date) else {
+            return Err(ArrowError::FileNotFound(format!(
+                "no snapshot on or before {} in {}",
+                date,
+                self.root.display()
+            )));
+        };
+
+        let cached = self
+            .loaded
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(snapshot)
+            .cloned();
+        if let Some(database) = cached {
+            return Ok((*snapshot, database));
+        }
+
+        // Decode without the lock so a cold snapshot does not stall lookups
+        // in the others; if two threads race, the first insert wins
+        let database = Arc::new(IpAsnDatabase::load(path)?);
+        let mut loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        let database = loaded.entry(*snapshot).or_insert(database).clone();
+        Ok((*snapshot, database))
+    }
+
+    /// Find the ASN information of an address as of `date`
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - IPv4 or IPv6 address
+    /// * `date` - Day the ownership should be valid for
+    pub fn lookup_at(&self, ip: IpAddr, date: SnapshotDate) -> Result<Option<SnapshotAnswer>> {
+        let (snapshot, database) = self.database_at(date)?;
+
+        Ok(database
+            .lookup(ip)
+            .map(|info| SnapshotAnswer { info, snapshot }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::IPV4_IPC_FILE;
+    use crate::test_util::write_google_v4;
+    use tempfile::TempDir;
+
+    fn date(s: &str) -> SnapshotDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let parsed = date("2026-03-01");
+        assert_eq!((parsed.year(), parsed.month(), parsed.day()), (2026, 3, 1));
+        assert_eq!(parsed.to_string(), "2026-03-01");
+        assert!(date("2026-02-28") < parsed);
+
+        for bad in [
+            "2026-3-01",
+            "2026-02-30",
+            "2026-13-01",
+            "20260301",
+            "latest",
+            "2026-03-01x",
+        ] {
+            assert!(bad.parse::<SnapshotDate>().is_err(), "{}", bad);
+        }
+        assert!(SnapshotDate::today() > parsed);
+    }
+
+    #[test]
+    fn test_lookup_at() {
+        let root = TempDir::new().unwrap();
+        for (name, asn) in [("2026-01-01", 64500), ("2026-03-01", 64501)] {
+            let dir = root.path().join(name).join("arrow");
+            fs::create_dir_all(&dir).unwrap();
+            write_google_v4(&dir.join(IPV4_IPC_FILE), asn);
+        }
+        write_google_v4(&root.path().join("2026-04-15.arrow"), 64502);
+        fs::create_dir(root.path().join("latest")).unwrap();
+
+        let store = SnapshotStore::open(root.path()).unwrap();
+        assert_eq!(
+            store.dates(),
+            vec![date("2026-01-01"), date("2026-03-01"), date("2026-04-15")]
+        );
+
+        let ip: IpAddr = "8.8.8.8".parse().unwrap();
+        let asn_at = |day: &str| {
+            let answer = store.lookup_at(ip, date(day)).unwrap().unwrap();
+            (answer.info.asn.0, answer.snapshot.to_string())
+        };
+        assert_eq!(asn_at("2026-01-01"), (64500, "2026-01-01".to_string()));
+        assert_eq!(asn_at("2026-02-28"), (64500, "2026-01-01".to_string()));
+        assert_eq!(asn_at("2026-03-01"), (64501, "2026-03-01".to_string()));
+        assert_eq!(asn_at("2027-01-01"), (64502, "2026-04-15".to_string()));
+
+        assert!(store
+            .lookup_at("1.1.1.1".parse().unwrap(), date("2026-03-01"))
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            store.lookup_at(ip, date("2025-12-31")),
+            Err(ArrowError::FileNotFound(_))
+        ));
+        assert_eq!(store.snapshot_at(date("2025-12-31")), None);
+
+        assert!(SnapshotStore::open(&root.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_concurrent_loads_share_database() {
+        let root = TempDir::new().unwrap();
+        write_google_v4(&root.path().join("2026-01-01.arrow"), 64500);
+        let store = SnapshotStore::open(root.path()).unwrap();
+
+        let databases: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| store.database_at(date("2026-02-01")).unwrap().1))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(databases.iter().all(|db| Arc::ptr_eq(db, &databases[0])));
+    }
+}
@@ -0,0 +1,24 @@
+//! Fixtures shared by the tests of rasn-arrow and the crates built on it
+//!
+//! Compiled for this crate's tests and behind the `test-util` feature, which
+//! dependents enable from their `[dev-dependencies]`.
+
+use crate::IpRangeTableV4;
+use std::path::Path;
+
+/// One-row IPv4 table mapping 8.8.8.0/24 to `asn` (US, `GOOGLE`)
+pub fn google_v4(asn: u32) -> IpRangeTableV4 {
+    IpRangeTableV4::from_vecs(
+        vec![0x08080800],
+        vec![0x080808FF],
+        vec![asn],
+        vec!["US".to_string()],
+        vec!["GOOGLE".to_string()],
+    )
+    .unwrap()
+}
+
+/// Write [`google_v4`] as an Arrow IPC file
+pub fn write_google_v4(path: &Path, asn: u32) {
+    google_v4(asn).write_ipc(path).unwrap();
+}
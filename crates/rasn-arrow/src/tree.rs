@@ -0,0 +1,250 @@
+//! Static B+ tree over sorted range start addresses
+//!
+//! Binary search over a large range table touches a new cache line on
+//! almost every step. [`SearchTree`] instead samples every 16th start
+//! address into cache-line sized nodes, level by level, until the top
+//! level fits into a single node. A lookup reads one node per level and
+//! finishes inside a block of 16 leaf keys, counting the keys `<= ip` in
+//! each node with AVX2 when available.
+//!
+//! The leaf keys are the table's own start address column, so the tree
+//! adds about 1/15 of that column in memory and nothing for the leaves.
+
+/// Keys per node: 16 `u32` keys fill one 64-byte cache line
+const FANOUT: usize = 16;
+
+/// One node of an internal level
+#[derive(Debug, Clone, Copy)]
+#[repr(C, align(64))]
+struct Node([u32; FANOUT]);
+
+/// An internal level of the tree
+///
+/// The last node is padded with `u32::MAX`; `len` is the number of real
+/// keys.
+#[derive(Debug, Clone)]
+struct Level {
+    nodes: Vec<Node>,
+    len: usize,
+}
+
+impl Level {
+    fn new(keys: &[u32]) -> Self {
+        let nodes = keys
+            .chunks(FANOUT)
+            .map(|chunk| {
+                let mut node = Node([u32::MAX; FANOUT]);
+                node.0[..chunk.len()].copy_from_slice(chunk);
+                node
+            })
+            .collect();
+
+        Self {
+            nodes,
+            len: keys.len(),
+        }
+    }
+
+    /// Get the key at an index
+    fn key(&self, index: usize) -> u32 {
+        self.nodes[index / FANOUT].0[index % FANOUT]
+    }
+}
+
+/// Implicit static B+ tree over a sorted `u32` column
+///
+/// The tree only stores the internal levels; the sorted leaf keys are
+/// passed to [`SearchTree::find`] and must be the same keys the tree was
+/// built from.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SearchTree {
+    /// Internal levels, bottom-up
+    levels: Vec<Level>,
+}
+
+impl SearchTree {
+    /// Build the internal levels over sorted keys
+    pub(crate) fn new(keys: &[u32]) -> Self {
+        let mut levels: Vec<Level> = Vec::new();
+        let mut len = keys.len();
+
+        while len > FANOUT {
+            let sampled: Vec<u32> = match levels.last() {
+                Some(below) => (0..len).step_by(FANOUT).map(|i| below.key(i)).collect(),
+                None => keys.iter().step_by(FANOUT).copied().collect(),
+            };
+            len = sampled.len();
+            levels.push(Level::new(&sampled));
+        }
+
+        Self { levels }
+    }
+
+    /// Find the index of the last key `<= ip`
+    ///
+    /// Returns `None` when `ip` is below the first key or `keys` is empty.
+    pub(crate) fn find(&self, keys: &[u32], ip: u32) -> Option<usize> {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") {
+                // SAFETY: We've checked AVX2 is available
+                return unsafe { self.find_avx2(keys, ip) };
+            }
+        }
+
+        self.find_scalar(keys, ip)
+    }
+
+    /// Portable tree search
+    pub(crate) fn find_scalar(&self, keys: &[u32], ip: u32) -> Option<usize> {
+        let mut pos = 0;
+        for level in self.levels.iter().rev() {
+            let count = count_le(&level.nodes[pos].0, ip);
+            pos = (pos * FANOUT + count).min(level.len).checked_sub(1)?;
+        }
+
+        let block = pos * FANOUT;
+        let end = (block + FANOUT).min(keys.len());
+        (block + count_le(&keys[block..end], ip)).checked_sub(1)
+    }
+
+    /// Tree search comparing 16 keys per node with AVX2
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx2")]
+    unsafe fn find_avx2(&self, keys: &[u32], ip: u32) -> Option<usize> {
+        let mut pos = 0;
+        for level in self.levels.iter().rev() {
+            let count = count_le_avx2(&level.nodes[pos].0, ip);
+            pos = (pos * FANOUT + count).min(level.len).checked_sub(1)?;
+        }
+
+        let block = pos * FANOUT;
+        let end = (block + FANOUT).min(keys.len());
+        let count = match keys[block..end].try_into() {
+            Ok(full) => count_le_avx2(full, ip),
+            Err(_) => count_le(&keys[block..end], ip),
+        };
+        (block + count).checked_sub(1)
+    }
+}
+
+/// Count the keys `<= ip` without branching on the comparisons
+#[inline]
+fn count_le(keys: &[u32], ip: u32) -> usize {
+    keys.iter().map(|&key| (key <= ip) as usize).sum()
+}
+
+/// Count the keys `<= ip` in a full node with AVX2
+///
+/// AVX2 only has signed 32-bit compares, so both sides are offset by
+/// `0x8000_0000` first, which maps unsigned order onto signed order.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn count_le_avx2(keys: &[u32; FANOUT], ip: u32) -> usize {
+    use std::arch::x86_64::*;
+
+    let bias = _mm256_set1_epi32(i32::MIN);
+    let needle = _mm256_xor_si256(_mm256_set1_epi32(ip as i32), bias);
+
+    let ptr = keys.as_ptr() as *const __m256i;
+    let lo = _mm256_xor_si256(_mm256_loadu_si256(ptr), bias);
+    let hi = _mm256_xor_si256(_mm256_loadu_si256(ptr.add(1)), bias);
+
+    // Lanes where key > ip
+    let gt_lo = _mm256_movemask_ps(_mm256_castsi256_ps(_mm256_cmpgt_epi32(lo, needle)));
+    let gt_hi = _mm256_movemask_ps(_mm256_castsi256_ps(_mm256_cmpgt_epi32(hi, needle)));
+    let greater = (gt_lo as u32 | (gt_hi as u32) << 8).count_ones() as usize;
+
+    FANOUT - greater
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Reference answer: index of the last key `<= ip`
+    fn last_le(keys: &[u32], ip: u32) -> Option<usize> {
+        keys.partition_point(|&key| key <= ip).checked_sub(1)
+    }
+
+    #[test]
+    fn test_levels() {
+        let keys: Vec<u32> = (0..1000).map(|i| i * 10).collect();
+        let tree = SearchTree::new(&keys);
+
+        // 1000 leaves -> 63 keys -> 4 keys
+        assert_eq!(tree.levels.len(), 2);
+        assert_eq!(tree.levels[0].len, 63);
+        assert_eq!(tree.levels[1].len, 4);
+        assert_eq!(tree.levels[1].key(3), 7680);
+
+        assert!(SearchTree::new(&keys[..16]).levels.is_empty());
+    }
+
+    #[test]
+    fn test_find_edges() {
+        let keys = [0, 5, 0x7fff_ffff, 0x8000_0000, 0xffff_fff0, u32::MAX];
+        let tree = SearchTree::new(&keys);
+
+        for ip in [
+            0,
+            4,
+            5,
+            0x7fff_ffff,
+            0x8000_0000,
+            0x9000_0000,
+            u32::MAX - 1,
+            u32::MAX,
+        ] {
+            assert_eq!(tree.find(&keys, ip), last_le(&keys, ip), "ip {ip:#x}");
+            assert_eq!(tree.find_scalar(&keys, ip), last_le(&keys, ip));
+        }
+
+        assert_eq!(SearchTree::new(&[]).find(&[], 1), None);
+        assert_eq!(tree.find(&keys[1..], 0), None);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_count_le_avx2_unsigned() {
+        if !is_x86_feature_detected!("avx2") {
+            return;
+        }
+
+        let mut node = [0u32; FANOUT];
+        for (i, key) in node.iter_mut().enumerate() {
+            *key = 0x7fff_fff8 + i as u32;
+        }
+
+        for ip in [
+            0,
+            0x7fff_fff8,
+            0x7fff_ffff,
+            0x8000_0000,
+            0x8000_0007,
+            u32::MAX,
+        ] {
+            // SAFETY: We've checked AVX2 is available
+            let count = unsafe { count_le_avx2(&node, ip) };
+            assert_eq!(count, count_le(&node, ip), "ip {ip:#x}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_find_matches_partition_point(
+            mut keys in prop::collection::vec(any::<u32>(), 0..5000),
+            ips in prop::collection::vec(any::<u32>(), 64),
+        ) {
+            keys.sort_unstable();
+            let tree = SearchTree::new(&keys);
+
+            for ip in keys.iter().flat_map(|&k| [k.wrapping_sub(1), k]).chain(ips) {
+                prop_assert_eq!(tree.find(&keys, ip), last_le(&keys, ip));
+                prop_assert_eq!(tree.find_scalar(&keys, ip), last_le(&keys, ip));
+            }
+        }
+    }
+}
@@ -0,0 +1,458 @@
+//! Longest-prefix-match trie for IPv4 prefixes
+//!
+//! [`IpRangeTableV4`] needs flattened, non-overlapping ranges, so a
+//! more-specific announcement inside a covering prefix has to be cut out of
+//! it. [`PrefixTrieV4`] keeps the announced prefixes as they are and answers
+//! with the most specific one containing the address.
+//!
+//! The trie is a Poptrie-style multibit trie: every node covers 6 address
+//! bits (64 slots) with two bitmaps, one marking the slots that lead to
+//! child nodes and one marking where a run of equal leaves starts. Child
+//! nodes and leaf runs are stored contiguously, so a slot is found by
+//! counting the set bits below it. A lookup visits at most 6 nodes.
+
+use crate::column::StringColumn;
+use crate::{ArrowError, IpRangeTableV4, Ipv4Lookup, RangeEntry, Result};
+use rasn_core::{Asn, AsnInfo};
+
+/// Address bits consumed per node
+const STRIDE: u32 = 6;
+
+/// Slots per node
+const SLOTS: usize = 1 << STRIDE;
+
+/// A flattened trie node
+#[derive(Debug, Clone, Copy, Default)]
+struct Node {
+    /// Slots that lead to a child node
+    vector: u64,
+    /// Leaf slots that start a new run of leaf values
+    leafvec: u64,
+    /// Index of the first leaf run in `leaves`
+    base0: u32,
+    /// Index of the first child in `nodes`
+    base1: u32,
+}
+
+/// A node slot while building the trie
+enum Slot {
+    /// Prefix row + 1, or 0 when no prefix covers the slot
+    Leaf(u32),
+    Node(Box<[Slot; SLOTS]>),
+}
+
+/// Get the slot index of an address in a node at a bit offset
+#[inline]
+fn slot_index(ip: u32, offset: u32) -> u32 {
+    (ip << offset) >> (32 - STRIDE)
+}
+
+/// Get the network mask of a prefix length
+fn prefix_mask(prefix_len: u8) -> u32 {
+    u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0)
+}
+
+/// IPv4 longest-prefix-match index over announced prefixes
+///
+/// Prefixes may overlap; a lookup returns the most specific prefix
+/// containing the address.
+///
+/// # Examples
+///
+/// ```
+/// use rasn_arrow::PrefixTrieV4;
+///
+/// let trie = PrefixTrieV4::from_vecs(
+///     vec![0x08000000, 0x08080800],  // 8.0.0.0/8, 8.8.8.0/24
+///     vec![8, 24],
+///     vec![3356, 15169],
+///     vec!["US".to_string(), "US".to_string()],
+///     vec!["LEVEL3".to_string(), "GOOGLE".to_string()],
+/// )?;
+///
+/// assert_eq!(trie.find_ip(0x08080808).unwrap().asn.0, 15169);  // 8.8.8.8
+/// assert_eq!(trie.find_ip(0x08010101).unwrap().asn.0, 3356);   // 8.1.1.1
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct PrefixTrieV4 {
+    nodes: Vec<Node>,
+    leaves: Vec<u32>,
+    networks: Vec<u32>,
+    prefix_lens: Vec<u8>,
+    asns: Vec<u32>,
+    countries: StringColumn,
+    orgs: StringColumn,
+}
+
+impl PrefixTrieV4 {
+    /// Build the trie from prefix columns
+    ///
+    /// Host bits below the prefix length are ignored. When the same prefix
+    /// appears twice, the later row wins.
+    pub fn from_vecs(
+        networks: Vec<u32>,
+        prefix_lens: Vec<u8>,
+        asns: Vec<u32>,
+        countries: Vec<String>,
+        orgs: Vec<String>,
+    ) -> Result<Self> {
+        let len = networks.len();
+        if [prefix_lens.len(), asns.len(), countries.len(), orgs.len()]
+            .iter()
+            .any(|&other| other != len)
+        {
+            return Err(ArrowError::InvalidData(
+                "prefix columns have different lengths".to_string(),
+            ));
+        }
+        if let Some(bad) = prefix_lens.iter().find(|&&prefix_len| prefix_len > 32) {
+            return Err(ArrowError::InvalidData(format!(
+                "invalid IPv4 prefix length /{}",
+                bad
+            )));
+        }
+
+        let networks: Vec<u32> = networks
+            .iter()
+            .zip(&prefix_lens)
+            .map(|(&network, &prefix_len)| network & prefix_mask(prefix_len))
+            .collect();
+
+        // Shorter prefixes first, so more specific ones overwrite them
+        let mut order: Vec<usize> = (0..len).collect();
+        order.sort_by_key(|&row| prefix_lens[row]);
+
+        let mut root: [Slot; SLOTS] = std::array::from_fn(|_| Slot::Leaf(0));
+        for row in order {
+            insert(&mut root, networks[row], prefix_lens[row], row as u32 + 1);
+        }
+        let (nodes, leaves) = flatten(&root);
+
+        Ok(Self {
+            nodes,
+            leaves,
+            networks,
+            prefix_lens,
+            asns,
+            countries: StringColumn::from_strings(countries),
+            orgs: StringColumn::from_strings(orgs),
+        })
+    }
+
+    /// Build the trie from the ranges of a range table
+    ///
+    /// Each range is split into the fewest prefixes covering it exactly.
+    pub fn from_ranges(table: &IpRangeTableV4) -> Result<Self> {
+        let mut networks = Vec::with_capacity(table.len());
+        let mut prefix_lens = Vec::with_capacity(table.len());
+        let mut asns = Vec::with_capacity(table.len());
+        let mut countries = Vec::with_capacity(table.len());
+        let mut orgs = Vec::with_capacity(table.len());
+
+        for row in 0..table.len() {
+            let Some(entry) = table.entry_at(row) else {
+                continue;
+            };
+            for (network, prefix_len) in range_prefixes(entry.start, entry.end) {
+                networks.push(network);
+                prefix_lens.push(prefix_len);
+                asns.push(entry.asn.0);
+                countries.push(entry.country.to_string());
+                orgs.push(entry.organization.to_string());
+            }
+        }
+
+        Self::from_vecs(networks, prefix_lens, asns, countries, orgs)
+    }
+
+    /// Find ASN information for an IPv4 address from the most specific
+    /// prefix containing it
+    pub fn find_ip(&self, ip: u32) -> Option<AsnInfo> {
+        self.find_entry(ip).map(|entry| entry.to_asn_info())
+    }
+
+    /// Find the most specific prefix containing an address, without
+    /// allocating
+    ///
+    /// The entry's `start` and `end` are the first and last address of the
+    /// prefix.
+    pub fn find_entry(&self, ip: u32) -> Option<RangeEntry<'_, u32>> {
+        self.entry_at(self.find_row(ip)?)
+    }
+
+    /// Get the `(network, prefix length)` of a row
+    pub fn prefix_at(&self, row: usize) -> Option<(u32, u8)> {
+        Some((*self.networks.get(row)?, *self.prefix_lens.get(row)?))
+    }
+
+    /// Get a borrowed view of a row
+    pub fn entry_at(&self, row: usize) -> Option<RangeEntry<'_, u32>> {
+        let (network, prefix_len) = self.prefix_at(row)?;
+        Some(RangeEntry {
+            start: network,
+            end: network | !prefix_mask(prefix_len),
+            asn: Asn(*self.asns.get(row)?),
+            organization: self.orgs.get(row)?,
+            country: self.countries.get(row)?,
+        })
+    }
+
+    /// Walk the trie to the row of the most specific prefix
+    #[inline]
+    fn find_row(&self, ip: u32) -> Option<usize> {
+        let mut node = &self.nodes[0];
+        let mut offset = 0;
+
+        loop {
+            let bit = 1u64 << slot_index(ip, offset);
+            if node.vector & bit != 0 {
+                let child = (node.vector & (bit - 1)).count_ones();
+                node = &self.nodes[(node.base1 + child) as usize];
+                offset += STRIDE;
+                continue;
+            }
+
+            let run = (node.leafvec & (bit | (bit - 1))).count_ones() - 1;
+            let leaf = self.leaves[(node.base0 + run) as usize];
+            return (leaf as usize).checked_sub(1);
+        }
+    }
+
+    /// Get the number of trie nodes
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Get the number of prefixes
+    pub fn len(&self) -> usize {
+        self.networks.len()
+    }
+
+    /// Check if the trie has no prefixes
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty()
+    }
+}
+
+impl Ipv4Lookup for PrefixTrieV4 {
+    fn find_ip(&self, ip: u32) -> Option<AsnInfo> {
+        PrefixTrieV4::find_ip(self, ip)
+    }
+}
+
+/// Set every slot covered by a prefix to `value`, creating child nodes as
+/// needed
+///
+/// Prefixes must be inserted shortest first: a new child node starts out
+/// with its parent slot's value, so it inherits the covering prefix.
+fn insert(root: &mut [Slot; SLOTS], network: u32, prefix_len: u8, value: u32) {
+    let prefix_len = prefix_len as u32;
+    let mut slots = root;
+    let mut offset = 0;
+
+    loop {
+        let index = slot_index(network, offset) as usize;
+        if prefix_len <= offset + STRIDE {
+            let span = 1 << (offset + STRIDE - prefix_len);
+            for slot in &mut slots[index..index + span] {
+                *slot = Slot::Leaf(value);
+            }
+            return;
+        }
+
+        if let Slot::Leaf(inherited) = slots[index] {
+            slots[index] = Slot::Node(Box::new(std::array::from_fn(|_| Slot::Leaf(inherited))));
+        }
+        slots = match &mut slots[index] {
+            Slot::Node(children) => children,
+            Slot::Leaf(_) => unreachable!("slot was just replaced by a node"),
+        };
+        offset += STRIDE;
+    }
+}
+
+/// Flatten the build tree breadth-first into nodes and compressed leaves
+fn flatten(root: &[Slot; SLOTS]) -> (Vec<Node>, Vec<u32>) {
+    let mut pending: Vec<&[Slot; SLOTS]> = vec![root];
+    let mut nodes = vec![Node::default()];
+    let mut leaves = Vec::new();
+
+    let mut next = 0;
+    while let Some(&slots) = pending.get(next) {
+        let mut node = Node {
+            base0: leaves.len() as u32,
+            base1: nodes.len() as u32,
+            ..Node::default()
+        };
+
+        let mut last = None;
+        for (index, slot) in slots.iter().enumerate() {
+            match slot {
+                Slot::Node(children) => {
+                    node.vector |= 1 << index;
+                    nodes.push(Node::default());
+                    pending.push(children);
+                }
+                Slot::Leaf(value) => {
+                    if last != Some(*value) {
+                        node.leafvec |= 1 << index;
+                        leaves.push(*value);
+                        last = Some(*value);
+                    }
+                }
+            }
+        }
+
+        nodes[next] = node;
+        next += 1;
+    }
+
+    (nodes, leaves)
+}
+
+/// Split an inclusive address range into the fewest covering prefixes
+fn range_prefixes(start: u32, end: u32) -> Vec<(u32, u8)> {
+    let mut prefixes = Vec::new();
+    let end = end as u64;
+    let mut current = start as u64;
+
+    while current <= end {
+        let mut size = current.trailing_zeros().min(32);
+        while current + (1 << size) - 1 > end {
+            size -= 1;
+        }
+        prefixes.push((current as u32, (32 - size) as u8));
+        current += 1 << size;
+    }
+
+    prefixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn trie(prefixes: &[(u32, u8, u32)]) -> PrefixTrieV4 {
+        PrefixTrieV4::from_vecs(
+            prefixes.iter().map(|p| p.0).collect(),
+            prefixes.iter().map(|p| p.1).collect(),
+            prefixes.iter().map(|p| p.2).collect(),
+            vec!["US".to_string(); prefixes.len()],
+            prefixes.iter().map(|p| format!("AS{}", p.2)).collect(),
+        )
+        .unwrap()
+    }
+
+    fn asn(trie: &PrefixTrieV4, ip: u32) -> Option<u32> {
+        trie.find_entry(ip).map(|entry| entry.asn.0)
+    }
+
+    #[test]
+    fn test_most_specific_wins() {
+        let trie = trie(&[
+            (0x08080800, 24, 15169), // 8.8.8.0/24
+            (0x08000000, 8, 3356),   // 8.0.0.0/8
+            (0x08080808, 32, 1),     // 8.8.8.8/32
+            (0x08080000, 16, 2),     // 8.8.0.0/16
+        ]);
+
+        assert_eq!(asn(&trie, 0x08080808), Some(1));
+        assert_eq!(asn(&trie, 0x08080809), Some(15169));
+        assert_eq!(asn(&trie, 0x08080900), Some(2));
+        assert_eq!(asn(&trie, 0x08FFFFFF), Some(3356));
+        assert_eq!(asn(&trie, 0x09000000), None);
+        assert_eq!(asn(&trie, 0x07FFFFFF), None);
+
+        let entry = trie.find_entry(0x080808FF).unwrap();
+        assert_eq!((entry.start, entry.end), (0x08080800, 0x080808FF));
+        assert_eq!(entry.organization, "AS15169");
+    }
+
+    #[test]
+    fn test_edges() {
+        let trie = trie(&[
+            (0, 0, 64512),       // default route
+            (0xFFFFFFFF, 32, 1), // 255.255.255.255/32
+            (0x80000000, 1, 2),  // 128.0.0.0/1
+            (0xC0000201, 30, 3), // 192.0.2.0/30, host bits set
+        ]);
+
+        assert_eq!(asn(&trie, 0), Some(64512));
+        assert_eq!(asn(&trie, 0x7FFFFFFF), Some(64512));
+        assert_eq!(asn(&trie, 0x80000000), Some(2));
+        assert_eq!(asn(&trie, 0xFFFFFFFE), Some(2));
+        assert_eq!(asn(&trie, 0xFFFFFFFF), Some(1));
+        assert_eq!(asn(&trie, 0xC0000203), Some(3));
+        assert_eq!(asn(&trie, 0xC0000204), Some(2));
+        assert_eq!(trie.prefix_at(3), Some((0xC0000200, 30)));
+
+        let empty = PrefixTrieV4::from_vecs(vec![], vec![], vec![], vec![], vec![]).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.node_count(), 1);
+        assert!(empty.find_ip(0x08080808).is_none());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let result = PrefixTrieV4::from_vecs(vec![0], vec![33], vec![1], vec![], vec![]);
+        assert!(matches!(result, Err(ArrowError::InvalidData(_))));
+
+        let result = PrefixTrieV4::from_vecs(
+            vec![0],
+            vec![33],
+            vec![1],
+            vec![String::new()],
+            vec![String::new()],
+        );
+        match result {
+            Err(ArrowError::InvalidData(msg)) => assert!(msg.contains("/33")),
+            _ => panic!("expected InvalidData"),
+        }
+    }
+
+    #[test]
+    fn test_range_prefixes() {
+        assert_eq!(range_prefixes(0, u32::MAX), vec![(0, 0)]);
+        assert_eq!(
+            range_prefixes(0x0A000001, 0x0A000006),
+            vec![
+                (0x0A000001, 32),
+                (0x0A000002, 31),
+                (0x0A000004, 31),
+                (0x0A000006, 32)
+            ]
+        );
+        assert_eq!(range_prefixes(u32::MAX, u32::MAX), vec![(u32::MAX, 32)]);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_matches_range_table(
+            mut bounds in prop::collection::vec(any::<u32>(), 0..200),
+            ips in prop::collection::vec(any::<u32>(), 256),
+        ) {
+            bounds.sort_unstable();
+            bounds.dedup();
+            let pairs: Vec<(u32, u32)> = bounds
+                .chunks_exact(2)
+                .map(|pair| (pair[0], pair[1]))
+                .collect();
+
+            let table = IpRangeTableV4::from_vecs(
+                pairs.iter().map(|p| p.0).collect(),
+                pairs.iter().map(|p| p.1).collect(),
+                (0..pairs.len() as u32).collect(),
+                vec!["US".to_string(); pairs.len()],
+                vec!["Org".to_string(); pairs.len()],
+            )
+            .unwrap();
+            let trie = PrefixTrieV4::from_ranges(&table).unwrap();
+
+            let edges = pairs.iter().flat_map(|&(s, e)| [s.wrapping_sub(1), s, e, e.wrapping_add(1)]);
+            for ip in edges.chain(ips) {
+                prop_assert_eq!(trie.find_ip(ip), table.find_ip_scalar(ip));
+            }
+        }
+    }
+}
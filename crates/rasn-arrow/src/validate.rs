@@ -0,0 +1,276 @@
+//! Range table integrity checks
+//!
+//! Binary search assumes ranges are sorted by start address, non-overlapping
+//! and well-formed; a table that violates this returns wrong answers without
+//! any error. [`ValidationReport`] lists every violation together with the
+//! address space coverage (gaps between consecutive ranges).
+
+use serde::Serialize;
+use std::fmt;
+
+/// Maximum number of individual issues kept in a report (counts are exact)
+pub const MAX_REPORTED_ISSUES: usize = 100;
+
+/// Kind of integrity violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// A range starts before the previous range
+    Unsorted,
+    /// A range starts inside the previous range
+    Overlap,
+    /// A range has `start > end`
+    InvertedRange,
+    /// A column has a different length than the table
+    ColumnLength,
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            IssueKind::Unsorted => "unsorted",
+            IssueKind::Overlap => "overlap",
+            IssueKind::InvertedRange => "inverted range",
+            IssueKind::ColumnLength => "column length",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single integrity violation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    /// Kind of violation
+    pub kind: IssueKind,
+    /// Row at which the violation was detected
+    pub row: usize,
+    /// Human-readable description
+    pub message: String,
+}
+
+/// Integrity report of a range table
+///
+/// Addresses are reported as `u128` so that IPv4 and IPv6 tables share the
+/// same report type.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    /// Number of rows in the table
+    pub rows: usize,
+    /// Number of ranges starting before the previous range
+    pub unsorted: usize,
+    /// Number of ranges starting inside the previous range
+    pub overlaps: usize,
+    /// Number of ranges with `start > end`
+    pub inverted: usize,
+    /// Number of columns whose length differs from the table length
+    pub column_mismatches: usize,
+    /// First [`MAX_REPORTED_ISSUES`] violations, in row order
+    pub issues: Vec<ValidationIssue>,
+    /// Number of unassigned gaps between consecutive ranges
+    pub gaps: usize,
+    /// Number of addresses inside those gaps
+    #[serde(serialize_with = "serialize_u128")]
+    pub gap_addresses: u128,
+    /// Largest gap as an inclusive `(start, end)` pair
+    pub largest_gap: Option<(u128, u128)>,
+    /// Number of addresses covered by the ranges
+    #[serde(serialize_with = "serialize_u128")]
+    pub covered_addresses: u128,
+}
+
+impl ValidationReport {
+    /// Check if the table has no integrity violations
+    ///
+    /// Gaps are informational and do not make a table invalid.
+    pub fn is_valid(&self) -> bool {
+        self.issue_count() == 0
+    }
+
+    /// Get the total number of violations
+    pub fn issue_count(&self) -> usize {
+        self.unsorted + self.overlaps + self.inverted + self.column_mismatches
+    }
+
+    fn push(&mut self, kind: IssueKind, row: usize, message: String) {
+        match kind {
+            IssueKind::Unsorted => self.unsorted += 1,
+            IssueKind::Overlap => self.overlaps += 1,
+            IssueKind::InvertedRange => self.inverted += 1,
+            IssueKind::ColumnLength => self.column_mismatches += 1,
+        }
+        if self.issues.len() < MAX_REPORTED_ISSUES {
+            self.issues.push(ValidationIssue { kind, row, message });
+        }
+    }
+}
+
+/// Check sorted start/end columns and the lengths of the other columns
+///
+/// `columns` lists `(name, length)` of every column besides start/end;
+/// `format` renders an address for messages.
+pub(crate) fn validate_ranges<A>(
+    starts: &[A],
+    ends: &[A],
+    len: usize,
+    columns: &[(&str, usize)],
+    format: impl Fn(A) -> String,
+) -> ValidationReport
+where
+    A: Copy + Ord + Into<u128>,
+{
+    let mut report = ValidationReport {
+        rows: len,
+        ..Default::default()
+    };
+
+    for &(name, column_len) in [("start_ip", starts.len()), ("end_ip", ends.len())]
+        .iter()
+        .chain(columns)
+    {
+        if column_len != len {
+            report.push(
+                IssueKind::ColumnLength,
+                column_len.min(len),
+                format!(
+                    "column '{}' has {} rows, expected {}",
+                    name, column_len, len
+                ),
+            );
+        }
+    }
+
+    let rows = starts.len().min(ends.len()).min(len);
+    // Range reaching furthest so far: a nested range must not hide the end
+    // of the range it sits in from the overlap and gap checks
+    let mut furthest: Option<(A, A)> = None;
+    for row in 0..rows {
+        let (start, end) = (starts[row], ends[row]);
+
+        if start > end {
+            report.push(
+                IssueKind::InvertedRange,
+                row,
+                format!("range {}-{} has start > end", format(start), format(end)),
+            );
+            // Covers nothing, so it neither overlaps nor closes a gap
+            continue;
+        }
+        report.covered_addresses = report
+            .covered_addresses
+            .saturating_add((end.into() - start.into()).saturating_add(1));
+
+        if row > 0 && start < starts[row - 1] {
+            let (prev_start, prev_end) = (starts[row - 1], ends[row - 1]);
+            report.push(
+                IssueKind::Unsorted,
+                row,
+                format!(
+                    "range {}-{} starts before previous range {}-{}",
+                    format(start),
+                    format(end),
+                    format(prev_start),
+                    format(prev_end)
+                ),
+            );
+        } else if let Some((max_start, max_end)) = furthest {
+            if start <= max_end {
+                report.push(
+                    IssueKind::Overlap,
+                    row,
+                    format!(
+                        "range {}-{} overlaps range {}-{}",
+                        format(start),
+                        format(end),
+                        format(max_start),
+                        format(max_end)
+                    ),
+                );
+            } else if start.into() > max_end.into() + 1 {
+                let gap = (max_end.into() + 1, start.into() - 1);
+                report.gaps += 1;
+                report.gap_addresses = report.gap_addresses.saturating_add(gap.1 - gap.0 + 1);
+                if report
+                    .largest_gap
+                    .map_or(true, |(s, e)| gap.1 - gap.0 > e - s)
+                {
+                    report.largest_gap = Some(gap);
+                }
+            }
+        }
+
+        if furthest.map_or(true, |(_, max_end)| end > max_end) {
+            furthest = Some((start, end));
+        }
+    }
+
+    report
+}
+
+/// u128 does not fit in a JSON number, so serialize it as a string
+pub(crate) fn serialize_u128<S: serde::Serializer>(
+    value: &u128,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(starts: &[u32], ends: &[u32]) -> ValidationReport {
+        validate_ranges(starts, ends, starts.len(), &[], |ip| ip.to_string())
+    }
+
+    #[test]
+    fn test_valid_with_gaps() {
+        let report = check(&[0, 10, 11, 100], &[4, 10, 20, 100]);
+        assert!(report.is_valid());
+        assert_eq!(report.gaps, 2);
+        assert_eq!(report.gap_addresses, 5 + 79);
+        assert_eq!(report.largest_gap, Some((21, 99)));
+        assert_eq!(report.covered_addresses, 5 + 1 + 10 + 1);
+    }
+
+    #[test]
+    fn test_violations() {
+        let report = check(&[0, 5, 3, 50], &[10, 20, 4, 40]);
+        assert!(!report.is_valid());
+        assert_eq!(report.overlaps, 1);
+        assert_eq!(report.unsorted, 1);
+        assert_eq!(report.inverted, 1);
+        assert_eq!(report.issue_count(), 3);
+        assert_eq!(report.issues[0].kind, IssueKind::Overlap);
+        assert_eq!(report.issues[0].row, 1);
+        // No gap is measured from the unsorted or inverted rows
+        assert_eq!(report.gaps, 0);
+    }
+
+    #[test]
+    fn test_nested_ranges() {
+        // Both later ranges sit inside the first one
+        let report = check(&[0, 10, 30, 150], &[100, 20, 40, 160]);
+        assert_eq!(report.overlaps, 2);
+        assert_eq!(report.gaps, 1);
+        assert_eq!(report.largest_gap, Some((101, 149)));
+        assert!(report.issues[1].message.contains("overlaps range 0-100"));
+    }
+
+    #[test]
+    fn test_column_lengths() {
+        let report = validate_ranges(&[0u32, 10], &[5], 2, &[("org", 2), ("country", 1)], |ip| {
+            ip.to_string()
+        });
+        assert_eq!(report.column_mismatches, 2);
+        assert!(report.issues[0].message.contains("'end_ip' has 1 rows"));
+    }
+
+    #[test]
+    fn test_issue_cap() {
+        let starts: Vec<u32> = (0..500).map(|i| 1000 - i).collect();
+        let ends = starts.clone();
+        let report = check(&starts, &ends);
+        assert_eq!(report.unsorted, 499);
+        assert_eq!(report.issues.len(), MAX_REPORTED_ISSUES);
+    }
+}
@@ -0,0 +1,79 @@
+use rasn_arrow::IpRangeTableV6;
+use std::net::Ipv6Addr;
+use std::path::Path;
+
+fn find_data() -> Option<&'static Path> {
+    [
+        Path::new("data/arrow/ip2asn-v6.parquet"),
+        Path::new("../../data/arrow/ip2asn-v6.parquet"),
+    ]
+    .into_iter()
+    .find(|p| p.exists())
+}
+
+fn ip(s: &str) -> u128 {
+    u128::from(s.parse::<Ipv6Addr>().unwrap())
+}
+
+#[test]
+fn test_load_real_parquet_file() {
+    let Some(path) = find_data() else {
+        eprintln!("Skipping test: real IPv6 data not found");
+        return;
+    };
+
+    let table = IpRangeTableV6::from_parquet(path).expect("Failed to load real Parquet file");
+
+    // The file has multiple row groups; every row must be loaded
+    assert!(
+        table.len() > 1024,
+        "Table should contain all record batches"
+    );
+
+    println!("✓ Loaded {} IPv6 ranges", table.len());
+}
+
+#[test]
+fn test_lookup_google_dns() {
+    let Some(path) = find_data() else {
+        eprintln!("Skipping test: real IPv6 data not found");
+        return;
+    };
+
+    let table = IpRangeTableV6::from_parquet(path).expect("Failed to load");
+
+    // 2001:4860:4860::8888 (Google DNS)
+    if let Some(info) = table.find_ip(ip("2001:4860:4860::8888")) {
+        println!(
+            "✓ Found 2001:4860:4860::8888: ASN {} ({})",
+            info.asn, info.organization
+        );
+        assert_eq!(info.asn.0, 15169, "Google ASN should be 15169");
+    } else {
+        panic!("Should find 2001:4860:4860::8888");
+    }
+}
+
+#[test]
+fn test_lookup_cloudflare() {
+    let Some(path) = find_data() else {
+        return;
+    };
+
+    let table = IpRangeTableV6::from_parquet(path).expect("Failed to load");
+
+    // 2606:4700:4700::1111 (Cloudflare DNS)
+    if let Some(info) = table.find_ip(ip("2606:4700:4700::1111")) {
+        println!(
+            "✓ Found 2606:4700:4700::1111: ASN {} ({})",
+            info.asn, info.organization
+        );
+        assert_eq!(info.asn.0, 13335, "Cloudflare ASN should be 13335");
+    }
+}
+
+#[test]
+fn test_from_parquet_missing_file() {
+    let result = IpRangeTableV6::from_parquet(Path::new("does/not/exist.parquet"));
+    assert!(result.is_err());
+}
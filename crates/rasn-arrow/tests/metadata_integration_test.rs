@@ -0,0 +1,30 @@
+use rasn_arrow::AsnMetadataTable;
+use rasn_core::Asn;
+use std::path::Path;
+
+fn find_data() -> Option<&'static Path> {
+    [
+        Path::new("data/arrow/asn-metadata.parquet"),
+        Path::new("../../data/arrow/asn-metadata.parquet"),
+    ]
+    .into_iter()
+    .find(|p| p.exists())
+}
+
+#[test]
+fn test_load_real_metadata() {
+    let Some(path) = find_data() else {
+        eprintln!("Skipping test: real ASN metadata not found");
+        return;
+    };
+
+    let table = AsnMetadataTable::from_parquet(path).expect("Failed to load ASN metadata");
+    assert!(
+        table.len() > 1024,
+        "Table should contain all record batches"
+    );
+
+    let meta = table.get(Asn(15169)).expect("Should find AS15169");
+    println!("✓ Found AS15169: {} ({})", meta.handle, meta.description);
+    assert_eq!(meta.handle, "GOOGLE");
+}
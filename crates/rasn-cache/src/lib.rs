@@ -27,6 +27,7 @@
 //!     organization: "Google".to_string(),
 //!     country: Some("US".to_string()),
 //!     description: Some("Google LLC".to_string()),
+//!     handle: None,
 //! };
 //!
 //! // Set with 5 minute TTL
@@ -44,7 +45,8 @@
 //! ```
 
 use lru::LruCache;
-use rasn_core::AsnInfo;
+use rasn_core::{AsnInfo, AsnStore, AsyncAsnLookup, BoxFuture, LookupAnswer, LookupSource};
+use std::net::IpAddr;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -61,6 +63,9 @@ pub enum CacheError {
 
 pub type Result<T> = std::result::Result<T, CacheError>;
 
+/// Time to live of entries written by a [`rasn_core::LookupChain`]
+pub const DEFAULT_TTL: Duration = Duration::from_secs(300);
+
 /// Cached value with TTL
 #[derive(Clone, Debug)]
 struct CachedValue {
@@ -237,6 +242,30 @@ impl CacheLayer {
     }
 }
 
+/// Cache tier of a lookup chain, keyed by the IP address string
+impl AsyncAsnLookup for CacheLayer {
+    fn source(&self) -> LookupSource {
+        LookupSource::Cache
+    }
+
+    fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, rasn_core::Result<Option<LookupAnswer>>> {
+        Box::pin(async move {
+            let cached = self.get(&ip.to_string()).await;
+            Ok(cached.map(|info| LookupAnswer::new(info, LookupSource::Cache)))
+        })
+    }
+}
+
+/// Keeps write-backs for [`DEFAULT_TTL`]
+impl AsnStore for CacheLayer {
+    fn store(&self, ip: IpAddr, info: AsnInfo) -> BoxFuture<'_, rasn_core::Result<()>> {
+        Box::pin(async move {
+            self.set(&ip.to_string(), info, DEFAULT_TTL).await;
+            Ok(())
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,6 +277,7 @@ mod tests {
             organization: "Google".to_string(),
             country: Some("US".to_string()),
             description: Some("Google LLC".to_string()),
+            handle: None,
         }
     }
 
@@ -381,4 +411,18 @@ mod tests {
         assert!(cache.get("key2").await.is_some());
         assert!(cache.get("key3").await.is_some());
     }
+
+    #[tokio::test]
+    async fn test_lookup_tier() {
+        let cache = CacheLayer::new(100).unwrap();
+        let ip: IpAddr = "8.8.8.8".parse().unwrap();
+
+        assert!(AsyncAsnLookup::lookup(&cache, ip).await.unwrap().is_none());
+
+        cache.store(ip, test_asn_info()).await.unwrap();
+        let answer = AsyncAsnLookup::lookup(&cache, ip).await.unwrap().unwrap();
+        assert_eq!(answer.info.asn, Asn(15169));
+        assert_eq!(answer.source, LookupSource::Cache);
+        assert!(cache.get("8.8.8.8").await.is_some());
+    }
 }
@@ -0,0 +1,246 @@
+//! IPv6 CIDR blocks
+//!
+//! Addresses are parsed with the standard library, which accepts every
+//! RFC 4291 text form (`::` compression, embedded IPv4 such as
+//! `::ffff:192.0.2.1`), and displayed in the RFC 5952 canonical form:
+//! lowercase, leading zeros dropped, the longest run of zero groups
+//! compressed to `::`.
+
+use crate::{CidrError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::Ipv6Addr;
+
+/// IPv6 CIDR block representation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cidr6 {
+    /// Network address (base IP)
+    network: u128,
+    /// Prefix length (0-128)
+    prefix_len: u8,
+    /// Network mask
+    mask: u128,
+}
+
+impl Cidr6 {
+    /// Parse IPv6 CIDR notation string
+    ///
+    /// # Arguments
+    ///
+    /// * `cidr` - CIDR string (e.g., "2001:db8::/32")
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasn_cidr::Cidr6;
+    ///
+    /// let cidr = Cidr6::parse("2001:DB8:0:0::1/64").unwrap();
+    /// assert_eq!(cidr.prefix_len(), 64);
+    /// assert_eq!(cidr.to_string(), "2001:db8::/64");
+    /// ```
+    pub fn parse(cidr: &str) -> Result<Self> {
+        let Some((ip_str, prefix_str)) = cidr.split_once('/') else {
+            return Err(CidrError::InvalidNotation(
+                "Expected format: x:x::x/prefix".to_string(),
+            ));
+        };
+
+        let prefix_len: u8 = prefix_str
+            .parse()
+            .map_err(|_| CidrError::InvalidNotation(format!("Invalid prefix: {}", prefix_str)))?;
+
+        let ip: Ipv6Addr = ip_str
+            .parse()
+            .map_err(|_| CidrError::InvalidIpAddress(ip_str.to_string()))?;
+
+        Self::new(ip.into(), prefix_len)
+    }
+
+    /// Create new CIDR from network address and prefix length
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - Network address as u128
+    /// * `prefix_len` - Prefix length (0-128)
+    pub fn new(network: u128, prefix_len: u8) -> Result<Self> {
+        if prefix_len > 128 {
+            return Err(CidrError::InvalidPrefixLength(prefix_len));
+        }
+
+        Ok(Self::from_prefix(network, prefix_len))
+    }
+
+    /// Create a block from a prefix length already known to be 0-128
+    pub(crate) fn from_prefix(network: u128, prefix_len: u8) -> Self {
+        let mask = if prefix_len == 0 {
+            0
+        } else {
+            u128::MAX << (128 - prefix_len)
+        };
+
+        Self {
+            network: network & mask,
+            prefix_len,
+            mask,
+        }
+    }
+
+    /// Get network address
+    pub fn network(&self) -> u128 {
+        self.network
+    }
+
+    /// Get prefix length
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Get network mask
+    pub fn mask(&self) -> u128 {
+        self.mask
+    }
+
+    /// Get first address (the network address)
+    ///
+    /// IPv6 has no broadcast address, so every address of the block is
+    /// usable.
+    pub fn first(&self) -> u128 {
+        self.network
+    }
+
+    /// Get last address, the IPv6 counterpart of the broadcast address
+    pub fn last(&self) -> u128 {
+        self.network | !self.mask
+    }
+
+    /// Get total number of IPs in this CIDR block
+    ///
+    /// `::/0` holds 2^128 addresses, one more than a u128 can count; its
+    /// size is reported as `u128::MAX`.
+    pub fn size(&self) -> u128 {
+        (self.last() - self.network).saturating_add(1)
+    }
+
+    /// Check if IP address is in this CIDR block
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - IP address as u128
+    pub fn contains(&self, ip: u128) -> bool {
+        (ip & self.mask) == self.network
+    }
+
+    /// Check if another CIDR block lies entirely inside this one
+    pub fn contains_cidr(&self, other: &Cidr6) -> bool {
+        self.prefix_len <= other.prefix_len && self.contains(other.network)
+    }
+
+    /// Check if this block shares any address with another block
+    pub fn overlaps(&self, other: &Cidr6) -> bool {
+        self.contains_cidr(other) || other.contains_cidr(self)
+    }
+
+    /// Get iterator over all IPs in this CIDR block
+    ///
+    /// Note: Anything shorter than a /64 holds more addresses than can ever
+    /// be visited; iterate small blocks only.
+    pub fn iter(&self) -> Cidr6Iterator {
+        Cidr6Iterator {
+            next: Some(self.network),
+            end: self.last(),
+        }
+    }
+}
+
+impl fmt::Display for Cidr6 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", Ipv6Addr::from(self.network), self.prefix_len)
+    }
+}
+
+/// Iterator over IPs in an IPv6 CIDR block
+pub struct Cidr6Iterator {
+    next: Option<u128>,
+    end: u128,
+}
+
+impl Iterator for Cidr6Iterator {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ip = self.next?;
+        self.next = if ip < self.end { Some(ip + 1) } else { None };
+        Some(ip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cidr6() {
+        let cidr = Cidr6::parse("2001:db8::/32").unwrap();
+        assert_eq!(cidr.network(), 0x2001_0db8 << 96);
+        assert_eq!(cidr.prefix_len(), 32);
+
+        // Host bits are masked off
+        let cidr = Cidr6::parse("2001:db8:1234::1/48").unwrap();
+        assert_eq!(cidr.to_string(), "2001:db8:1234::/48");
+
+        let mapped = Cidr6::parse("::ffff:192.0.2.1/128").unwrap();
+        assert_eq!(mapped.network(), 0xffff_c000_0201);
+
+        assert!(Cidr6::parse("2001:db8::").is_err());
+        assert!(Cidr6::parse("2001:db8::/129").is_err());
+        assert!(Cidr6::parse("2001:db8:::1/64").is_err());
+        assert!(Cidr6::parse("192.168.0.0/24").is_err());
+    }
+
+    #[test]
+    fn test_cidr6_display_canonical() {
+        for (input, canonical) in [
+            (
+                "2001:0DB8:0000:0000:0000:0000:0000:0000/32",
+                "2001:db8::/32",
+            ),
+            ("2001:db8:0:0:1:0:0:0/80", "2001:db8:0:0:1::/80"),
+            ("2001:db8:0:1:1:1:1:1/128", "2001:db8:0:1:1:1:1:1/128"),
+            ("0:0:0:0:0:0:0:0/0", "::/0"),
+            ("::1/128", "::1/128"),
+        ] {
+            assert_eq!(Cidr6::parse(input).unwrap().to_string(), canonical);
+        }
+    }
+
+    #[test]
+    fn test_cidr6_range() {
+        let cidr = Cidr6::parse("2001:db8::/64").unwrap();
+        assert_eq!(cidr.first(), cidr.network());
+        assert_eq!(cidr.last(), cidr.network() | u128::from(u64::MAX));
+        assert_eq!(cidr.size(), 1 << 64);
+        assert!(cidr.contains(cidr.network() + 12345));
+        assert!(!cidr.contains(cidr.last() + 1));
+
+        assert_eq!(Cidr6::parse("::/0").unwrap().size(), u128::MAX);
+        assert_eq!(Cidr6::parse("::1/128").unwrap().size(), 1);
+
+        let outer = Cidr6::parse("2001:db8::/32").unwrap();
+        assert!(outer.contains_cidr(&cidr) && outer.overlaps(&cidr));
+        assert!(!cidr.contains_cidr(&outer) && cidr.overlaps(&outer));
+        assert!(!cidr.overlaps(&Cidr6::parse("2001:db8:0:1::/64").unwrap()));
+    }
+
+    #[test]
+    fn test_cidr6_iterator() {
+        let ips: Vec<u128> = Cidr6::parse("2001:db8::/126").unwrap().iter().collect();
+        assert_eq!(ips.len(), 4);
+        assert_eq!(ips[3], (0x2001_0db8 << 96) | 3);
+
+        let last: Vec<u128> = Cidr6::parse("ffff:ffff:ffff:ffff:ffff:ffff:ffff:fffe/127")
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(last, vec![u128::MAX - 1, u128::MAX]);
+    }
+}
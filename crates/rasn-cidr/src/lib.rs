@@ -1,10 +1,12 @@
 //! CIDR operations and IP range queries
 //!
 //! Provides utilities for working with CIDR notation and IP ranges:
-//! - Parse CIDR notation (e.g., "192.168.0.0/24")
+//! - Parse CIDR notation (e.g., "192.168.0.0/24", "2001:db8::/32")
 //! - Generate IP iterators for ranges
 //! - Check if IP is in CIDR block
-//! - Range calculations
+//! - Range calculations, range ↔ minimal CIDR list conversion
+//! - Set algebra over CIDR lists (aggregate, supernet, split, intersect, subtract)
+//! - IANA special-purpose registry classification (private, CGNAT, documentation, ...)
 //!
 //! # Examples
 //!
@@ -18,10 +20,26 @@
 //! assert!(!cidr.contains(0xC0A80001)); // 192.168.0.1
 //! ```
 
+pub mod ipv6;
+pub mod range;
+pub mod set;
+pub mod special;
+
+pub use ipv6::{Cidr6, Cidr6Iterator};
+pub use range::{
+    cidrs_to_ranges, cidrs_to_ranges6, ip_range_to_cidrs, range_to_cidrs, range_to_cidrs6,
+};
+pub use set::{overlapping_pairs, CidrSet};
+pub use special::{classify, AddressClass, SpecialPurpose, SPECIAL_PURPOSE};
+
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::net::IpAddr;
 use thiserror::Error;
 
+/// Maximum number of subnets [`Cidr::split`] produces
+pub const MAX_SPLIT: u64 = 65_536;
+
 /// CIDR errors
 #[derive(Error, Debug)]
 pub enum CidrError {
@@ -34,12 +52,20 @@ pub enum CidrError {
     InvalidIpAddress(String),
 
     /// Invalid prefix length
-    #[error("Invalid prefix length: {0} (must be 0-32)")]
+    #[error("Invalid prefix length: {0} (must be 0-32 for IPv4, 0-128 for IPv6)")]
     InvalidPrefixLength(u8),
 
     /// Range too large
     #[error("CIDR range too large: /{0} (use smaller prefix)")]
     RangeTooLarge(u8),
+
+    /// Invalid address range
+    #[error("Invalid IP range: {0}")]
+    InvalidRange(String),
+
+    /// Split prefix shorter than the block's own prefix
+    #[error("Cannot split /{0} into /{1} subnets")]
+    InvalidSplit(u8, u8),
 }
 
 pub type Result<T> = std::result::Result<T, CidrError>;
@@ -139,17 +165,22 @@ impl Cidr {
             return Err(CidrError::InvalidPrefixLength(prefix_len));
         }
 
+        Ok(Self::from_prefix(network, prefix_len))
+    }
+
+    /// Create a block from a prefix length already known to be 0-32
+    fn from_prefix(network: u32, prefix_len: u8) -> Self {
         let mask = if prefix_len == 0 {
             0
         } else {
             !((1u64 << (32 - prefix_len)) - 1) as u32
         };
 
-        Ok(Self {
+        Self {
             network: network & mask,
             prefix_len,
             mask,
-        })
+        }
     }
 
     /// Get network address
@@ -208,6 +239,58 @@ impl Cidr {
         (ip & self.mask) == self.network
     }
 
+    /// Check if another CIDR block lies entirely inside this one
+    pub fn contains_cidr(&self, other: &Cidr) -> bool {
+        self.prefix_len <= other.prefix_len && self.contains(other.network)
+    }
+
+    /// Check if this block shares any address with another block
+    ///
+    /// CIDR blocks either nest or are disjoint, so two blocks overlap
+    /// exactly when one contains the other.
+    pub fn overlaps(&self, other: &Cidr) -> bool {
+        self.contains_cidr(other) || other.contains_cidr(self)
+    }
+
+    /// Split this block into subnets of a longer prefix
+    ///
+    /// Fails if `new_prefix` is shorter than this block's prefix or longer
+    /// than 32, or if the split would produce more than [`MAX_SPLIT`]
+    /// subnets.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_prefix` - Prefix length of the subnets
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasn_cidr::Cidr;
+    ///
+    /// let cidr = Cidr::parse("192.168.0.0/24").unwrap();
+    /// let subnets = cidr.split(26).unwrap();
+    /// assert_eq!(subnets.len(), 4);
+    /// assert_eq!(subnets[1].to_string(), "192.168.0.64/26");
+    /// ```
+    pub fn split(&self, new_prefix: u8) -> Result<Vec<Cidr>> {
+        if new_prefix > 32 {
+            return Err(CidrError::InvalidPrefixLength(new_prefix));
+        }
+        if new_prefix < self.prefix_len {
+            return Err(CidrError::InvalidSplit(self.prefix_len, new_prefix));
+        }
+
+        let count = 1u64 << (new_prefix - self.prefix_len);
+        if count > MAX_SPLIT {
+            return Err(CidrError::RangeTooLarge(new_prefix));
+        }
+
+        let step = 1u64 << (32 - new_prefix);
+        Ok((0..count)
+            .map(|i| Self::from_prefix((self.network as u64 + i * step) as u32, new_prefix))
+            .collect())
+    }
+
     /// Get iterator over all IPs in this CIDR block
     ///
     /// Note: For large blocks (e.g., /8), this may be very slow.
@@ -231,6 +314,113 @@ impl fmt::Display for Cidr {
     }
 }
 
+/// CIDR block of either address family
+///
+/// # Examples
+///
+/// ```
+/// use rasn_cidr::IpCidr;
+///
+/// let v6 = IpCidr::parse("2001:4860::/32").unwrap();
+/// assert!(v6.contains("2001:4860:4860::8888".parse().unwrap()));
+/// assert!(!v6.contains("8.8.8.8".parse().unwrap()));
+///
+/// let v4 = IpCidr::parse("8.8.8.0/24").unwrap();
+/// assert_eq!(v4.size(), 256);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IpCidr {
+    /// IPv4 block
+    V4(Cidr),
+    /// IPv6 block
+    V6(Cidr6),
+}
+
+impl IpCidr {
+    /// Parse IPv4 or IPv6 CIDR notation
+    ///
+    /// The family is taken from the address: anything containing `:` is
+    /// IPv6.
+    pub fn parse(cidr: &str) -> Result<Self> {
+        if cidr.contains(':') {
+            Cidr6::parse(cidr).map(IpCidr::V6)
+        } else {
+            Cidr::parse(cidr).map(IpCidr::V4)
+        }
+    }
+
+    /// Check if this is an IPv6 block
+    pub fn is_ipv6(&self) -> bool {
+        matches!(self, IpCidr::V6(_))
+    }
+
+    /// Get prefix length
+    pub fn prefix_len(&self) -> u8 {
+        match self {
+            IpCidr::V4(cidr) => cidr.prefix_len(),
+            IpCidr::V6(cidr) => cidr.prefix_len(),
+        }
+    }
+
+    /// Get first address (the network address)
+    pub fn first(&self) -> IpAddr {
+        match self {
+            IpCidr::V4(cidr) => IpAddr::from(cidr.network().to_be_bytes()),
+            IpCidr::V6(cidr) => IpAddr::from(cidr.first().to_be_bytes()),
+        }
+    }
+
+    /// Get last address (the broadcast address for IPv4)
+    pub fn last(&self) -> IpAddr {
+        match self {
+            IpCidr::V4(cidr) => IpAddr::from(cidr.broadcast().to_be_bytes()),
+            IpCidr::V6(cidr) => IpAddr::from(cidr.last().to_be_bytes()),
+        }
+    }
+
+    /// Get total number of IPs in this CIDR block
+    ///
+    /// Saturates at `u128::MAX` for `::/0`, see [`Cidr6::size`].
+    pub fn size(&self) -> u128 {
+        match self {
+            IpCidr::V4(cidr) => cidr.size().into(),
+            IpCidr::V6(cidr) => cidr.size(),
+        }
+    }
+
+    /// Check if IP address is in this CIDR block
+    ///
+    /// Addresses of the other family are never contained.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self, ip) {
+            (IpCidr::V4(cidr), IpAddr::V4(ip)) => cidr.contains(ip.into()),
+            (IpCidr::V6(cidr), IpAddr::V6(ip)) => cidr.contains(ip.into()),
+            _ => false,
+        }
+    }
+}
+
+impl From<Cidr> for IpCidr {
+    fn from(cidr: Cidr) -> Self {
+        IpCidr::V4(cidr)
+    }
+}
+
+impl From<Cidr6> for IpCidr {
+    fn from(cidr: Cidr6) -> Self {
+        IpCidr::V6(cidr)
+    }
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpCidr::V4(cidr) => cidr.fmt(f),
+            IpCidr::V6(cidr) => cidr.fmt(f),
+        }
+    }
+}
+
 /// Iterator over IPs in a CIDR block
 pub struct CidrIterator {
     current: u64,
@@ -345,6 +535,86 @@ mod tests {
         assert_eq!(cidr.prefix_len(), 24);
     }
 
+    #[test]
+    fn test_cidr_contains_and_overlaps() {
+        let outer = Cidr::parse("192.168.0.0/23").unwrap();
+        let inner = Cidr::parse("192.168.1.0/24").unwrap();
+        let other = Cidr::parse("192.168.2.0/24").unwrap();
+
+        assert!(outer.contains_cidr(&inner));
+        assert!(!inner.contains_cidr(&outer));
+        assert!(outer.overlaps(&inner) && inner.overlaps(&outer));
+        assert!(!outer.overlaps(&other));
+        assert!(Cidr::parse("0.0.0.0/0").unwrap().contains_cidr(&other));
+    }
+
+    #[test]
+    fn test_cidr_split() {
+        let cidr = Cidr::parse("192.168.0.0/24").unwrap();
+        let subnets: Vec<String> = cidr
+            .split(26)
+            .unwrap()
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            subnets,
+            vec![
+                "192.168.0.0/26",
+                "192.168.0.64/26",
+                "192.168.0.128/26",
+                "192.168.0.192/26"
+            ]
+        );
+
+        assert_eq!(cidr.split(24).unwrap(), vec![cidr]);
+        assert_eq!(
+            Cidr::parse("255.255.255.254/31")
+                .unwrap()
+                .split(32)
+                .unwrap()[1]
+                .network(),
+            u32::MAX
+        );
+        assert!(matches!(
+            cidr.split(23),
+            Err(CidrError::InvalidSplit(24, 23))
+        ));
+        assert!(matches!(
+            cidr.split(33),
+            Err(CidrError::InvalidPrefixLength(33))
+        ));
+        assert!(matches!(
+            Cidr::parse("10.0.0.0/8").unwrap().split(32),
+            Err(CidrError::RangeTooLarge(32))
+        ));
+    }
+
+    #[test]
+    fn test_ip_cidr() {
+        let v4 = IpCidr::parse("192.168.1.0/24").unwrap();
+        assert!(!v4.is_ipv6());
+        assert_eq!(v4.first(), "192.168.1.0".parse::<IpAddr>().unwrap());
+        assert_eq!(v4.last(), "192.168.1.255".parse::<IpAddr>().unwrap());
+        assert_eq!(v4.to_string(), "192.168.1.0/24");
+
+        let v6 = IpCidr::parse("2001:db8::/32").unwrap();
+        assert!(v6.is_ipv6());
+        assert_eq!(v6.prefix_len(), 32);
+        assert_eq!(
+            v6.last(),
+            "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff"
+                .parse::<IpAddr>()
+                .unwrap()
+        );
+        assert_eq!(v6.size(), 1 << 96);
+        assert!(v6.contains("2001:db8::1".parse().unwrap()));
+        assert!(!v6.contains("0.0.0.1".parse().unwrap()));
+
+        assert!(IpCidr::parse("2001:db8::/200").is_err());
+        assert!(IpCidr::parse("not a cidr").is_err());
+    }
+
     #[test]
     fn test_cidr_slash_32() {
         let cidr = Cidr::parse("192.168.1.1/32").unwrap();
@@ -0,0 +1,248 @@
+//! Address range ↔ CIDR list conversion
+//!
+//! Data sources describe allocations as inclusive `start`–`end` ranges
+//! (iptoasn rows, cold storage), while firewall configs and routing want
+//! CIDR prefixes. [`range_to_cidrs`] and [`range_to_cidrs6`] split a range
+//! into the fewest blocks covering exactly it; [`cidrs_to_ranges`] and
+//! [`cidrs_to_ranges6`] go back, merging overlapping and adjacent blocks
+//! into the fewest ranges.
+
+use crate::{Cidr, Cidr6, CidrError, CidrSet, IpCidr, Result};
+use std::net::IpAddr;
+
+/// Split an inclusive IPv4 range into the minimal list of CIDR blocks
+///
+/// Returns an empty list when `start > end`.
+///
+/// # Arguments
+///
+/// * `start` - First address of the range as u32
+/// * `end` - Last address of the range as u32
+///
+/// # Examples
+///
+/// ```
+/// use rasn_cidr::range_to_cidrs;
+///
+/// // 10.0.0.1 - 10.0.0.4
+/// let cidrs: Vec<String> = range_to_cidrs(0x0A000001, 0x0A000004)
+///     .iter()
+///     .map(|c| c.to_string())
+///     .collect();
+/// assert_eq!(cidrs, vec!["10.0.0.1/32", "10.0.0.2/31", "10.0.0.4/32"]);
+/// ```
+pub fn range_to_cidrs(start: u32, end: u32) -> Vec<Cidr> {
+    blocks(start.into(), end.into(), 32)
+        .into_iter()
+        .map(|(network, prefix_len)| Cidr::from_prefix(network as u32, prefix_len))
+        .collect()
+}
+
+/// Split an inclusive IPv6 range into the minimal list of CIDR blocks
+///
+/// Returns an empty list when `start > end`.
+///
+/// # Arguments
+///
+/// * `start` - First address of the range as u128
+/// * `end` - Last address of the range as u128
+pub fn range_to_cidrs6(start: u128, end: u128) -> Vec<Cidr6> {
+    blocks(start, end, 128)
+        .into_iter()
+        .map(|(network, prefix_len)| Cidr6::from_prefix(network, prefix_len))
+        .collect()
+}
+
+/// Split an inclusive range of either family into the minimal CIDR list
+///
+/// Fails if the addresses are of different families or `start > end`.
+///
+/// # Examples
+///
+/// ```
+/// use rasn_cidr::ip_range_to_cidrs;
+///
+/// let cidrs = ip_range_to_cidrs(
+///     "2001:db8::".parse().unwrap(),
+///     "2001:db8::1:ffff".parse().unwrap(),
+/// )
+/// .unwrap();
+/// assert_eq!(cidrs[0].to_string(), "2001:db8::/111");
+/// ```
+pub fn ip_range_to_cidrs(start: IpAddr, end: IpAddr) -> Result<Vec<IpCidr>> {
+    let cidrs: Vec<IpCidr> = match (start, end) {
+        (IpAddr::V4(s), IpAddr::V4(e)) if s <= e => range_to_cidrs(s.into(), e.into())
+            .into_iter()
+            .map(IpCidr::V4)
+            .collect(),
+        (IpAddr::V6(s), IpAddr::V6(e)) if s <= e => range_to_cidrs6(s.into(), e.into())
+            .into_iter()
+            .map(IpCidr::V6)
+            .collect(),
+        (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_)) => {
+            return Err(CidrError::InvalidRange(format!(
+                "{} is after {}",
+                start, end
+            )))
+        }
+        _ => {
+            return Err(CidrError::InvalidRange(format!(
+                "{} and {} are of different address families",
+                start, end
+            )))
+        }
+    };
+
+    Ok(cidrs)
+}
+
+/// Merge IPv4 CIDR blocks into the minimal list of inclusive ranges
+///
+/// The result is sorted; overlapping and adjacent blocks become one range.
+pub fn cidrs_to_ranges(cidrs: &[Cidr]) -> Vec<(u32, u32)> {
+    cidrs.iter().copied().collect::<CidrSet>().ranges().to_vec()
+}
+
+/// Merge IPv6 CIDR blocks into the minimal list of inclusive ranges
+///
+/// The result is sorted; overlapping and adjacent blocks become one range.
+pub fn cidrs_to_ranges6(cidrs: &[Cidr6]) -> Vec<(u128, u128)> {
+    let mut ranges: Vec<(u128, u128)> = cidrs
+        .iter()
+        .map(|cidr| (cidr.first(), cidr.last()))
+        .collect();
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(u128, u128)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
+}
+
+/// Split an inclusive range into `(network, prefix_len)` blocks
+///
+/// Works for both families: `bits` is the address width (32 or 128) and
+/// IPv4 addresses are passed widened to u128.
+fn blocks(start: u128, end: u128, bits: u32) -> Vec<(u128, u8)> {
+    // Offset of the last address in a block of 2^size_bits addresses
+    let block_last = |size_bits: u32| {
+        if size_bits >= 128 {
+            u128::MAX
+        } else {
+            (1u128 << size_bits) - 1
+        }
+    };
+
+    let mut blocks = Vec::new();
+    let mut start = start;
+
+    while start <= end {
+        // Largest block aligned at `start` that still fits before `end`
+        let mut size_bits = start.trailing_zeros().min(bits);
+        while size_bits > 0 && end - start < block_last(size_bits) {
+            size_bits -= 1;
+        }
+
+        blocks.push((start, (bits - size_bits) as u8));
+
+        let last = start + block_last(size_bits);
+        if last >= end {
+            break;
+        }
+        start = last + 1;
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings<T: ToString>(cidrs: &[T]) -> Vec<String> {
+        cidrs.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_range_to_cidrs() {
+        assert_eq!(
+            strings(&range_to_cidrs(0x08080800, 0x080808FF)),
+            vec!["8.8.8.0/24"]
+        );
+        assert_eq!(
+            strings(&range_to_cidrs(0x0A000001, 0x0A000004)),
+            vec!["10.0.0.1/32", "10.0.0.2/31", "10.0.0.4/32"]
+        );
+        assert_eq!(strings(&range_to_cidrs(0, u32::MAX)), vec!["0.0.0.0/0"]);
+        assert_eq!(
+            strings(&range_to_cidrs(u32::MAX, u32::MAX)),
+            vec!["255.255.255.255/32"]
+        );
+        assert!(range_to_cidrs(2, 1).is_empty());
+
+        assert_eq!(strings(&range_to_cidrs6(0, u128::MAX)), vec!["::/0"]);
+        assert_eq!(
+            strings(&range_to_cidrs6(u128::MAX, u128::MAX)),
+            vec!["ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128"]
+        );
+        // 2001:db8::1 - 2001:db8::ffff
+        let base = 0x2001_0db8u128 << 96;
+        assert_eq!(
+            range_to_cidrs6(base + 1, base + 0xffff).len(),
+            16 // one block per bit below /112
+        );
+    }
+
+    #[test]
+    fn test_ip_range_to_cidrs() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+
+        assert_eq!(
+            strings(&ip_range_to_cidrs(ip("192.168.0.0"), ip("192.168.2.255")).unwrap()),
+            vec!["192.168.0.0/23", "192.168.2.0/24"]
+        );
+        assert_eq!(
+            strings(&ip_range_to_cidrs(ip("2001:db8::"), ip("2001:db9::ffff")).unwrap()),
+            vec!["2001:db8::/32", "2001:db9::/112"]
+        );
+        assert!(ip_range_to_cidrs(ip("10.0.0.2"), ip("10.0.0.1")).is_err());
+        assert!(ip_range_to_cidrs(ip("10.0.0.1"), ip("::1")).is_err());
+    }
+
+    #[test]
+    fn test_cidrs_to_ranges() {
+        let cidrs: Vec<Cidr> = ["10.0.0.2/31", "10.0.0.1/32", "10.0.0.4/32", "10.0.1.0/24"]
+            .iter()
+            .map(|s| Cidr::parse(s).unwrap())
+            .collect();
+        assert_eq!(
+            cidrs_to_ranges(&cidrs),
+            vec![(0x0A000001, 0x0A000004), (0x0A000100, 0x0A0001FF)]
+        );
+
+        let cidrs6: Vec<Cidr6> = ["2001:db9::/112", "2001:db8::/32", "ffff::/16"]
+            .iter()
+            .map(|s| Cidr6::parse(s).unwrap())
+            .collect();
+        let base = 0x2001_0db8u128 << 96;
+        assert_eq!(
+            cidrs_to_ranges6(&cidrs6),
+            vec![
+                (base, (0x2001_0db9u128 << 96) + 0xffff),
+                (0xffffu128 << 112, u128::MAX)
+            ]
+        );
+
+        // Round trip: the ranges split back into the same blocks
+        let (start, end) = cidrs_to_ranges6(&cidrs6)[0];
+        assert_eq!(
+            strings(&range_to_cidrs6(start, end)),
+            vec!["2001:db8::/32", "2001:db9::/112"]
+        );
+    }
+}
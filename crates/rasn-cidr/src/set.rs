@@ -0,0 +1,408 @@
+//! CIDR set algebra
+//!
+//! [`CidrSet`] holds an arbitrary set of IPv4 addresses as sorted, disjoint,
+//! inclusive address ranges. Building a set sorts and merges its input once
+//! (O(n log n)); union, intersection and subtraction are then linear merges
+//! of two range lists, so they stay fast on lists of hundreds of thousands
+//! of prefixes. [`CidrSet::cidrs`] turns the ranges back into the minimal
+//! list of CIDR blocks.
+
+use crate::{range_to_cidrs, Cidr};
+
+/// Set of IPv4 addresses built from CIDR blocks
+///
+/// # Examples
+///
+/// ```
+/// use rasn_cidr::{Cidr, CidrSet};
+///
+/// let set: CidrSet = ["192.168.0.0/24", "192.168.1.0/24", "10.0.0.0/8"]
+///     .iter()
+///     .map(|s| Cidr::parse(s).unwrap())
+///     .collect();
+///
+/// let aggregated: Vec<String> = set.cidrs().iter().map(|c| c.to_string()).collect();
+/// assert_eq!(aggregated, vec!["10.0.0.0/8", "192.168.0.0/23"]);
+/// assert!(set.contains(0xC0A801FF)); // 192.168.1.255
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CidrSet {
+    /// Sorted, disjoint and non-adjacent inclusive ranges
+    ranges: Vec<(u32, u32)>,
+}
+
+impl CidrSet {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a set from inclusive `(start, end)` address ranges
+    ///
+    /// Ranges may be unsorted, overlapping or adjacent; ranges with
+    /// `start > end` are ignored.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = (u32, u32)>) -> Self {
+        let mut ranges: Vec<(u32, u32)> = ranges
+            .into_iter()
+            .filter(|(start, end)| start <= end)
+            .collect();
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(u32, u32)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    /// Get the address ranges of the set, sorted and disjoint
+    pub fn ranges(&self) -> &[(u32, u32)] {
+        &self.ranges
+    }
+
+    /// Check if the set holds no address
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Get the number of addresses in the set
+    pub fn size(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| u64::from(end - start) + 1)
+            .sum()
+    }
+
+    /// Get the minimal list of CIDR blocks covering exactly this set
+    ///
+    /// This is the aggregated form of the input: overlapping blocks are
+    /// merged and adjacent blocks combined into their common supernet.
+    pub fn cidrs(&self) -> Vec<Cidr> {
+        self.ranges
+            .iter()
+            .flat_map(|&(start, end)| range_to_cidrs(start, end))
+            .collect()
+    }
+
+    /// Check if an address is in the set
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - IP address as u32
+    pub fn contains(&self, ip: u32) -> bool {
+        self.range_from(ip)
+            .first()
+            .is_some_and(|&(start, _)| start <= ip)
+    }
+
+    /// Check if every address of a CIDR block is in the set
+    pub fn contains_cidr(&self, cidr: &Cidr) -> bool {
+        self.range_from(cidr.network())
+            .first()
+            .is_some_and(|&(start, end)| start <= cidr.network() && cidr.broadcast() <= end)
+    }
+
+    /// Check if any address of a CIDR block is in the set
+    pub fn overlaps(&self, cidr: &Cidr) -> bool {
+        self.range_from(cidr.network())
+            .first()
+            .is_some_and(|&(start, _)| start <= cidr.broadcast())
+    }
+
+    /// Get the smallest CIDR block containing the whole set
+    ///
+    /// Returns `None` for an empty set.
+    pub fn supernet(&self) -> Option<Cidr> {
+        let first = self.ranges.first()?.0;
+        let last = self.ranges.last()?.1;
+        let prefix_len = (first ^ last).leading_zeros() as u8;
+
+        Some(Cidr::from_prefix(first, prefix_len))
+    }
+
+    /// Get the addresses in either set
+    pub fn union(&self, other: &CidrSet) -> CidrSet {
+        Self::from_ranges(self.ranges.iter().chain(&other.ranges).copied())
+    }
+
+    /// Get the addresses in both sets
+    pub fn intersect(&self, other: &CidrSet) -> CidrSet {
+        let (a, b) = (&self.ranges, &other.ranges);
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < a.len() && j < b.len() {
+            let start = a[i].0.max(b[j].0);
+            let end = a[i].1.min(b[j].1);
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            // The range ending first cannot meet anything further on
+            if a[i].1 < b[j].1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Get the addresses of this set that are not in `other`
+    pub fn subtract(&self, other: &CidrSet) -> CidrSet {
+        let cuts = &other.ranges;
+        let mut ranges = Vec::new();
+        let mut first_cut = 0;
+
+        for &(start, end) in &self.ranges {
+            // u64 so that the address after a cut ending at u32::MAX fits
+            let mut start = u64::from(start);
+            let end = u64::from(end);
+
+            while first_cut < cuts.len() && u64::from(cuts[first_cut].1) < start {
+                first_cut += 1;
+            }
+
+            for &(cut_start, cut_end) in cuts[first_cut..]
+                .iter()
+                .take_while(|(cut_start, _)| u64::from(*cut_start) <= end)
+            {
+                if u64::from(cut_start) > start {
+                    ranges.push((start as u32, cut_start - 1));
+                }
+                start = start.max(u64::from(cut_end) + 1);
+            }
+
+            if start <= end {
+                ranges.push((start as u32, end as u32));
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Get the ranges from the first one ending at or after `ip`
+    fn range_from(&self, ip: u32) -> &[(u32, u32)] {
+        let index = self.ranges.partition_point(|&(_, end)| end < ip);
+        &self.ranges[index..]
+    }
+}
+
+impl FromIterator<Cidr> for CidrSet {
+    fn from_iter<I: IntoIterator<Item = Cidr>>(iter: I) -> Self {
+        Self::from_ranges(
+            iter.into_iter()
+                .map(|cidr| (cidr.network(), cidr.broadcast())),
+        )
+    }
+}
+
+/// Find every pair of overlapping blocks in a CIDR list
+///
+/// Blocks either nest or are disjoint, so each pair is reported as
+/// `(outer, inner)`. Runs in O(n log n) plus the number of pairs found.
+///
+/// # Examples
+///
+/// ```
+/// use rasn_cidr::{overlapping_pairs, Cidr};
+///
+/// let cidrs: Vec<Cidr> = ["192.168.0.0/23", "192.168.1.0/24", "10.0.0.0/8"]
+///     .iter()
+///     .map(|s| Cidr::parse(s).unwrap())
+///     .collect();
+///
+/// let pairs = overlapping_pairs(&cidrs);
+/// assert_eq!(pairs.len(), 1);
+/// assert_eq!(pairs[0].1.to_string(), "192.168.1.0/24");
+/// ```
+pub fn overlapping_pairs(cidrs: &[Cidr]) -> Vec<(Cidr, Cidr)> {
+    let mut sorted = cidrs.to_vec();
+    sorted.sort_unstable_by_key(|cidr| (cidr.network(), cidr.prefix_len()));
+
+    // Blocks containing the current one, outermost first
+    let mut enclosing: Vec<Cidr> = Vec::new();
+    let mut pairs = Vec::new();
+
+    for cidr in sorted {
+        while enclosing
+            .last()
+            .is_some_and(|outer| outer.broadcast() < cidr.network())
+        {
+            enclosing.pop();
+        }
+
+        pairs.extend(enclosing.iter().map(|&outer| (outer, cidr)));
+        enclosing.push(cidr);
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(cidrs: &[&str]) -> CidrSet {
+        cidrs.iter().map(|s| Cidr::parse(s).unwrap()).collect()
+    }
+
+    fn strings(set: &CidrSet) -> Vec<String> {
+        set.cidrs().iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let aggregated = set(&[
+            "192.168.1.0/24",
+            "192.168.0.0/24",
+            "192.168.0.128/25",
+            "192.168.2.0/24",
+            "10.0.0.0/8",
+        ]);
+        assert_eq!(
+            strings(&aggregated),
+            vec!["10.0.0.0/8", "192.168.0.0/23", "192.168.2.0/24"]
+        );
+        assert_eq!(aggregated.size(), (1 << 24) + 768);
+
+        // 256 adjacent /24s collapse into one /16
+        let many: CidrSet = (0..256u32)
+            .map(|i| Cidr::new(0xAC100000 | (i << 8), 24).unwrap())
+            .collect();
+        assert_eq!(strings(&many), vec!["172.16.0.0/16"]);
+
+        assert_eq!(
+            strings(&set(&["0.0.0.0/1", "128.0.0.0/1"])),
+            vec!["0.0.0.0/0"]
+        );
+        assert!(CidrSet::new().cidrs().is_empty());
+    }
+
+    #[test]
+    fn test_from_ranges() {
+        assert_eq!(
+            strings(&CidrSet::from_ranges([(u32::MAX, u32::MAX), (5, 4)])),
+            vec!["255.255.255.255/32"]
+        );
+    }
+
+    #[test]
+    fn test_contains_and_overlaps() {
+        let set = set(&["192.168.0.0/24", "192.168.1.0/24", "10.0.0.0/8"]);
+
+        assert!(set.contains(0x0A010203));
+        assert!(!set.contains(0x0B000000));
+        assert!(set.contains_cidr(&Cidr::parse("192.168.0.128/25").unwrap()));
+        // Covered by two adjacent blocks together
+        assert!(set.contains_cidr(&Cidr::parse("192.168.0.0/23").unwrap()));
+        assert!(!set.contains_cidr(&Cidr::parse("192.168.0.0/22").unwrap()));
+        assert!(set.overlaps(&Cidr::parse("192.168.0.0/22").unwrap()));
+        assert!(!set.overlaps(&Cidr::parse("172.16.0.0/12").unwrap()));
+    }
+
+    #[test]
+    fn test_supernet() {
+        let supernet = set(&["192.168.0.0/24", "192.168.2.0/24"]).supernet();
+        assert_eq!(supernet.unwrap().to_string(), "192.168.0.0/22");
+        assert_eq!(
+            set(&["10.0.0.0/8", "192.168.0.0/16"])
+                .supernet()
+                .unwrap()
+                .to_string(),
+            "0.0.0.0/0"
+        );
+        assert_eq!(
+            set(&["8.8.8.8/32"]).supernet().unwrap().to_string(),
+            "8.8.8.8/32"
+        );
+        assert_eq!(CidrSet::new().supernet(), None);
+    }
+
+    #[test]
+    fn test_intersect_and_subtract() {
+        let a = set(&["10.0.0.0/8", "192.168.0.0/16"]);
+        let b = set(&[
+            "10.1.0.0/16",
+            "10.2.0.0/16",
+            "172.16.0.0/12",
+            "192.168.255.0/24",
+        ]);
+
+        assert_eq!(
+            strings(&a.intersect(&b)),
+            vec!["10.1.0.0/16", "10.2.0.0/16", "192.168.255.0/24"]
+        );
+        assert_eq!(a.intersect(&b), b.intersect(&a));
+
+        let rest = a.subtract(&b);
+        assert_eq!(
+            strings(&rest),
+            vec![
+                "10.0.0.0/16",
+                "10.3.0.0/16",
+                "10.4.0.0/14",
+                "10.8.0.0/13",
+                "10.16.0.0/12",
+                "10.32.0.0/11",
+                "10.64.0.0/10",
+                "10.128.0.0/9",
+                "192.168.0.0/17",
+                "192.168.128.0/18",
+                "192.168.192.0/19",
+                "192.168.224.0/20",
+                "192.168.240.0/21",
+                "192.168.248.0/22",
+                "192.168.252.0/23",
+                "192.168.254.0/24",
+            ]
+        );
+        assert_eq!(rest.size() + a.intersect(&b).size(), a.size());
+        assert_eq!(rest.union(&a.intersect(&b)), a);
+
+        let everything = set(&["0.0.0.0/0"]);
+        assert!(a.subtract(&everything).is_empty());
+        assert_eq!(
+            strings(&everything.subtract(&set(&["0.0.0.0/1"]))),
+            vec!["128.0.0.0/1"]
+        );
+        assert_eq!(
+            strings(&everything.subtract(&set(&["255.255.255.255/32"])))
+                .last()
+                .unwrap(),
+            "255.255.255.254/32"
+        );
+    }
+
+    #[test]
+    fn test_overlapping_pairs() {
+        let cidrs: Vec<Cidr> = [
+            "192.168.1.0/24",
+            "10.0.0.0/8",
+            "192.168.0.0/16",
+            "192.168.1.128/25",
+            "172.16.0.0/12",
+        ]
+        .iter()
+        .map(|s| Cidr::parse(s).unwrap())
+        .collect();
+
+        let pairs: Vec<(String, String)> = overlapping_pairs(&cidrs)
+            .iter()
+            .map(|(outer, inner)| (outer.to_string(), inner.to_string()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("192.168.0.0/16".to_string(), "192.168.1.0/24".to_string()),
+                ("192.168.0.0/16".to_string(), "192.168.1.128/25".to_string()),
+                ("192.168.1.0/24".to_string(), "192.168.1.128/25".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,389 @@
+//! IANA special-purpose address registry
+//!
+//! Private, shared, documentation, loopback, link-local, multicast and
+//! reserved blocks are never routed on the public Internet, so no ASN owns
+//! them and a lookup can only miss. [`classify`] names the block an address
+//! falls in instead, following the IANA IPv4 and IPv6 Special-Purpose
+//! Address Registries (RFC 6890) plus the remaining bogon space (240/4,
+//! the discard-only IPv6 prefix). IPv4-mapped IPv6 addresses are classified
+//! as the IPv4 address they carry, as lookups treat them.
+//!
+//! When blocks nest (`0.0.0.0/32` in `0.0.0.0/8`, `255.255.255.255/32` in
+//! `240.0.0.0/4`) the most specific one wins.
+
+use crate::{Cidr, Cidr6, IpCidr};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Kind of special-purpose block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressClass {
+    /// Private-use space (RFC 1918, IPv6 unique-local RFC 4193)
+    Private,
+    /// Shared address space for carrier-grade NAT (RFC 6598)
+    Cgnat,
+    /// Documentation and example space (RFC 5737, RFC 3849, RFC 9637)
+    Documentation,
+    /// Loopback (RFC 1122, RFC 4291)
+    Loopback,
+    /// Link-local (RFC 3927, RFC 4291)
+    LinkLocal,
+    /// Multicast (RFC 5771, RFC 4291)
+    Multicast,
+    /// Reserved, unspecified, benchmarking and other bogon space
+    Reserved,
+}
+
+impl AddressClass {
+    /// Get the class name as used in JSON output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddressClass::Private => "private",
+            AddressClass::Cgnat => "cgnat",
+            AddressClass::Documentation => "documentation",
+            AddressClass::Loopback => "loopback",
+            AddressClass::LinkLocal => "link_local",
+            AddressClass::Multicast => "multicast",
+            AddressClass::Reserved => "reserved",
+        }
+    }
+
+    /// Check if blocks of this class are never globally routed
+    ///
+    /// No registry can own such an address, so it can be classified without
+    /// querying any lookup tier. `Reserved` is excluded: some of its blocks
+    /// (IETF protocol assignments, the 6to4 relay anycast) hold registered
+    /// space.
+    pub fn is_never_routed(&self) -> bool {
+        !matches!(self, AddressClass::Reserved)
+    }
+}
+
+impl fmt::Display for AddressClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Entry of the special-purpose registry
+///
+/// Serializes as `{cidr, name, rfc, class}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpecialPurpose {
+    /// Network address of the block
+    pub network: IpAddr,
+    /// Prefix length of the block
+    pub prefix_len: u8,
+    /// Registry name (e.g., "Private-Use")
+    pub name: &'static str,
+    /// Defining document (e.g., "RFC 1918")
+    pub rfc: &'static str,
+    /// Kind of block
+    pub class: AddressClass,
+}
+
+impl SpecialPurpose {
+    /// Get the block as a CIDR
+    pub fn cidr(&self) -> IpCidr {
+        match self.network {
+            IpAddr::V4(network) => Cidr::from_prefix(network.into(), self.prefix_len).into(),
+            IpAddr::V6(network) => Cidr6::from_prefix(network.into(), self.prefix_len).into(),
+        }
+    }
+
+    /// Check if an address falls in this block
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.cidr().contains(ip)
+    }
+}
+
+impl Serialize for SpecialPurpose {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SpecialPurpose", 4)?;
+        state.serialize_field("cidr", &self.cidr().to_string())?;
+        state.serialize_field("name", self.name)?;
+        state.serialize_field("rfc", self.rfc)?;
+        state.serialize_field("class", &self.class)?;
+        state.end()
+    }
+}
+
+const fn v4(
+    octets: [u8; 4],
+    prefix_len: u8,
+    name: &'static str,
+    rfc: &'static str,
+    class: AddressClass,
+) -> SpecialPurpose {
+    SpecialPurpose {
+        network: IpAddr::V4(Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3])),
+        prefix_len,
+        name,
+        rfc,
+        class,
+    }
+}
+
+const fn v6(
+    segments: [u16; 8],
+    prefix_len: u8,
+    name: &'static str,
+    rfc: &'static str,
+    class: AddressClass,
+) -> SpecialPurpose {
+    let [a, b, c, d, e, f, g, h] = segments;
+    SpecialPurpose {
+        network: IpAddr::V6(Ipv6Addr::new(a, b, c, d, e, f, g, h)),
+        prefix_len,
+        name,
+        rfc,
+        class,
+    }
+}
+
+use AddressClass::*;
+
+/// The special-purpose registry, IPv4 blocks first, in address order
+pub static SPECIAL_PURPOSE: &[SpecialPurpose] = &[
+    v4([0, 0, 0, 0], 8, "This network", "RFC 791", Reserved),
+    v4([0, 0, 0, 0], 32, "This host", "RFC 1122", Reserved),
+    v4([10, 0, 0, 0], 8, "Private-Use", "RFC 1918", Private),
+    v4(
+        [100, 64, 0, 0],
+        10,
+        "Shared Address Space",
+        "RFC 6598",
+        Cgnat,
+    ),
+    v4([127, 0, 0, 0], 8, "Loopback", "RFC 1122", Loopback),
+    v4([169, 254, 0, 0], 16, "Link Local", "RFC 3927", LinkLocal),
+    v4([172, 16, 0, 0], 12, "Private-Use", "RFC 1918", Private),
+    v4(
+        [192, 0, 0, 0],
+        24,
+        "IETF Protocol Assignments",
+        "RFC 6890",
+        Reserved,
+    ),
+    v4(
+        [192, 0, 2, 0],
+        24,
+        "Documentation (TEST-NET-1)",
+        "RFC 5737",
+        Documentation,
+    ),
+    v4(
+        [192, 88, 99, 0],
+        24,
+        "Deprecated 6to4 Relay Anycast",
+        "RFC 7526",
+        Reserved,
+    ),
+    v4([192, 168, 0, 0], 16, "Private-Use", "RFC 1918", Private),
+    v4([198, 18, 0, 0], 15, "Benchmarking", "RFC 2544", Reserved),
+    v4(
+        [198, 51, 100, 0],
+        24,
+        "Documentation (TEST-NET-2)",
+        "RFC 5737",
+        Documentation,
+    ),
+    v4(
+        [203, 0, 113, 0],
+        24,
+        "Documentation (TEST-NET-3)",
+        "RFC 5737",
+        Documentation,
+    ),
+    v4([224, 0, 0, 0], 4, "Multicast", "RFC 5771", Multicast),
+    v4([240, 0, 0, 0], 4, "Reserved", "RFC 1112", Reserved),
+    v4(
+        [255, 255, 255, 255],
+        32,
+        "Limited Broadcast",
+        "RFC 919",
+        Reserved,
+    ),
+    v6(
+        [0, 0, 0, 0, 0, 0, 0, 0],
+        128,
+        "Unspecified Address",
+        "RFC 4291",
+        Reserved,
+    ),
+    v6(
+        [0, 0, 0, 0, 0, 0, 0, 1],
+        128,
+        "Loopback Address",
+        "RFC 4291",
+        Loopback,
+    ),
+    v6(
+        [0x100, 0, 0, 0, 0, 0, 0, 0],
+        64,
+        "Discard-Only Address Block",
+        "RFC 6666",
+        Reserved,
+    ),
+    v6(
+        [0x2001, 0, 0, 0, 0, 0, 0, 0],
+        23,
+        "IETF Protocol Assignments",
+        "RFC 2928",
+        Reserved,
+    ),
+    v6(
+        [0x2001, 0xdb8, 0, 0, 0, 0, 0, 0],
+        32,
+        "Documentation",
+        "RFC 3849",
+        Documentation,
+    ),
+    v6(
+        [0x3fff, 0, 0, 0, 0, 0, 0, 0],
+        20,
+        "Documentation",
+        "RFC 9637",
+        Documentation,
+    ),
+    v6(
+        [0xfc00, 0, 0, 0, 0, 0, 0, 0],
+        7,
+        "Unique-Local",
+        "RFC 4193",
+        Private,
+    ),
+    v6(
+        [0xfe80, 0, 0, 0, 0, 0, 0, 0],
+        10,
+        "Link-Local Unicast",
+        "RFC 4291",
+        LinkLocal,
+    ),
+    v6(
+        [0xff00, 0, 0, 0, 0, 0, 0, 0],
+        8,
+        "Multicast",
+        "RFC 4291",
+        Multicast,
+    ),
+];
+
+/// Find the special-purpose block an address falls in
+///
+/// Returns `None` for ordinary, globally routable addresses. IPv4-mapped
+/// IPv6 addresses are classified by the IPv4 address they carry.
+///
+/// # Arguments
+///
+/// * `ip` - IPv4 or IPv6 address
+///
+/// # Examples
+///
+/// ```
+/// use rasn_cidr::{classify, AddressClass};
+///
+/// let special = classify("100.64.1.1".parse().unwrap()).unwrap();
+/// assert_eq!(special.class, AddressClass::Cgnat);
+/// assert_eq!(special.rfc, "RFC 6598");
+///
+/// assert!(classify("8.8.8.8".parse().unwrap()).is_none());
+/// ```
+pub fn classify(ip: IpAddr) -> Option<&'static SpecialPurpose> {
+    let ip = ip.to_canonical();
+    SPECIAL_PURPOSE
+        .iter()
+        .filter(|entry| entry.contains(ip))
+        .max_by_key(|entry| entry.prefix_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(ip: &str) -> Option<AddressClass> {
+        classify(ip.parse().unwrap()).map(|entry| entry.class)
+    }
+
+    #[test]
+    fn test_classify_ipv4() {
+        assert_eq!(class("10.1.2.3"), Some(Private));
+        assert_eq!(class("172.31.255.255"), Some(Private));
+        assert_eq!(class("192.168.0.1"), Some(Private));
+        assert_eq!(class("100.64.0.0"), Some(Cgnat));
+        assert_eq!(class("100.127.255.255"), Some(Cgnat));
+        assert_eq!(class("192.0.2.1"), Some(Documentation));
+        assert_eq!(class("198.51.100.7"), Some(Documentation));
+        assert_eq!(class("203.0.113.255"), Some(Documentation));
+        assert_eq!(class("127.0.0.1"), Some(Loopback));
+        assert_eq!(class("169.254.169.254"), Some(LinkLocal));
+        assert_eq!(class("239.255.255.250"), Some(Multicast));
+        assert_eq!(class("198.19.0.1"), Some(Reserved));
+        assert_eq!(class("250.0.0.1"), Some(Reserved));
+
+        // Just outside the blocks
+        assert_eq!(class("100.128.0.0"), None);
+        assert_eq!(class("172.32.0.0"), None);
+        assert_eq!(class("8.8.8.8"), None);
+        assert_eq!(class("1.1.1.1"), None);
+    }
+
+    #[test]
+    fn test_never_routed() {
+        assert!(Private.is_never_routed());
+        assert!(Cgnat.is_never_routed());
+        assert!(Multicast.is_never_routed());
+        assert!(!Reserved.is_never_routed());
+    }
+
+    #[test]
+    fn test_classify_most_specific() {
+        let entry = classify("255.255.255.255".parse().unwrap()).unwrap();
+        assert_eq!(entry.name, "Limited Broadcast");
+        let entry = classify("0.0.0.0".parse().unwrap()).unwrap();
+        assert_eq!(entry.name, "This host");
+        let entry = classify("0.1.2.3".parse().unwrap()).unwrap();
+        assert_eq!(entry.name, "This network");
+    }
+
+    #[test]
+    fn test_classify_ipv6() {
+        assert_eq!(class("fc00::1"), Some(Private));
+        assert_eq!(class("fd12:3456::1"), Some(Private));
+        assert_eq!(class("2001:db8::1"), Some(Documentation));
+        assert_eq!(class("3fff::1"), Some(Documentation));
+        assert_eq!(class("::1"), Some(Loopback));
+        assert_eq!(class("::"), Some(Reserved));
+        assert_eq!(class("fe80::1"), Some(LinkLocal));
+        assert_eq!(class("ff02::1"), Some(Multicast));
+        // IPv4-mapped addresses are classified as IPv4
+        assert_eq!(class("::ffff:8.8.8.8"), None);
+        assert_eq!(class("::ffff:10.0.0.1"), Some(Private));
+
+        assert_eq!(class("2001:4860:4860::8888"), None);
+        assert_eq!(class("2606:4700::1111"), None);
+    }
+
+    #[test]
+    fn test_special_purpose_serialize() {
+        let entry = classify("10.0.0.1".parse().unwrap()).unwrap();
+        assert_eq!(entry.cidr().to_string(), "10.0.0.0/8");
+        assert_eq!(
+            serde_json::to_value(entry).unwrap(),
+            serde_json::json!({
+                "cidr": "10.0.0.0/8",
+                "name": "Private-Use",
+                "rfc": "RFC 1918",
+                "class": "private"
+            })
+        );
+
+        // Every registry network is aligned to its prefix
+        for entry in SPECIAL_PURPOSE {
+            assert_eq!(entry.cidr().first(), entry.network, "{:?}", entry);
+        }
+    }
+}
@@ -9,11 +9,12 @@
 
 use anyhow::Result;
 use rayon::prelude::*;
+use std::net::IpAddr;
 use std::path::Path;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
-use rasn_arrow::IpRangeTableV4;
+use rasn_arrow::IpAsnDatabase;
 use rasn_core::AsnInfo;
 use rasn_resolver::DnsResolver;
 
@@ -23,6 +24,9 @@ fn num_cpus_get() -> usize {
     num_cpus::get()
 }
 
+/// Number of addresses looked up together by [`BatchProcessor::process_ips`]
+const LOOKUP_CHUNK: usize = 65_536;
+
 /// Batch processing result
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -34,7 +38,7 @@ pub struct BatchResult {
 /// Batch processor with parallel execution
 #[allow(dead_code)]
 pub struct BatchProcessor {
-    arrow_table: Option<Arc<IpRangeTableV4>>,
+    database: Option<Arc<IpAsnDatabase>>,
     dns_resolver: Option<Arc<DnsResolver>>,
     thread_pool: rayon::ThreadPool,
 }
@@ -54,8 +58,8 @@ impl BatchProcessor {
             .num_threads(num_threads)
             .build()?;
 
-        let arrow_table = if let Some(path) = arrow_path {
-            Some(Arc::new(IpRangeTableV4::from_parquet(path)?))
+        let database = if let Some(path) = arrow_path {
+            Some(Arc::new(IpAsnDatabase::load(path)?))
         } else {
             None
         };
@@ -63,7 +67,7 @@ impl BatchProcessor {
         let dns_resolver = Some(Arc::new(DnsResolver::new()?));
 
         Ok(Self {
-            arrow_table,
+            database,
             dns_resolver,
             thread_pool,
         })
@@ -71,33 +75,28 @@ impl BatchProcessor {
 
     /// Process a batch of IP addresses in parallel
     ///
+    /// Addresses are split into chunks of [`LOOKUP_CHUNK`], and each chunk
+    /// is looked up with one sorted pass over the range tables.
+    ///
     /// # Arguments
     ///
-    /// * `ips` - Vector of IP addresses (as u32)
-    pub fn process_ips(&self, ips: Vec<u32>) -> Vec<BatchResult> {
+    /// * `ips` - IPv4 and IPv6 addresses, in any mix
+    pub fn process_ips(&self, ips: &[IpAddr]) -> Vec<BatchResult> {
         let total = ips.len();
         let processed = Arc::new(AtomicUsize::new(0));
 
         self.thread_pool.install(|| {
-            ips.into_par_iter()
-                .map(|ip| {
-                    let result = self.lookup_ip(ip);
+            ips.par_chunks(LOOKUP_CHUNK)
+                .flat_map_iter(|chunk| {
+                    let results = self.lookup_ips(chunk);
 
-                    let count = processed.fetch_add(1, Ordering::Relaxed) + 1;
-                    if count % 1000 == 0 || count == total {
-                        eprintln!("Processed {}/{} IPs", count, total);
-                    }
+                    let count = processed.fetch_add(chunk.len(), Ordering::Relaxed) + chunk.len();
+                    eprintln!("Processed {}/{} IPs", count, total);
 
-                    BatchResult {
-                        input: format!(
-                            "{}.{}.{}.{}",
-                            (ip >> 24) & 0xFF,
-                            (ip >> 16) & 0xFF,
-                            (ip >> 8) & 0xFF,
-                            ip & 0xFF
-                        ),
+                    chunk.iter().zip(results).map(|(ip, result)| BatchResult {
+                        input: ip.to_string(),
                         result,
-                    }
+                    })
                 })
                 .collect()
         })
@@ -133,11 +132,24 @@ impl BatchProcessor {
         })
     }
 
-    /// Lookup single IP in Arrow table
-    fn lookup_ip(&self, ip: u32) -> Result<AsnInfo, String> {
-        if let Some(ref table) = self.arrow_table {
-            table
-                .find_ip(ip)
+    /// Lookup a chunk of addresses in Arrow tables
+    fn lookup_ips(&self, ips: &[IpAddr]) -> Vec<Result<AsnInfo, String>> {
+        let Some(ref database) = self.database else {
+            return vec![Err("Arrow table not loaded".to_string()); ips.len()];
+        };
+
+        database
+            .lookup_many(ips)
+            .into_iter()
+            .map(|info| info.ok_or_else(|| "No ASN found for IP".to_string()))
+            .collect()
+    }
+
+    /// Lookup single IP in Arrow tables
+    fn lookup_ip(&self, ip: IpAddr) -> Result<AsnInfo, String> {
+        if let Some(ref database) = self.database {
+            database
+                .lookup(ip)
                 .ok_or_else(|| "No ASN found for IP".to_string())
         } else {
             Err("Arrow table not loaded".to_string())
@@ -162,18 +174,7 @@ impl BatchProcessor {
 
         let ip = ips.first().ok_or_else(|| "No IPs returned".to_string())?;
 
-        // Convert IpAddr to u32 (IPv4 only for now)
-        let ip_u32 = match ip {
-            std::net::IpAddr::V4(ipv4) => {
-                let octets = ipv4.octets();
-                u32::from_be_bytes(octets)
-            }
-            std::net::IpAddr::V6(_) => {
-                return Err("IPv6 not yet supported".to_string());
-            }
-        };
-
-        self.lookup_ip(ip_u32)
+        self.lookup_ip(*ip)
     }
 
     /// Get thread pool info
@@ -193,6 +194,48 @@ mod tests {
         assert_eq!(processor.unwrap().thread_count(), 4);
     }
 
+    #[test]
+    fn test_process_ips_without_database() {
+        let processor = BatchProcessor::new(None, Some(2)).unwrap();
+        let ips = ["8.8.8.8".parse().unwrap(), "2001:db8::1".parse().unwrap()];
+        let results = processor.process_ips(&ips);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].input, "8.8.8.8");
+        assert_eq!(results[1].input, "2001:db8::1");
+        assert!(results.iter().all(|r| r.result.is_err()));
+    }
+
+    #[test]
+    fn test_process_ips_dual_stack() {
+        use rasn_arrow::test_util::google_v4;
+        use rasn_arrow::IpRangeTableV6;
+
+        let v4 = google_v4(15169);
+        let v6 = IpRangeTableV6::from_vecs(
+            vec![0x2001_4860 << 96],
+            vec![(0x2001_4860 << 96) | ((1u128 << 96) - 1)],
+            vec![15169],
+            vec!["US".to_string()],
+            vec!["GOOGLE".to_string()],
+        )
+        .unwrap();
+        let mut processor = BatchProcessor::new(None, Some(2)).unwrap();
+        processor.database = Some(Arc::new(IpAsnDatabase::new(Some(v4), Some(v6))));
+
+        let ips = [
+            "2001:4860:4860::8888".parse().unwrap(),
+            "8.8.8.8".parse().unwrap(),
+            "1.1.1.1".parse().unwrap(),
+        ];
+        let results = processor.process_ips(&ips);
+
+        assert_eq!(results[0].input, "2001:4860:4860::8888");
+        assert_eq!(results[0].result.as_ref().unwrap().asn.0, 15169);
+        assert_eq!(results[1].result.as_ref().unwrap().asn.0, 15169);
+        assert!(results[2].result.is_err());
+    }
+
     #[test]
     fn test_batch_processor_default_threads() {
         let processor = BatchProcessor::new(None, None).unwrap();
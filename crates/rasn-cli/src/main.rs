@@ -1,7 +1,7 @@
 use anyhow::Result;
 mod batch;
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 use serde::Serialize;
 
@@ -28,6 +28,16 @@ enum Commands {
     Lookup(LookupArgs),
     /// Batch process multiple inputs from file or stdin
     Batch(BatchArgs),
+    /// Search ASNs by organization name
+    SearchOrg(SearchOrgArgs),
+    /// Summarize the ASNs and address space of a country
+    Country(CountryArgs),
+    /// CIDR calculations and set operations
+    Cidr(CidrArgs),
+    /// Build the Parquet database files from reference data
+    BuildDb(BuildDbArgs),
+    /// Inspect and check database files
+    Db(DbArgs),
     /// Start MCP server for AI agent integration
     Mcp(McpArgs),
     /// Manage API authentication
@@ -39,6 +49,44 @@ struct LookupArgs {
     /// IP address, ASN number (e.g., AS15169), or domain name
     #[arg(value_name = "TARGET")]
     target: String,
+
+    /// Answer from the snapshot valid at DATE (YYYY-MM-DD) instead of the current data
+    #[arg(long, value_name = "DATE")]
+    at: Option<rasn_arrow::SnapshotDate>,
+
+    #[command(flatten)]
+    chain: ChainArgs,
+
+    #[command(flatten)]
+    snapshots: SnapshotArgs,
+}
+
+/// Historical snapshots used for point-in-time lookups
+#[derive(Args)]
+struct SnapshotArgs {
+    /// Directory of dated datasets [default: snapshots/ in the data directory]
+    #[arg(long = "snapshots", value_name = "DIR")]
+    dir: Option<std::path::PathBuf>,
+}
+
+/// Lookup tiers used for IP addresses
+#[derive(Args)]
+struct ChainArgs {
+    /// Lookup tiers, in order (arrow, cache, cold_storage, api, whois)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "arrow,cache,cold_storage,api,whois"
+    )]
+    tiers: Vec<rasn_core::LookupSource>,
+
+    /// Timeout of one tier in milliseconds (e.g., api=2000), repeatable
+    #[arg(long, value_name = "TIER=MS", value_parser = parse_tier_timeout)]
+    tier_timeout: Vec<(rasn_core::LookupSource, std::time::Duration)>,
+
+    /// RocksDB cold storage directory
+    #[arg(long, value_name = "DIR")]
+    cold_storage: Option<std::path::PathBuf>,
 }
 
 #[derive(Parser)]
@@ -52,6 +100,210 @@ struct BatchArgs {
     workers: usize,
 }
 
+#[derive(Parser)]
+struct SearchOrgArgs {
+    /// Organization name or part of it (case-insensitive)
+    #[arg(value_name = "QUERY")]
+    query: String,
+
+    /// Only show ASNs registered in this country (e.g., US)
+    #[arg(short, long)]
+    country: Option<String>,
+
+    /// Only show exact name matches
+    #[arg(short, long)]
+    exact: bool,
+
+    /// Maximum number of results
+    #[arg(short, long, default_value = "20")]
+    limit: usize,
+}
+
+#[derive(Parser)]
+struct CountryArgs {
+    /// ISO 3166-1 alpha-2 country code (e.g., NL)
+    #[arg(value_name = "CC")]
+    country: String,
+
+    /// Include every IPv4/IPv6 range instead of the per-ASN summary
+    #[arg(short, long)]
+    ranges: bool,
+
+    /// Number of ASNs shown in human output
+    #[arg(short, long, default_value = "20")]
+    limit: usize,
+}
+
+#[derive(Parser)]
+struct CidrArgs {
+    #[command(subcommand)]
+    command: CidrCommand,
+}
+
+#[derive(Subcommand)]
+enum CidrCommand {
+    /// Merge overlapping and adjacent CIDRs into the minimal list
+    Aggregate(CidrListArgs),
+    /// Find the smallest CIDR containing all CIDRs
+    Supernet(CidrListArgs),
+    /// Split CIDRs into subnets of a longer prefix
+    Split(SplitArgs),
+    /// Check if a CIDR is fully covered by the CIDR list
+    Contains(ContainsArgs),
+    /// List overlapping CIDRs, or the CIDRs overlapping a target
+    Overlaps(OverlapsArgs),
+    /// Addresses both in the CIDR list and in the --with list
+    Intersect(OperandArgs),
+    /// Addresses in the CIDR list that are not in the --with list
+    Subtract(OperandArgs),
+    /// Convert IPv4/IPv6 address ranges to the minimal CIDR list
+    #[command(name = "range2cidr")]
+    RangeToCidr(RangeToCidrArgs),
+    /// Break a CIDR down by the ASNs, countries and gaps of the loaded data
+    Analyze(AnalyzeArgs),
+}
+
+#[derive(Parser)]
+struct AnalyzeArgs {
+    /// IPv4 or IPv6 CIDR (e.g., 8.8.0.0/16)
+    #[arg(value_name = "CIDR")]
+    cidr: String,
+
+    /// Number of ASNs, countries and gaps shown in human output
+    #[arg(short, long, default_value = "20")]
+    limit: usize,
+}
+
+#[derive(Parser)]
+struct RangeToCidrArgs {
+    /// Ranges as START-END (e.g., 10.0.0.1-10.0.0.4) [default: one per line
+    /// from stdin, START-END or START and END in the first two columns]
+    #[arg(value_name = "RANGE")]
+    ranges: Vec<String>,
+}
+
+#[derive(Args)]
+struct CidrListArgs {
+    /// CIDRs (e.g., 192.168.0.0/24) [default: one per line from stdin]
+    #[arg(value_name = "CIDR")]
+    cidrs: Vec<String>,
+}
+
+#[derive(Parser)]
+struct SplitArgs {
+    /// Prefix length of the subnets
+    #[arg(short, long)]
+    prefix: u8,
+
+    #[command(flatten)]
+    list: CidrListArgs,
+}
+
+#[derive(Parser)]
+struct ContainsArgs {
+    /// CIDR or address to check
+    #[arg(short, long)]
+    target: String,
+
+    #[command(flatten)]
+    list: CidrListArgs,
+}
+
+#[derive(Parser)]
+struct OverlapsArgs {
+    /// Only list the CIDRs overlapping this CIDR
+    #[arg(short, long)]
+    target: Option<String>,
+
+    #[command(flatten)]
+    list: CidrListArgs,
+}
+
+#[derive(Parser)]
+struct OperandArgs {
+    /// Second CIDR list, comma-separated
+    #[arg(
+        short,
+        long,
+        value_name = "CIDR",
+        value_delimiter = ',',
+        required = true
+    )]
+    with: Vec<String>,
+
+    #[command(flatten)]
+    list: CidrListArgs,
+}
+
+#[derive(Parser)]
+struct BuildDbArgs {
+    /// Directory containing the downloaded reference data
+    #[arg(short, long, value_name = "DIR", default_value = "reference_data")]
+    reference: std::path::PathBuf,
+
+    /// Data directory; Parquet files are written to its arrow/ subdirectory
+    #[arg(short, long, value_name = "DIR", default_value = "data")]
+    data_dir: std::path::PathBuf,
+}
+
+#[derive(Parser)]
+struct DbArgs {
+    #[command(subcommand)]
+    command: DbCommand,
+}
+
+#[derive(Subcommand)]
+enum DbCommand {
+    /// Check a range table for unsorted, overlapping or malformed ranges
+    Verify(VerifyArgs),
+    /// Convert a Parquet range table to a memory-mappable Arrow IPC file
+    Convert(ConvertArgs),
+    /// Show when and from which source data the range tables were built
+    Info(InfoArgs),
+    /// Report ranges and ASNs that changed between two builds of a range table
+    Diff(DiffArgs),
+}
+
+#[derive(Parser)]
+struct VerifyArgs {
+    /// IPv4 or IPv6 range table file (Parquet or Arrow IPC)
+    #[arg(value_name = "FILE")]
+    file: std::path::PathBuf,
+}
+
+#[derive(Parser)]
+struct ConvertArgs {
+    /// IPv4 or IPv6 range table Parquet file
+    #[arg(value_name = "FILE")]
+    file: std::path::PathBuf,
+
+    /// Output file [default: FILE with the .arrow extension]
+    #[arg(short = 'O', long, value_name = "FILE")]
+    out: Option<std::path::PathBuf>,
+}
+
+#[derive(Parser)]
+struct DiffArgs {
+    /// Older range table file (Parquet or Arrow IPC)
+    #[arg(value_name = "OLD")]
+    old: std::path::PathBuf,
+
+    /// Newer range table file of the same address family
+    #[arg(value_name = "NEW")]
+    new: std::path::PathBuf,
+
+    /// Maximum number of changes listed per section in human output
+    #[arg(long, default_value = "20")]
+    limit: usize,
+}
+
+#[derive(Parser)]
+struct InfoArgs {
+    /// Data directory or range table file [default: the data used by lookups]
+    #[arg(value_name = "PATH")]
+    path: Option<std::path::PathBuf>,
+}
+
 #[derive(Parser)]
 struct McpArgs {
     /// Transport mode
@@ -61,6 +313,20 @@ struct McpArgs {
     /// HTTP port (only for http transport)
     #[arg(short, long, default_value = "8080")]
     port: u16,
+
+    /// Seconds between checks of the data files for changes
+    #[arg(long, value_name = "SECS", default_value = "30")]
+    reload_interval: u64,
+
+    /// Do not reload data on SIGHUP or file changes
+    #[arg(long)]
+    no_watch: bool,
+
+    #[command(flatten)]
+    chain: ChainArgs,
+
+    #[command(flatten)]
+    snapshots: SnapshotArgs,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -102,8 +368,37 @@ struct LookupResult {
     target: String,
     asn: Option<u32>,
     organization: Option<String>,
+    handle: Option<String>,
     country: Option<String>,
     description: Option<String>,
+    source: Option<rasn_core::LookupSource>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot: Option<rasn_arrow::SnapshotDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    special: Option<&'static rasn_cidr::SpecialPurpose>,
+}
+
+impl LookupResult {
+    /// Answer for a miss on an address in an IANA special-purpose block
+    ///
+    /// No ASN owns these blocks, so the registry entry is reported instead
+    /// of "Not Found".
+    fn special(target: &str, special: &'static rasn_cidr::SpecialPurpose) -> Self {
+        Self {
+            target: target.to_string(),
+            asn: None,
+            organization: Some(special.name.to_string()),
+            handle: None,
+            country: None,
+            description: Some(format!(
+                "Special-purpose address ({}, {})",
+                special.class, special.rfc
+            )),
+            source: None,
+            snapshot: None,
+            special: Some(special),
+        }
+    }
 }
 
 fn main() -> Result<()> {
@@ -112,6 +407,11 @@ fn main() -> Result<()> {
     match cli.command {
         Commands::Lookup(args) => handle_lookup(args, cli.output, cli.verbose)?,
         Commands::Batch(args) => handle_batch(args, cli.output, cli.verbose)?,
+        Commands::SearchOrg(args) => handle_search_org(args, cli.output, cli.verbose)?,
+        Commands::Country(args) => handle_country(args, cli.output, cli.verbose)?,
+        Commands::Cidr(args) => handle_cidr(args, cli.output, cli.verbose)?,
+        Commands::BuildDb(args) => handle_build_db(args, cli.output, cli.verbose)?,
+        Commands::Db(args) => handle_db(args, cli.output, cli.verbose)?,
         Commands::Mcp(args) => handle_mcp(args, cli.verbose)?,
         Commands::Auth(args) => handle_auth(args, cli.verbose)?,
     }
@@ -120,16 +420,276 @@ fn main() -> Result<()> {
 }
 
 fn handle_lookup(args: LookupArgs, format: OutputFormat, verbose: bool) -> Result<()> {
-    use rasn_arrow::IpRangeTableV4;
-    use std::env;
-    use std::path::PathBuf;
-
     if verbose {
         eprintln!("{} Looking up: {}", "›".blue(), args.target);
     }
 
-    // Try to find data file
-    let data_paths = [
+    if let Some(date) = args.at {
+        let store = open_snapshot_store(&args.snapshots, verbose)?
+            .ok_or_else(|| anyhow::anyhow!("No snapshot directory found (use --snapshots DIR)"))?;
+        if verbose {
+            eprintln!(
+                "{} {} snapshots in {:?}",
+                "›".blue(),
+                store.len(),
+                store.root()
+            );
+        }
+        let result = lookup_target_at(&store, &args.target, date)?;
+        print_result(&result, format)?;
+        return Ok(());
+    }
+
+    let database = load_database(verbose).map(std::sync::Arc::new);
+    let cache = rasn_cache::CacheLayer::new(10000)
+        .map_err(|e| anyhow::anyhow!("Failed to create cache: {}", e))?;
+    let chain = build_chain(database, &args.chain, std::sync::Arc::new(cache), verbose)?;
+
+    let result = lookup_target(&chain, &args.target)?;
+
+    print_result(&result, format)?;
+    Ok(())
+}
+
+/// Look up an IPv4 or IPv6 address with any lookup backend
+fn lookup_target(backend: &dyn rasn_core::AsyncAsnLookup, target: &str) -> Result<LookupResult> {
+    let ip: std::net::IpAddr = target
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid IP address format"))?;
+
+    // Never-routed addresses (private, loopback, documentation, ...) are
+    // answered locally so they are not sent to the API or WHOIS tiers
+    if let Some(special) = rasn_cidr::classify(ip).filter(|s| s.class.is_never_routed()) {
+        return Ok(LookupResult::special(target, special));
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    // Other special-purpose blocks are classified on a miss, so a failing
+    // tier must not turn them into an error
+    let answer = match runtime.block_on(backend.lookup(ip)) {
+        Ok(answer) => answer,
+        Err(_) if rasn_cidr::classify(ip).is_some() => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    let result = match answer {
+        Some(answer) => {
+            let info = answer.info;
+            LookupResult {
+                target: target.to_string(),
+                asn: Some(info.asn.0),
+                organization: Some(info.organization),
+                handle: info.handle,
+                country: info.country, // Already Option<String>
+                description: info.description.or(Some(format!("AS{}", info.asn.0))),
+                source: Some(answer.source),
+                snapshot: None,
+                special: None,
+            }
+        }
+        None => match rasn_cidr::classify(ip) {
+            Some(special) => LookupResult::special(target, special),
+            None => LookupResult {
+                target: target.to_string(),
+                asn: None,
+                organization: Some("Not Found".to_string()),
+                handle: None,
+                country: None,
+                description: Some("IP not in database".to_string()),
+                source: None,
+                snapshot: None,
+                special: None,
+            },
+        },
+    };
+
+    Ok(result)
+}
+
+/// Look up an IPv4 or IPv6 address in the snapshot valid at a date
+fn lookup_target_at(
+    store: &rasn_arrow::SnapshotStore,
+    target: &str,
+    date: rasn_arrow::SnapshotDate,
+) -> Result<LookupResult> {
+    let ip: std::net::IpAddr = target
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid IP address format"))?;
+
+    let (snapshot, database) = match store.database_at(date) {
+        Ok(found) => found,
+        Err(e) => {
+            return match rasn_cidr::classify(ip) {
+                Some(special) => Ok(LookupResult::special(target, special)),
+                None => Err(e.into()),
+            }
+        }
+    };
+    let result = match rasn_arrow::IpAsnDatabase::lookup(&database, ip) {
+        Some(info) => LookupResult {
+            target: target.to_string(),
+            asn: Some(info.asn.0),
+            organization: Some(info.organization),
+            handle: info.handle,
+            country: info.country,
+            description: info.description.or(Some(format!("AS{}", info.asn.0))),
+            source: Some(rasn_core::LookupSource::Arrow),
+            snapshot: Some(snapshot),
+            special: None,
+        },
+        None => match rasn_cidr::classify(ip) {
+            Some(special) => LookupResult {
+                snapshot: Some(snapshot),
+                ..LookupResult::special(target, special)
+            },
+            None => LookupResult {
+                target: target.to_string(),
+                asn: None,
+                organization: Some("Not Found".to_string()),
+                handle: None,
+                country: None,
+                description: Some(format!("IP not in snapshot {}", snapshot)),
+                source: None,
+                snapshot: Some(snapshot),
+                special: None,
+            },
+        },
+    };
+
+    Ok(result)
+}
+
+/// Open the snapshot directory given with `--snapshots`, or the
+/// `snapshots/` directory of the first data path that has one
+fn open_snapshot_store(
+    args: &SnapshotArgs,
+    verbose: bool,
+) -> Result<Option<rasn_arrow::SnapshotStore>> {
+    use rasn_arrow::SnapshotStore;
+
+    if let Some(ref dir) = args.dir {
+        let store = SnapshotStore::open(dir)
+            .map_err(|e| anyhow::anyhow!("Failed to open snapshots {:?}: {}", dir, e))?;
+        return Ok(Some(store));
+    }
+
+    for path in data_paths() {
+        let dir = path.join("snapshots");
+        if !dir.is_dir() {
+            continue;
+        }
+        if verbose {
+            eprintln!("{} Using snapshots from: {:?}", "›".blue(), dir);
+        }
+        if let Ok(store) = SnapshotStore::open(&dir) {
+            return Ok(Some(store));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Parse a `TIER=MS` tier timeout
+fn parse_tier_timeout(
+    value: &str,
+) -> std::result::Result<(rasn_core::LookupSource, std::time::Duration), String> {
+    let (tier, millis) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected TIER=MS, got '{}'", value))?;
+    let tier = tier
+        .parse()
+        .map_err(|e: rasn_core::RasnError| e.to_string())?;
+    let millis = millis
+        .parse()
+        .map_err(|_| format!("invalid milliseconds: '{}'", millis))?;
+
+    Ok((tier, std::time::Duration::from_millis(millis)))
+}
+
+/// Build the lookup chain from the requested tiers
+///
+/// Tiers that are not available (no database, no API key, no cold storage
+/// directory) are skipped.
+fn build_chain(
+    database: Option<std::sync::Arc<rasn_arrow::DatabaseHandle>>,
+    args: &ChainArgs,
+    cache: std::sync::Arc<rasn_cache::CacheLayer>,
+    verbose: bool,
+) -> Result<rasn_core::LookupChain> {
+    use rasn_core::{LookupChain, LookupSource};
+    use std::sync::Arc;
+
+    let mut chain = LookupChain::new();
+
+    for &source in &args.tiers {
+        let timeout = args
+            .tier_timeout
+            .iter()
+            .rev()
+            .find(|(tier, _)| *tier == source)
+            .map_or(source.default_timeout(), |&(_, timeout)| timeout);
+
+        let skipped = match source {
+            LookupSource::Arrow => match database {
+                Some(ref database) => {
+                    chain = chain.with_tier(database.clone(), timeout);
+                    None
+                }
+                None => Some("no data file found"),
+            },
+            LookupSource::Cache => {
+                chain = chain.with_store_tier(cache.clone(), timeout);
+                None
+            }
+            LookupSource::ColdStorage => match args.cold_storage {
+                #[cfg(feature = "cold-storage")]
+                Some(ref path) => {
+                    let storage = rasn_db::ColdStorage::open(path)
+                        .map_err(|e| anyhow::anyhow!("Failed to open cold storage: {}", e))?;
+                    // RocksDB reads block; run them off the executor so
+                    // the tier timeout holds
+                    chain = chain.with_store_tier(
+                        Arc::new(rasn_core::Blocking::new(Arc::new(storage))),
+                        timeout,
+                    );
+                    None
+                }
+                #[cfg(not(feature = "cold-storage"))]
+                Some(_) => Some("built without the cold-storage feature"),
+                None => Some("no --cold-storage directory"),
+            },
+            LookupSource::Api => match rasn_core::security::KeyManager::new().get_api_key() {
+                Ok(key) => {
+                    chain = chain.with_tier(Arc::new(rasn_client::ApiClient::new(key)), timeout);
+                    None
+                }
+                Err(_) => Some("RASN_API_KEY not set"),
+            },
+            LookupSource::Whois => {
+                chain = chain.with_tier(Arc::new(rasn_whois::WhoisClient::new()), timeout);
+                None
+            }
+        };
+
+        if let (Some(reason), true) = (skipped, verbose) {
+            eprintln!("{} Skipping {} tier: {}", "⚠".yellow(), source, reason);
+        }
+    }
+
+    Ok(chain)
+}
+
+/// Standard data paths, in search order
+fn data_paths() -> Vec<std::path::PathBuf> {
+    use std::env;
+    use std::path::PathBuf;
+
+    [
         env::var("RASN_DATA_DIR").ok().map(PathBuf::from),
         Some(PathBuf::from(format!(
             "{}/.local/share/rasn",
@@ -138,30 +698,40 @@ fn handle_lookup(args: LookupArgs, format: OutputFormat, verbose: bool) -> Resul
         Some(PathBuf::from("/usr/local/share/rasn")),
         Some(PathBuf::from(".rasn-data")),
         Some(PathBuf::from("data")),
-    ];
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
 
-    let mut table = None;
-    for path in data_paths.iter().flatten() {
-        // Try Parquet files (in arrow subdirectory)
-        let parquet_paths = [
-            path.join("arrow/ip2asn-v4.parquet"),
-            path.join("asn.parquet"),
-        ];
-
-        for parquet_path in &parquet_paths {
-            if parquet_path.exists() {
-                if verbose {
-                    eprintln!("{} Loading data from: {:?}", "›".blue(), parquet_path);
-                }
-                table = IpRangeTableV4::from_parquet(parquet_path).ok();
-                if table.is_some() {
-                    break;
-                }
+/// Locate and load the IPv4/IPv6 range tables from the standard data paths
+///
+/// Databases loaded from a data directory or Parquet file can be reloaded
+/// from the same path; TSV data cannot.
+fn load_database(verbose: bool) -> Option<rasn_arrow::DatabaseHandle> {
+    use rasn_arrow::{DatabaseHandle, IpAsnDatabase, IpRangeTableV4};
+
+    for path in data_paths() {
+        // Try table files (IPv4 and IPv6, Arrow IPC or Parquet, in arrow subdirectory)
+        let arrow_dir = path.join("arrow");
+        if arrow_dir.is_dir() {
+            if verbose {
+                eprintln!("{} Loading data from: {:?}", "›".blue(), arrow_dir);
+            }
+            if let Ok(database) = IpAsnDatabase::open(&arrow_dir) {
+                return Some(DatabaseHandle::new(database).with_path(&arrow_dir));
             }
         }
 
-        if table.is_some() {
-            break;
+        let parquet_path = path.join("asn.parquet");
+        if parquet_path.exists() {
+            if verbose {
+                eprintln!("{} Loading data from: {:?}", "›".blue(), parquet_path);
+            }
+            if let Ok(table) = IpRangeTableV4::open(&parquet_path) {
+                let database = IpAsnDatabase::new(Some(table), None);
+                return Some(DatabaseHandle::new(database).with_path(&parquet_path));
+            }
         }
 
         // Fallback to TSV file
@@ -170,51 +740,13 @@ fn handle_lookup(args: LookupArgs, format: OutputFormat, verbose: bool) -> Resul
             if verbose {
                 eprintln!("{} Loading data from TSV: {:?}", "›".blue(), tsv_path);
             }
-            table = load_tsv_data(&tsv_path).ok();
-            if table.is_some() {
-                break;
+            if let Ok(table) = load_tsv_data(&tsv_path) {
+                return Some(DatabaseHandle::new(IpAsnDatabase::new(Some(table), None)));
             }
         }
     }
 
-    // Parse IP address
-    let ip_u32 = parse_ip(&args.target)?;
-
-    let result = if let Some(ref table) = table {
-        // Real lookup from Arrow table
-        if let Some(info) = table.find_ip(ip_u32) {
-            LookupResult {
-                target: args.target.clone(),
-                asn: Some(info.asn.0),
-                organization: Some(info.organization),
-                country: info.country, // Already Option<String>
-                description: Some(format!("AS{}", info.asn.0)),
-            }
-        } else {
-            LookupResult {
-                target: args.target.clone(),
-                asn: None,
-                organization: Some("Not Found".to_string()),
-                country: None,
-                description: Some("IP not in database".to_string()),
-            }
-        }
-    } else {
-        // Fallback to demo data if no Arrow table found
-        if verbose {
-            eprintln!("{} No data file found, using demo data", "⚠".yellow());
-        }
-        LookupResult {
-            target: args.target.clone(),
-            asn: Some(15169),
-            organization: Some("Google LLC (DEMO DATA)".to_string()),
-            country: Some("US".to_string()),
-            description: Some("Install data with: make install-data".to_string()),
-        }
-    };
-
-    print_result(&result, format)?;
-    Ok(())
+    None
 }
 
 fn parse_ip(ip_str: &str) -> Result<u32> {
@@ -295,73 +827,1451 @@ fn handle_batch(args: BatchArgs, _format: OutputFormat, verbose: bool) -> Result
     Ok(())
 }
 
-fn handle_mcp(args: McpArgs, verbose: bool) -> Result<()> {
-    let server = rasn_mcp::McpServer::new(None)
-        .map_err(|e| anyhow::anyhow!("Failed to create MCP server: {}", e))?;
-    let server = std::sync::Arc::new(server);
+fn handle_search_org(args: SearchOrgArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    use rasn_arrow::OrgQuery;
 
-    match args.transport {
-        TransportMode::Stdio => {
-            if verbose {
-                eprintln!("{} Starting MCP server on STDIO", "›".blue());
-            }
-            let transport = rasn_mcp::transport::StdioTransport::new(server);
-            transport
-                .run_blocking()
-                .map_err(|e| anyhow::anyhow!("STDIO transport error: {}", e))?;
-        }
-        TransportMode::Http => {
-            if verbose {
-                eprintln!(
-                    "{} Starting MCP server on HTTP port {}",
-                    "›".blue(),
-                    args.port
-                );
-            }
-            println!("{}", "HTTP transport - Use STDIO for now".yellow());
-            println!("STDIO mode is fully operational for Claude Desktop integration");
-        }
+    if verbose {
+        eprintln!("{} Searching organizations: {}", "›".blue(), args.query);
     }
 
-    Ok(())
-}
-
-fn handle_auth(args: AuthArgs, verbose: bool) -> Result<()> {
-    use rasn_core::security::KeyManager;
+    let database = load_database(verbose)
+        .ok_or_else(|| anyhow::anyhow!("No data found. Install data with: make install-data"))?
+        .load();
 
-    let manager = KeyManager::new();
+    let mut query = OrgQuery::new(&args.query)
+        .exact(args.exact)
+        .with_limit(args.limit);
+    if let Some(ref country) = args.country {
+        query = query.with_country(country);
+    }
+    let matches = database.search_org(&query);
 
-    match args.command {
-        AuthCommand::Status => {
-            if verbose {
-                eprintln!("{} Checking API key status", "›".blue());
+    match format {
+        OutputFormat::Human => {
+            println!();
+            println!(
+                "{} {}",
+                "Organization Search:".bold().cyan(),
+                args.query.bold()
+            );
+            println!("{}", "─".repeat(70).dimmed());
+            if matches.is_empty() {
+                println!("  {}", "No matching organizations".yellow());
             }
-
-            if manager.has_api_key() {
-                let masked = manager
-                    .get_masked_key()
-                    .unwrap_or_else(|_| "****".to_string());
-                println!("{}", "✓ API key configured".green());
-                println!("  Key: {}", masked);
-            } else {
-                println!("{}", "✗ No API key configured".yellow());
-                println!("  Set RASN_API_KEY environment variable");
+            for m in &matches {
+                println!(
+                    "{:>12}  {:<30} {:<3} {:>6} ranges  {}",
+                    m.asn.to_string().green(),
+                    m.name,
+                    m.country.as_deref().unwrap_or("-"),
+                    m.ip_range_count,
+                    m.description.as_deref().unwrap_or("").dimmed()
+                );
             }
-        }
-        AuthCommand::Info => {
-            println!("{}", "API Key Configuration".bold().cyan());
-            println!("{}", "─".repeat(50).dimmed());
-            println!("  Environment variable: RASN_API_KEY");
-            println!("  Usage: export RASN_API_KEY=your_key_here");
             println!();
-            println!("  Status: rasn auth status");
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&matches)?),
+        OutputFormat::JsonCompact => println!("{}", serde_json::to_string(&matches)?),
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            wtr.write_record([
+                "asn",
+                "name",
+                "country",
+                "handle",
+                "description",
+                "ip_range_count",
+                "match",
+            ])?;
+            for m in &matches {
+                wtr.write_record([
+                    &m.asn.0.to_string(),
+                    &m.name,
+                    m.country.as_deref().unwrap_or(""),
+                    m.handle.as_deref().unwrap_or(""),
+                    m.description.as_deref().unwrap_or(""),
+                    &m.ip_range_count.to_string(),
+                    &format!("{:?}", m.kind).to_lowercase(),
+                ])?;
+            }
+            wtr.flush()?;
         }
     }
 
     Ok(())
 }
 
-fn print_result(result: &LookupResult, format: OutputFormat) -> Result<()> {
+#[derive(Serialize)]
+struct CountryOutput<'a> {
+    country: &'a str,
+    total_asns: usize,
+    ipv4_range_count: usize,
+    ipv6_range_count: usize,
+    ipv4_addresses: u64,
+    ipv6_addresses: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    geo_ipv4_range_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    geo_ipv4_addresses: Option<u64>,
+    asns: &'a [rasn_arrow::CountryAsn],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ipv4_ranges: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ipv6_ranges: Option<Vec<String>>,
+}
+
+fn handle_country(args: CountryArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    if verbose {
+        eprintln!("{} Summarizing country: {}", "›".blue(), args.country);
+    }
+
+    let database = load_database(verbose)
+        .ok_or_else(|| anyhow::anyhow!("No data found. Install data with: make install-data"))?
+        .load();
+    let summary = database
+        .country_summary(&args.country)
+        .ok_or_else(|| anyhow::anyhow!("No data for country: {}", args.country))?;
+
+    let ipv4_ranges: Vec<String> = summary
+        .ipv4_ranges
+        .iter()
+        .map(|&(start, end)| format!("{}-{}", Ipv4Addr::from(start), Ipv4Addr::from(end)))
+        .collect();
+    let ipv6_ranges: Vec<String> = summary
+        .ipv6_ranges
+        .iter()
+        .map(|&(start, end)| format!("{}-{}", Ipv6Addr::from(start), Ipv6Addr::from(end)))
+        .collect();
+
+    match format {
+        OutputFormat::Human => {
+            println!();
+            println!(
+                "{} {}",
+                "Country Summary:".bold().cyan(),
+                summary.country.bold()
+            );
+            println!("{}", "─".repeat(60).dimmed());
+            println!("{:>15}: {}", "ASNs".bold(), summary.asns.len());
+            println!(
+                "{:>15}: {} ({} addresses)",
+                "IPv4 ranges".bold(),
+                summary.ipv4_ranges.len(),
+                summary.ipv4_address_count()
+            );
+            println!(
+                "{:>15}: {} ({} addresses)",
+                "IPv6 ranges".bold(),
+                summary.ipv6_ranges.len(),
+                summary.ipv6_address_count()
+            );
+            if let Some(ref geo) = summary.geo_ipv4_ranges {
+                println!(
+                    "{:>15}: {} ({} addresses, country index)",
+                    "Geo IPv4".bold(),
+                    geo.len(),
+                    summary.geo_ipv4_address_count().unwrap_or(0)
+                );
+            }
+
+            if args.ranges {
+                println!();
+                for range in ipv4_ranges.iter().chain(&ipv6_ranges) {
+                    println!("  {}", range);
+                }
+            } else if !summary.asns.is_empty() {
+                println!();
+                println!("{}", "Top ASNs".bold());
+                for asn in summary.asns.iter().take(args.limit) {
+                    println!(
+                        "{:>12}  {:<30} {:>6} v4 {:>6} v6",
+                        asn.asn.to_string().green(),
+                        asn.name,
+                        asn.ipv4_ranges,
+                        asn.ipv6_ranges
+                    );
+                }
+            }
+            println!();
+        }
+        OutputFormat::Json | OutputFormat::JsonCompact => {
+            let output = CountryOutput {
+                country: &summary.country,
+                total_asns: summary.asns.len(),
+                ipv4_range_count: summary.ipv4_ranges.len(),
+                ipv6_range_count: summary.ipv6_ranges.len(),
+                ipv4_addresses: summary.ipv4_address_count(),
+                ipv6_addresses: summary.ipv6_address_count().to_string(),
+                geo_ipv4_range_count: summary.geo_ipv4_ranges.as_ref().map(Vec::len),
+                geo_ipv4_addresses: summary.geo_ipv4_address_count(),
+                asns: &summary.asns,
+                ipv4_ranges: args.ranges.then_some(ipv4_ranges),
+                ipv6_ranges: args.ranges.then_some(ipv6_ranges),
+            };
+            if matches!(format, OutputFormat::Json) {
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("{}", serde_json::to_string(&output)?);
+            }
+        }
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            if args.ranges {
+                wtr.write_record(["country", "family", "start", "end"])?;
+                for &(start, end) in &summary.ipv4_ranges {
+                    wtr.write_record([
+                        summary.country.as_str(),
+                        "ipv4",
+                        &Ipv4Addr::from(start).to_string(),
+                        &Ipv4Addr::from(end).to_string(),
+                    ])?;
+                }
+                for &(start, end) in &summary.ipv6_ranges {
+                    wtr.write_record([
+                        summary.country.as_str(),
+                        "ipv6",
+                        &Ipv6Addr::from(start).to_string(),
+                        &Ipv6Addr::from(end).to_string(),
+                    ])?;
+                }
+            } else {
+                wtr.write_record(["country", "asn", "name", "ipv4_ranges", "ipv6_ranges"])?;
+                for asn in &summary.asns {
+                    wtr.write_record([
+                        summary.country.as_str(),
+                        &asn.asn.0.to_string(),
+                        &asn.name,
+                        &asn.ipv4_ranges.to_string(),
+                        &asn.ipv6_ranges.to_string(),
+                    ])?;
+                }
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Result of a `rasn cidr` operation
+#[derive(Serialize)]
+struct CidrResult {
+    operation: &'static str,
+    input_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    output: CidrOutput,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compression_ratio: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum CidrOutput {
+    Cidrs(Vec<String>),
+    Pairs(Vec<OverlapPair>),
+    Contained(bool),
+}
+
+#[derive(Serialize)]
+struct OverlapPair {
+    cidr1: String,
+    cidr2: String,
+}
+
+fn handle_cidr(args: CidrArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    let result = match args.command {
+        CidrCommand::Analyze(args) => return handle_cidr_analyze(args, format, verbose),
+        command => run_cidr_command(command, verbose)?,
+    };
+
+    match format {
+        OutputFormat::Human => {
+            println!();
+            println!(
+                "{} {} ({} input CIDRs)",
+                "CIDR:".bold().cyan(),
+                result.operation.bold(),
+                result.input_count
+            );
+            println!("{}", "─".repeat(70).dimmed());
+            if let Some(ref target) = result.target {
+                println!("  {:<16} {}", "Target:".bold(), target);
+            }
+            match result.output {
+                CidrOutput::Cidrs(ref cidrs) => {
+                    for cidr in cidrs {
+                        println!("  {}", cidr);
+                    }
+                }
+                CidrOutput::Pairs(ref pairs) => {
+                    for pair in pairs {
+                        println!("  {} {} {}", pair.cidr1, "⊇".dimmed(), pair.cidr2);
+                    }
+                }
+                CidrOutput::Contained(contained) => {
+                    let answer = if contained { "yes".green() } else { "no".red() };
+                    println!("  {:<16} {}", "Contained:".bold(), answer);
+                }
+            }
+            if let Some(count) = result.output_count {
+                println!("{}", "─".repeat(70).dimmed());
+                println!("  {:<16} {}", "Output:".bold(), count);
+            }
+            if let Some(ratio) = result.compression_ratio {
+                println!("  {:<16} {:.2}x", "Compression:".bold(), ratio);
+            }
+            println!();
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+        OutputFormat::JsonCompact => println!("{}", serde_json::to_string(&result)?),
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            match result.output {
+                CidrOutput::Cidrs(ref cidrs) => {
+                    wtr.write_record(["cidr"])?;
+                    for cidr in cidrs {
+                        wtr.write_record([cidr])?;
+                    }
+                }
+                CidrOutput::Pairs(ref pairs) => {
+                    wtr.write_record(["cidr1", "cidr2"])?;
+                    for pair in pairs {
+                        wtr.write_record([&pair.cidr1, &pair.cidr2])?;
+                    }
+                }
+                CidrOutput::Contained(contained) => {
+                    wtr.write_record(["target", "contained"])?;
+                    wtr.write_record([
+                        result.target.clone().unwrap_or_default(),
+                        contained.to_string(),
+                    ])?;
+                }
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a CIDR operation
+fn run_cidr_command(command: CidrCommand, verbose: bool) -> Result<CidrResult> {
+    use rasn_cidr::{overlapping_pairs, CidrSet};
+
+    let (operation, list) = match command {
+        CidrCommand::RangeToCidr(ref args) => return run_range_to_cidr(args, verbose),
+        CidrCommand::Analyze(_) => unreachable!("handled by handle_cidr_analyze"),
+        CidrCommand::Aggregate(ref list) => ("aggregate", list),
+        CidrCommand::Supernet(ref list) => ("supernet", list),
+        CidrCommand::Split(ref args) => ("split", &args.list),
+        CidrCommand::Contains(ref args) => ("contains", &args.list),
+        CidrCommand::Overlaps(ref args) => ("overlaps", &args.list),
+        CidrCommand::Intersect(ref args) => ("intersect", &args.list),
+        CidrCommand::Subtract(ref args) => ("subtract", &args.list),
+    };
+    let cidrs = read_cidrs(list)?;
+    if verbose {
+        eprintln!("{} {} over {} CIDRs", "›".blue(), operation, cidrs.len());
+    }
+
+    let strings = |cidrs: Vec<rasn_cidr::Cidr>| -> Vec<String> {
+        cidrs.iter().map(|c| c.to_string()).collect()
+    };
+    let mut target = None;
+    let output = match command {
+        CidrCommand::Aggregate(_) => {
+            CidrOutput::Cidrs(strings(cidrs.iter().copied().collect::<CidrSet>().cidrs()))
+        }
+        CidrCommand::Supernet(_) => CidrOutput::Cidrs(strings(
+            cidrs
+                .iter()
+                .copied()
+                .collect::<CidrSet>()
+                .supernet()
+                .into_iter()
+                .collect(),
+        )),
+        CidrCommand::Split(ref args) => {
+            let mut subnets = Vec::new();
+            for cidr in &cidrs {
+                subnets.extend(cidr.split(args.prefix)?);
+                if subnets.len() as u64 > rasn_cidr::MAX_SPLIT {
+                    anyhow::bail!("Split produces more than {} subnets", rasn_cidr::MAX_SPLIT);
+                }
+            }
+            CidrOutput::Cidrs(strings(subnets))
+        }
+        CidrCommand::Contains(ref args) => {
+            let cidr = parse_cidr(&args.target)?;
+            target = Some(cidr.to_string());
+            let set: CidrSet = cidrs.iter().copied().collect();
+            CidrOutput::Contained(set.contains_cidr(&cidr))
+        }
+        CidrCommand::Overlaps(OverlapsArgs {
+            target: Some(ref other),
+            ..
+        }) => {
+            let cidr = parse_cidr(other)?;
+            target = Some(cidr.to_string());
+            CidrOutput::Cidrs(strings(
+                cidrs
+                    .iter()
+                    .filter(|c| c.overlaps(&cidr))
+                    .copied()
+                    .collect(),
+            ))
+        }
+        CidrCommand::Overlaps(_) => CidrOutput::Pairs(
+            overlapping_pairs(&cidrs)
+                .into_iter()
+                .map(|(outer, inner)| OverlapPair {
+                    cidr1: outer.to_string(),
+                    cidr2: inner.to_string(),
+                })
+                .collect(),
+        ),
+        CidrCommand::Intersect(ref args) | CidrCommand::Subtract(ref args) => {
+            let set: CidrSet = cidrs.iter().copied().collect();
+            let other = args
+                .with
+                .iter()
+                .map(|s| parse_cidr(s))
+                .collect::<Result<CidrSet>>()?;
+            let result = if operation == "intersect" {
+                set.intersect(&other)
+            } else {
+                set.subtract(&other)
+            };
+            CidrOutput::Cidrs(strings(result.cidrs()))
+        }
+        CidrCommand::RangeToCidr(_) | CidrCommand::Analyze(_) => unreachable!("handled above"),
+    };
+
+    let output_count = match output {
+        CidrOutput::Cidrs(ref cidrs) => Some(cidrs.len()),
+        CidrOutput::Pairs(ref pairs) => Some(pairs.len()),
+        CidrOutput::Contained(_) => None,
+    };
+    let compression_ratio = match (operation, output_count) {
+        ("aggregate", Some(count)) if count > 0 => Some(cidrs.len() as f64 / count as f64),
+        _ => None,
+    };
+
+    Ok(CidrResult {
+        operation,
+        input_count: cidrs.len(),
+        target,
+        output,
+        output_count,
+        compression_ratio,
+    })
+}
+
+/// Analyse a CIDR against the table of its family
+///
+/// Fails when that table is not loaded, since the report would be empty.
+fn analyze_cidr(
+    database: &rasn_arrow::IpAsnDatabase,
+    cidr: &str,
+    limit: usize,
+) -> Result<rasn_arrow::CidrAnalysis> {
+    let cidr = rasn_cidr::IpCidr::parse(cidr.trim())?;
+    let result = database.analyze_cidr(&cidr, limit);
+    if result.coverage.is_none() {
+        anyhow::bail!(
+            "No {} range table loaded",
+            if cidr.is_ipv6() { "IPv6" } else { "IPv4" }
+        );
+    }
+    Ok(result)
+}
+
+fn handle_cidr_analyze(args: AnalyzeArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    let database = load_database(verbose)
+        .ok_or_else(|| anyhow::anyhow!("No data found. Install data with: make install-data"))?
+        .load();
+    let result = analyze_cidr(&database, &args.cidr, args.limit)?;
+
+    match format {
+        OutputFormat::Human => print_cidr_analyze_human(&result),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+        OutputFormat::JsonCompact => println!("{}", serde_json::to_string(&result)?),
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            wtr.write_record([
+                "asn",
+                "organization",
+                "countries",
+                "ranges",
+                "addresses",
+                "percent",
+            ])?;
+            for asn in result.coverage.iter().flat_map(|c| &c.asns) {
+                wtr.write_record([
+                    asn.asn.0.to_string(),
+                    asn.organization.clone(),
+                    asn.countries.join(" "),
+                    asn.ranges.to_string(),
+                    asn.addresses.to_string(),
+                    format!("{:.4}", asn.percent),
+                ])?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn print_cidr_analyze_human(result: &rasn_arrow::CidrAnalysis) {
+    println!();
+    println!(
+        "{} {} ({})",
+        "CIDR Analysis:".bold().cyan(),
+        result.cidr.bold(),
+        result.family
+    );
+    println!("{}", "─".repeat(70).dimmed());
+    println!(
+        "  {:<16} {} - {}",
+        "Range:".bold(),
+        result.start,
+        result.end
+    );
+    println!("  {:<16} {}", "Addresses:".bold(), result.total_addresses);
+
+    let Some(coverage) = &result.coverage else {
+        println!();
+        return;
+    };
+    println!(
+        "  {:<16} {} ({:.2}%, {} ranges)",
+        "Covered:".bold(),
+        coverage.covered_addresses,
+        coverage.percent_covered,
+        coverage.ranges
+    );
+
+    let more = |shown: usize, total: usize| {
+        if total > shown {
+            println!("  {}", format!("... {} more", total - shown).dimmed());
+        }
+    };
+
+    if !coverage.asns.is_empty() {
+        println!();
+        println!("  {}", "ASNs:".bold());
+        for asn in &coverage.asns {
+            println!(
+                "    {:<10} {:>7.2}%  {:>12}  {} [{}]",
+                asn.asn.to_string().green(),
+                asn.percent,
+                asn.addresses,
+                asn.organization,
+                asn.countries.join(", ")
+            );
+        }
+        more(coverage.asns.len(), coverage.asn_count);
+    }
+
+    if !coverage.countries.is_empty() {
+        println!();
+        println!("  {}", "Countries:".bold());
+        for country in &coverage.countries {
+            let code = if country.country.is_empty() {
+                "??"
+            } else {
+                &country.country
+            };
+            println!(
+                "    {:<10} {:>7.2}%  {:>12}  {} ASNs",
+                code, country.percent, country.addresses, country.asns
+            );
+        }
+        more(coverage.countries.len(), coverage.country_count);
+    }
+
+    if !coverage.gaps.is_empty() {
+        println!();
+        println!("  {}", "Unallocated:".bold());
+        for gap in &coverage.gaps {
+            println!(
+                "    {} - {} ({} addresses)",
+                gap.start, gap.end, gap.addresses
+            );
+        }
+        more(coverage.gaps.len(), coverage.gap_count);
+    }
+    println!();
+}
+
+/// Convert address ranges to CIDRs
+///
+/// Unlike `aggregate`, each range is converted on its own, so the output
+/// keeps the input order and overlapping ranges are not merged.
+fn run_range_to_cidr(args: &RangeToCidrArgs, verbose: bool) -> Result<CidrResult> {
+    use std::io::BufRead;
+
+    let lines: Vec<String> = if args.ranges.is_empty() {
+        std::io::stdin()
+            .lock()
+            .lines()
+            .collect::<std::io::Result<_>>()?
+    } else {
+        args.ranges.clone()
+    };
+
+    let mut ranges = Vec::new();
+    for line in &lines {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if !line.is_empty() {
+            ranges.push(parse_range(line)?);
+        }
+    }
+    if ranges.is_empty() {
+        anyhow::bail!("No ranges given");
+    }
+    if verbose {
+        eprintln!("{} range2cidr over {} ranges", "›".blue(), ranges.len());
+    }
+
+    let mut cidrs = Vec::new();
+    for &(start, end) in &ranges {
+        cidrs.extend(
+            rasn_cidr::ip_range_to_cidrs(start, end)?
+                .iter()
+                .map(|c| c.to_string()),
+        );
+    }
+
+    Ok(CidrResult {
+        operation: "range2cidr",
+        input_count: ranges.len(),
+        target: None,
+        output_count: Some(cidrs.len()),
+        output: CidrOutput::Cidrs(cidrs),
+        compression_ratio: None,
+    })
+}
+
+/// Parse `START-END`, or START and END as the first two whitespace-separated
+/// columns (iptoasn TSV rows)
+fn parse_range(s: &str) -> Result<(std::net::IpAddr, std::net::IpAddr)> {
+    let (start, end) = match s.split_once('-') {
+        Some((start, end)) => (start.trim(), end.trim()),
+        None => {
+            let mut columns = s.split_whitespace();
+            match (columns.next(), columns.next()) {
+                (Some(start), Some(end)) => (start, end),
+                _ => anyhow::bail!("Invalid range '{}', expected START-END", s),
+            }
+        }
+    };
+
+    let parse = |ip: &str| {
+        ip.parse::<std::net::IpAddr>()
+            .map_err(|_| anyhow::anyhow!("Invalid IP address in range '{}': {}", s, ip))
+    };
+    Ok((parse(start)?, parse(end)?))
+}
+
+/// Parse a CIDR, taking a bare address as a /32
+fn parse_cidr(s: &str) -> Result<rasn_cidr::Cidr> {
+    let s = s.trim();
+    // Set operations work on IPv4 ranges; say so instead of "invalid IPv4"
+    if s.contains(':') {
+        anyhow::bail!("CIDR set operations support IPv4 only, got {}", s);
+    }
+    if s.contains('/') {
+        Ok(rasn_cidr::Cidr::parse(s)?)
+    } else {
+        Ok(rasn_cidr::Cidr::new(parse_ip(s)?, 32)?)
+    }
+}
+
+/// Parse the CIDR list arguments, reading stdin when none are given
+///
+/// Blank lines and `#` comments in stdin are skipped.
+fn read_cidrs(list: &CidrListArgs) -> Result<Vec<rasn_cidr::Cidr>> {
+    use std::io::BufRead;
+
+    let cidrs = if list.cidrs.is_empty() {
+        let mut cidrs = Vec::new();
+        for line in std::io::stdin().lock().lines() {
+            let line = line?;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if !line.is_empty() {
+                cidrs.push(parse_cidr(line)?);
+            }
+        }
+        cidrs
+    } else {
+        list.cidrs
+            .iter()
+            .map(|s| parse_cidr(s))
+            .collect::<Result<_>>()?
+    };
+
+    if cidrs.is_empty() {
+        anyhow::bail!("No CIDRs given");
+    }
+    Ok(cidrs)
+}
+
+fn handle_build_db(args: BuildDbArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    use rasn_arrow::builder::DatabaseBuilder;
+
+    let arrow_dir = args.data_dir.join("arrow");
+    if verbose {
+        eprintln!("{} Input:  {:?}", "›".blue(), args.reference);
+        eprintln!("{} Output: {:?}", "›".blue(), arrow_dir);
+    }
+
+    let report = DatabaseBuilder::new(&args.reference, &arrow_dir)
+        .build_all()
+        .map_err(|e| anyhow::anyhow!("Database build failed: {}", e))?;
+
+    match format {
+        OutputFormat::Human => {
+            println!();
+            println!("{}", "Database Build Summary".bold().cyan());
+            println!("{}", "─".repeat(70).dimmed());
+            for table in report.tables() {
+                let name = table
+                    .output
+                    .file_name()
+                    .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+                println!(
+                    "  {} {:<24} {:>9} records {:>7} skipped {:>9.1} MB",
+                    "✓".green(),
+                    name,
+                    table.records,
+                    table.skipped,
+                    table.output_bytes as f64 / 1024.0 / 1024.0
+                );
+            }
+            println!();
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::JsonCompact => println!("{}", serde_json::to_string(&report)?),
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            wtr.write_record([
+                "input",
+                "output",
+                "records",
+                "skipped",
+                "input_bytes",
+                "output_bytes",
+            ])?;
+            for table in report.tables() {
+                wtr.write_record([
+                    table.input.display().to_string(),
+                    table.output.display().to_string(),
+                    table.records.to_string(),
+                    table.skipped.to_string(),
+                    table.input_bytes.to_string(),
+                    table.output_bytes.to_string(),
+                ])?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_db(args: DbArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    match args.command {
+        DbCommand::Verify(args) => handle_verify(args, format, verbose),
+        DbCommand::Convert(args) => handle_convert(args, format, verbose),
+        DbCommand::Info(args) => handle_info(args, format, verbose),
+        DbCommand::Diff(args) => handle_diff(args, format, verbose),
+    }
+}
+
+/// A range table of either address family
+enum RangeTable {
+    V4(rasn_arrow::IpRangeTableV4),
+    V6(rasn_arrow::IpRangeTableV6),
+}
+
+impl RangeTable {
+    /// Open a Parquet or Arrow IPC range table
+    ///
+    /// The schema tells the address family apart: IPv4 is tried first, then IPv6.
+    fn open(path: &std::path::Path) -> Result<Self> {
+        use rasn_arrow::{ArrowError, IpRangeTableV4, IpRangeTableV6};
+
+        match IpRangeTableV4::open(path) {
+            Ok(table) => Ok(RangeTable::V4(table)),
+            Err(ArrowError::InvalidSchema(v4_error)) => match IpRangeTableV6::open(path) {
+                Ok(table) => Ok(RangeTable::V6(table)),
+                Err(_) => anyhow::bail!("Not a range table: {}", v4_error),
+            },
+            Err(e) => anyhow::bail!("Failed to load {:?}: {}", path, e),
+        }
+    }
+
+    fn family(&self) -> &'static str {
+        match self {
+            RangeTable::V4(_) => "ipv4",
+            RangeTable::V6(_) => "ipv6",
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            RangeTable::V4(table) => table.len(),
+            RangeTable::V6(table) => table.len(),
+        }
+    }
+
+    fn validate(&self) -> rasn_arrow::ValidationReport {
+        match self {
+            RangeTable::V4(table) => table.validate(),
+            RangeTable::V6(table) => table.validate(),
+        }
+    }
+
+    fn dataset_info(&self) -> Option<&rasn_arrow::DatasetInfo> {
+        match self {
+            RangeTable::V4(table) => table.dataset_info(),
+            RangeTable::V6(table) => table.dataset_info(),
+        }
+    }
+
+    fn write_ipc(&self, path: &std::path::Path) -> rasn_arrow::Result<()> {
+        match self {
+            RangeTable::V4(table) => table.write_ipc(path),
+            RangeTable::V6(table) => table.write_ipc(path),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct VerifyResult {
+    file: String,
+    family: &'static str,
+    valid: bool,
+    #[serde(flatten)]
+    report: rasn_arrow::ValidationReport,
+}
+
+fn handle_verify(args: VerifyArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    use std::io::Write;
+
+    if verbose {
+        eprintln!("{} Verifying {:?}", "›".blue(), args.file);
+    }
+
+    let table = RangeTable::open(&args.file)?;
+    let report = table.validate();
+
+    let result = VerifyResult {
+        file: args.file.display().to_string(),
+        family: table.family(),
+        valid: report.is_valid(),
+        report,
+    };
+    let address = |value: u128| match result.family {
+        "ipv4" => std::net::Ipv4Addr::from(value as u32).to_string(),
+        _ => std::net::Ipv6Addr::from(value).to_string(),
+    };
+
+    match format {
+        OutputFormat::Human => {
+            let report = &result.report;
+            println!();
+            println!(
+                "{} {} ({})",
+                "Integrity Report:".bold().cyan(),
+                result.file.bold(),
+                result.family
+            );
+            println!("{}", "─".repeat(70).dimmed());
+            println!("  {:<16} {}", "Rows:".bold(), report.rows);
+            println!(
+                "  {:<16} {} addresses",
+                "Covered:".bold(),
+                report.covered_addresses
+            );
+            print!(
+                "  {:<16} {} ({} addresses",
+                "Gaps:".bold(),
+                report.gaps,
+                report.gap_addresses
+            );
+            match report.largest_gap {
+                Some((start, end)) => {
+                    println!(", largest {} - {})", address(start), address(end))
+                }
+                None => println!(")"),
+            }
+            for (label, count) in [
+                ("Unsorted:", report.unsorted),
+                ("Overlaps:", report.overlaps),
+                ("Inverted:", report.inverted),
+                ("Column length:", report.column_mismatches),
+            ] {
+                let count = if count == 0 {
+                    count.to_string().green()
+                } else {
+                    count.to_string().red()
+                };
+                println!("  {:<16} {}", label.bold(), count);
+            }
+
+            if !report.issues.is_empty() {
+                println!();
+                for issue in report.issues.iter().take(10) {
+                    println!(
+                        "  {} row {:<9} {}",
+                        "✗".red(),
+                        issue.row,
+                        issue.message.dimmed()
+                    );
+                }
+                let shown = report.issues.len().min(10);
+                if report.issue_count() > shown {
+                    println!(
+                        "  {} more (use -o json for details)",
+                        (report.issue_count() - shown).to_string().dimmed()
+                    );
+                }
+            }
+
+            println!();
+            if result.valid {
+                println!("{}", "✓ Table is valid".green());
+            } else {
+                println!(
+                    "{}",
+                    format!("✗ Table is corrupt: {} violations", report.issue_count()).red()
+                );
+            }
+            println!();
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+        OutputFormat::JsonCompact => println!("{}", serde_json::to_string(&result)?),
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            wtr.write_record(["kind", "row", "message"])?;
+            for issue in &result.report.issues {
+                wtr.write_record([
+                    issue.kind.to_string(),
+                    issue.row.to_string(),
+                    issue.message.clone(),
+                ])?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    if !result.valid {
+        std::io::stdout().flush()?;
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ConvertResult {
+    input: String,
+    output: String,
+    family: &'static str,
+    records: usize,
+    output_bytes: u64,
+}
+
+fn handle_convert(args: ConvertArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    let output = args
+        .out
+        .unwrap_or_else(|| args.file.with_extension("arrow"));
+    if verbose {
+        eprintln!("{} Input:  {:?}", "›".blue(), args.file);
+        eprintln!("{} Output: {:?}", "›".blue(), output);
+    }
+
+    let table = RangeTable::open(&args.file)?;
+    table
+        .write_ipc(&output)
+        .map_err(|e| anyhow::anyhow!("Failed to write {:?}: {}", output, e))?;
+
+    let result = ConvertResult {
+        input: args.file.display().to_string(),
+        output: output.display().to_string(),
+        family: table.family(),
+        records: table.len(),
+        output_bytes: std::fs::metadata(&output)?.len(),
+    };
+
+    match format {
+        OutputFormat::Human => {
+            println!(
+                "{} {} ({} {} ranges, {:.1} MB)",
+                "✓".green(),
+                result.output.bold(),
+                result.records,
+                result.family,
+                result.output_bytes as f64 / 1024.0 / 1024.0
+            );
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+        OutputFormat::JsonCompact => println!("{}", serde_json::to_string(&result)?),
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            wtr.serialize(&result)?;
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct DiffResult {
+    old: String,
+    new: String,
+    family: &'static str,
+    #[serde(flatten)]
+    diff: rasn_arrow::TableDiff,
+}
+
+fn handle_diff(args: DiffArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    if verbose {
+        eprintln!("{} Old: {:?}", "›".blue(), args.old);
+        eprintln!("{} New: {:?}", "›".blue(), args.new);
+    }
+
+    let old = RangeTable::open(&args.old)?;
+    let new = RangeTable::open(&args.new)?;
+    let diff = match (&old, &new) {
+        (RangeTable::V4(old), RangeTable::V4(new)) => old.diff(new),
+        (RangeTable::V6(old), RangeTable::V6(new)) => old.diff(new),
+        _ => anyhow::bail!(
+            "Cannot compare an {} table with an {} table",
+            old.family(),
+            new.family()
+        ),
+    };
+
+    let result = DiffResult {
+        old: args.old.display().to_string(),
+        new: args.new.display().to_string(),
+        family: old.family(),
+        diff,
+    };
+
+    match format {
+        OutputFormat::Human => print_diff_human(&result, args.limit),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+        OutputFormat::JsonCompact => println!("{}", serde_json::to_string(&result)?),
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            wtr.write_record([
+                "kind",
+                "start",
+                "end",
+                "addresses",
+                "old_asn",
+                "new_asn",
+                "old_organization",
+                "new_organization",
+                "old_countries",
+                "new_countries",
+            ])?;
+            let asn = |asn: Option<rasn_core::Asn>| asn.map_or(String::new(), |a| a.0.to_string());
+            for change in &result.diff.ranges {
+                wtr.write_record([
+                    change.kind.to_string(),
+                    change.start.to_string(),
+                    change.end.to_string(),
+                    change.addresses.to_string(),
+                    asn(change.old_asn),
+                    asn(change.new_asn),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ])?;
+            }
+            for change in &result.diff.asns {
+                wtr.write_record([
+                    "asn_metadata_changed".to_string(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    change.asn.0.to_string(),
+                    change.asn.0.to_string(),
+                    change.old_organization.clone(),
+                    change.new_organization.clone(),
+                    change.old_countries.join(" "),
+                    change.new_countries.join(" "),
+                ])?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn print_diff_human(result: &DiffResult, limit: usize) {
+    let summary = &result.diff.summary;
+    let asn = |asn: Option<rasn_core::Asn>| asn.map_or("-".to_string(), |a| format!("AS{}", a.0));
+
+    println!();
+    println!(
+        "{} {} → {} ({})",
+        "Dataset Diff:".bold().cyan(),
+        result.old.bold(),
+        result.new.bold(),
+        result.family
+    );
+    println!("{}", "─".repeat(70).dimmed());
+    println!(
+        "  {:<16} {} → {}",
+        "Ranges:".bold(),
+        summary.old_ranges,
+        summary.new_ranges
+    );
+    for (label, count, addresses) in [
+        (
+            "ASN changed:",
+            summary.asn_changed,
+            summary.asn_changed_addresses,
+        ),
+        ("Added:", summary.added, summary.added_addresses),
+        ("Withdrawn:", summary.withdrawn, summary.withdrawn_addresses),
+    ] {
+        println!(
+            "  {:<16} {} ranges ({} addresses)",
+            label.bold(),
+            count,
+            addresses
+        );
+    }
+    println!(
+        "  {:<16} {} new, {} removed, {} changed",
+        "ASNs:".bold(),
+        summary.new_asns,
+        summary.removed_asns,
+        summary.changed_asns
+    );
+
+    if !result.diff.ranges.is_empty() {
+        println!();
+        for change in result.diff.ranges.iter().take(limit) {
+            let kind = match change.kind {
+                rasn_arrow::ChangeKind::AsnChanged => "~".yellow(),
+                rasn_arrow::ChangeKind::Added => "+".green(),
+                rasn_arrow::ChangeKind::Withdrawn => "-".red(),
+            };
+            println!(
+                "  {} {} - {}  {} → {}",
+                kind,
+                change.start,
+                change.end,
+                asn(change.old_asn),
+                asn(change.new_asn)
+            );
+        }
+        if result.diff.ranges.len() > limit {
+            println!(
+                "  {} more ranges (use -o json for details)",
+                (result.diff.ranges.len() - limit).to_string().dimmed()
+            );
+        }
+    }
+
+    if !result.diff.asns.is_empty() {
+        println!();
+        for change in result.diff.asns.iter().take(limit) {
+            print!("  {} AS{}", "~".yellow(), change.asn.0);
+            if change.old_organization != change.new_organization {
+                print!(
+                    "  org: {} → {}",
+                    change.old_organization, change.new_organization
+                );
+            }
+            if change.old_countries != change.new_countries {
+                print!(
+                    "  countries: {} → {}",
+                    change.old_countries.join(","),
+                    change.new_countries.join(",")
+                );
+            }
+            println!();
+        }
+        if result.diff.asns.len() > limit {
+            println!(
+                "  {} more ASNs (use -o json for details)",
+                (result.diff.asns.len() - limit).to_string().dimmed()
+            );
+        }
+    }
+
+    println!();
+    if result.diff.is_empty() {
+        println!("{}", "✓ No changes".green());
+        println!();
+    }
+}
+
+#[derive(Serialize)]
+struct DatasetResult {
+    path: String,
+    family: &'static str,
+    ranges: usize,
+    schema_version: Option<u32>,
+    build_time: Option<String>,
+    source_file: Option<String>,
+    source_url: Option<String>,
+    source_sha256: Option<String>,
+    row_count: Option<usize>,
+}
+
+impl DatasetResult {
+    fn new(
+        path: &std::path::Path,
+        family: &'static str,
+        ranges: usize,
+        info: Option<&rasn_arrow::DatasetInfo>,
+    ) -> Self {
+        let info = info.cloned().unwrap_or_default();
+        Self {
+            path: path.display().to_string(),
+            family,
+            ranges,
+            schema_version: info.schema_version,
+            build_time: info.build_time,
+            source_file: info.source_file,
+            source_url: info.source_url,
+            source_sha256: info.source_sha256,
+            row_count: info.row_count,
+        }
+    }
+}
+
+fn handle_info(args: InfoArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    use rasn_arrow::IpAsnDatabase;
+
+    let (path, database) = match args.path {
+        Some(path) if path.is_dir() => {
+            let database = IpAsnDatabase::open(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to load {:?}: {}", path, e))?;
+            (path, std::sync::Arc::new(database))
+        }
+        Some(path) => {
+            let table = RangeTable::open(&path)?;
+            let result =
+                DatasetResult::new(&path, table.family(), table.len(), table.dataset_info());
+            return print_dataset_results(&[result], format);
+        }
+        None => {
+            let handle = load_database(verbose).ok_or_else(|| {
+                anyhow::anyhow!("No database found (set RASN_DATA_DIR or pass a PATH)")
+            })?;
+            let path = handle.path().map(|p| p.to_path_buf()).unwrap_or_default();
+            (path, handle.load())
+        }
+    };
+
+    let mut results = Vec::new();
+    if let Some(table) = database.v4() {
+        results.push(DatasetResult::new(
+            &path,
+            "ipv4",
+            table.len(),
+            table.dataset_info(),
+        ));
+    }
+    if let Some(table) = database.v6() {
+        results.push(DatasetResult::new(
+            &path,
+            "ipv6",
+            table.len(),
+            table.dataset_info(),
+        ));
+    }
+    if results.is_empty() {
+        anyhow::bail!("No range tables in {:?}", path);
+    }
+
+    print_dataset_results(&results, format)
+}
+
+fn print_dataset_results(results: &[DatasetResult], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Human => {
+            for result in results {
+                println!();
+                println!(
+                    "{} {} ({}, {} ranges)",
+                    "Dataset Info:".bold().cyan(),
+                    result.path.bold(),
+                    result.family,
+                    result.ranges
+                );
+                println!("{}", "─".repeat(70).dimmed());
+
+                let Some(ref build_time) = result.build_time else {
+                    println!(
+                        "  {}",
+                        "No build metadata (built before it was recorded)".dimmed()
+                    );
+                    continue;
+                };
+                let unknown = || "unknown".to_string();
+                println!("  {:<16} {}", "Built:".bold(), build_time);
+                println!(
+                    "  {:<16} {}",
+                    "Source file:".bold(),
+                    result.source_file.clone().unwrap_or_else(unknown)
+                );
+                println!(
+                    "  {:<16} {}",
+                    "Source URL:".bold(),
+                    result.source_url.clone().unwrap_or_else(unknown)
+                );
+                println!(
+                    "  {:<16} {}",
+                    "SHA-256:".bold(),
+                    result.source_sha256.clone().unwrap_or_else(unknown)
+                );
+                println!(
+                    "  {:<16} {}",
+                    "Rows:".bold(),
+                    result.row_count.map_or_else(unknown, |n| n.to_string())
+                );
+                println!(
+                    "  {:<16} {}",
+                    "Schema:".bold(),
+                    result
+                        .schema_version
+                        .map_or_else(unknown, |v| format!("v{}", v))
+                );
+            }
+            println!();
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(results)?),
+        OutputFormat::JsonCompact => println!("{}", serde_json::to_string(results)?),
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            for result in results {
+                wtr.serialize(result)?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_mcp(args: McpArgs, verbose: bool) -> Result<()> {
+    let database = load_database(verbose).map(std::sync::Arc::new);
+    let server = rasn_mcp::McpServer::with_database_handle(database.clone())
+        .map_err(|e| anyhow::anyhow!("Failed to create MCP server: {}", e))?;
+    // Share the server's cache so reloads also clear the chain's cache tier
+    let chain = build_chain(database, &args.chain, server.cache(), verbose)?;
+    let mut server = server.with_chain(chain);
+    if let Some(store) = open_snapshot_store(&args.snapshots, verbose)? {
+        server = server.with_snapshots(store);
+    }
+    let server = std::sync::Arc::new(server);
+
+    if !args.no_watch {
+        if verbose {
+            eprintln!(
+                "{} Reloading data on SIGHUP or file changes (checked every {}s)",
+                "›".blue(),
+                args.reload_interval
+            );
+        }
+        rasn_mcp::reload::spawn_reload_watcher(
+            server.clone(),
+            std::time::Duration::from_secs(args.reload_interval.max(1)),
+            |result| match result {
+                Ok(report) => eprintln!(
+                    "{} Reloaded {} IPv4 and {} IPv6 ranges",
+                    "✓".green(),
+                    report.ipv4_ranges,
+                    report.ipv6_ranges
+                ),
+                Err(e) => eprintln!("{} Reload failed, keeping current data: {}", "✗".red(), e),
+            },
+        )?;
+    }
+
+    match args.transport {
+        TransportMode::Stdio => {
+            if verbose {
+                eprintln!("{} Starting MCP server on STDIO", "›".blue());
+            }
+            let transport = rasn_mcp::transport::StdioTransport::new(server);
+            transport
+                .run_blocking()
+                .map_err(|e| anyhow::anyhow!("STDIO transport error: {}", e))?;
+        }
+        TransportMode::Http => {
+            if verbose {
+                eprintln!(
+                    "{} Starting MCP server on HTTP port {}",
+                    "›".blue(),
+                    args.port
+                );
+            }
+            println!("{}", "HTTP transport - Use STDIO for now".yellow());
+            println!("STDIO mode is fully operational for Claude Desktop integration");
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_auth(args: AuthArgs, verbose: bool) -> Result<()> {
+    use rasn_core::security::KeyManager;
+
+    let manager = KeyManager::new();
+
+    match args.command {
+        AuthCommand::Status => {
+            if verbose {
+                eprintln!("{} Checking API key status", "›".blue());
+            }
+
+            if manager.has_api_key() {
+                let masked = manager
+                    .get_masked_key()
+                    .unwrap_or_else(|_| "****".to_string());
+                println!("{}", "✓ API key configured".green());
+                println!("  Key: {}", masked);
+            } else {
+                println!("{}", "✗ No API key configured".yellow());
+                println!("  Set RASN_API_KEY environment variable");
+            }
+        }
+        AuthCommand::Info => {
+            println!("{}", "API Key Configuration".bold().cyan());
+            println!("{}", "─".repeat(50).dimmed());
+            println!("  Environment variable: RASN_API_KEY");
+            println!("  Usage: export RASN_API_KEY=your_key_here");
+            println!();
+            println!("  Status: rasn auth status");
+        }
+    }
+
+    Ok(())
+}
+
+fn print_result(result: &LookupResult, format: OutputFormat) -> Result<()> {
     match format {
         OutputFormat::Human => print_human(result),
         OutputFormat::Json => print_json(result, true)?,
@@ -385,6 +2295,10 @@ fn print_human(result: &LookupResult) {
         println!("{:>15}: {}", "Organization".bold(), org);
     }
 
+    if let Some(ref handle) = result.handle {
+        println!("{:>15}: {}", "Handle".bold(), handle);
+    }
+
     if let Some(ref country) = result.country {
         println!("{:>15}: {}", "Country".bold(), country);
     }
@@ -392,6 +2306,27 @@ fn print_human(result: &LookupResult) {
     if let Some(ref desc) = result.description {
         println!("{:>15}: {}", "Description".bold(), desc);
     }
+
+    if let Some(source) = result.source {
+        println!("{:>15}: {}", "Source".bold(), source.as_str().dimmed());
+    }
+
+    if let Some(snapshot) = result.snapshot {
+        println!(
+            "{:>15}: {}",
+            "Snapshot".bold(),
+            snapshot.to_string().dimmed()
+        );
+    }
+
+    if let Some(special) = result.special {
+        println!(
+            "{:>15}: {}",
+            "Class".bold(),
+            special.class.as_str().yellow()
+        );
+        println!("{:>15}: {}", "Block".bold(), special.cidr());
+    }
     println!();
 }
 
@@ -406,14 +2341,37 @@ fn print_json(result: &LookupResult, pretty: bool) -> Result<()> {
 
 fn print_csv(result: &LookupResult) -> Result<()> {
     let mut wtr = csv::Writer::from_writer(std::io::stdout());
-    wtr.write_record(["target", "asn", "organization", "country", "description"])?;
-    wtr.write_record([
-        &result.target,
-        &result.asn.map_or(String::new(), |a| a.to_string()),
-        result.organization.as_deref().unwrap_or(""),
-        result.country.as_deref().unwrap_or(""),
-        result.description.as_deref().unwrap_or(""),
-    ])?;
+    let mut header = vec![
+        "target",
+        "asn",
+        "organization",
+        "handle",
+        "country",
+        "description",
+        "source",
+    ];
+    let mut record = vec![
+        result.target.clone(),
+        result.asn.map_or(String::new(), |a| a.to_string()),
+        result.organization.clone().unwrap_or_default(),
+        result.handle.clone().unwrap_or_default(),
+        result.country.clone().unwrap_or_default(),
+        result.description.clone().unwrap_or_default(),
+        result
+            .source
+            .map_or(String::new(), |source| source.to_string()),
+    ];
+    // Point-in-time lookups name the snapshot that answered
+    if let Some(snapshot) = result.snapshot {
+        header.push("snapshot");
+        record.push(snapshot.to_string());
+    }
+    if let Some(special) = result.special {
+        header.extend(["class", "block"]);
+        record.extend([special.class.to_string(), special.cidr().to_string()]);
+    }
+    wtr.write_record(&header)?;
+    wtr.write_record(&record)?;
     wtr.flush()?;
     Ok(())
 }
@@ -429,6 +2387,172 @@ mod tests {
         assert!(matches!(cli.output, OutputFormat::Human));
     }
 
+    #[test]
+    fn test_lookup_target() {
+        let database =
+            rasn_arrow::IpAsnDatabase::new(Some(rasn_arrow::test_util::google_v4(15169)), None);
+
+        let result = lookup_target(&database, " 8.8.8.8 ").unwrap();
+        assert_eq!(result.asn, Some(15169));
+        assert_eq!(result.description.as_deref(), Some("AS15169"));
+        assert_eq!(result.source, Some(rasn_core::LookupSource::Arrow));
+
+        let result = lookup_target(&database, "1.1.1.1").unwrap();
+        assert_eq!(result.asn, None);
+        assert_eq!(result.organization.as_deref(), Some("Not Found"));
+        assert!(result.special.is_none());
+
+        // Special-purpose blocks are classified instead of missing
+        let result = lookup_target(&database, "100.64.0.1").unwrap();
+        assert_eq!(result.asn, None);
+        assert_eq!(result.organization.as_deref(), Some("Shared Address Space"));
+        assert_eq!(
+            result.special.map(|s| s.class),
+            Some(rasn_cidr::AddressClass::Cgnat)
+        );
+        let result = lookup_target(&database, "fd00::1").unwrap();
+        assert_eq!(
+            result.special.map(|s| s.class),
+            Some(rasn_cidr::AddressClass::Private)
+        );
+
+        assert!(lookup_target(&database, "not-an-ip").is_err());
+    }
+
+    #[test]
+    fn test_lookup_target_failing_tier() {
+        use rasn_core::{AsnLookup, LookupAnswer, LookupChain, LookupSource, RasnError};
+
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Fails every lookup, counting them
+        #[derive(Default)]
+        struct Failing(AtomicUsize);
+
+        impl AsnLookup for Failing {
+            fn source(&self) -> LookupSource {
+                LookupSource::Whois
+            }
+
+            fn lookup(&self, _ip: std::net::IpAddr) -> rasn_core::Result<Option<LookupAnswer>> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Err(RasnError::Network("unreachable".to_string()))
+            }
+        }
+
+        let whois = std::sync::Arc::new(Failing::default());
+        let chain = LookupChain::new().with_tier(whois.clone(), std::time::Duration::from_secs(1));
+
+        // Never-routed addresses are classified without querying any tier
+        let result = lookup_target(&chain, "10.0.0.1").unwrap();
+        assert_eq!(result.asn, None);
+        assert_eq!(
+            result.special.map(|s| s.class),
+            Some(rasn_cidr::AddressClass::Private)
+        );
+        assert_eq!(whois.0.load(Ordering::SeqCst), 0);
+
+        // Other reserved blocks are classified after the failing tier
+        let result = lookup_target(&chain, "198.18.0.1").unwrap();
+        assert_eq!(
+            result.special.map(|s| s.class),
+            Some(rasn_cidr::AddressClass::Reserved)
+        );
+        assert_eq!(whois.0.load(Ordering::SeqCst), 1);
+
+        // Public addresses still report the failure
+        let Err(err) = lookup_target(&chain, "8.8.8.8") else {
+            panic!("expected the tier failure");
+        };
+        assert!(err.to_string().contains("unreachable"));
+
+        // A snapshot store without a snapshot at the date
+        let dir = tempfile::tempdir().unwrap();
+        let store = rasn_arrow::SnapshotStore::open(dir.path()).unwrap();
+        let date = "2026-03-01".parse().unwrap();
+        let result = lookup_target_at(&store, "192.0.2.1", date).unwrap();
+        assert_eq!(
+            result.special.map(|s| s.class),
+            Some(rasn_cidr::AddressClass::Documentation)
+        );
+        assert!(lookup_target_at(&store, "8.8.8.8", date).is_err());
+    }
+
+    #[test]
+    fn test_lookup_at_flag() {
+        let cli = Cli::parse_from([
+            "rasn",
+            "lookup",
+            "8.8.8.8",
+            "--at",
+            "2026-03-01",
+            "--snapshots",
+            "/var/lib/rasn/snapshots",
+        ]);
+        let Commands::Lookup(args) = cli.command else {
+            panic!("expected lookup command");
+        };
+        assert_eq!(args.at.unwrap().to_string(), "2026-03-01");
+        assert_eq!(
+            args.snapshots.dir,
+            Some(std::path::PathBuf::from("/var/lib/rasn/snapshots"))
+        );
+
+        assert!(Cli::try_parse_from(["rasn", "lookup", "8.8.8.8", "--at", "2026-02-30"]).is_err());
+    }
+
+    #[test]
+    fn test_build_chain() {
+        use rasn_core::LookupSource;
+
+        let cli = Cli::parse_from([
+            "rasn",
+            "lookup",
+            "8.8.8.8",
+            "--tiers",
+            "cache,arrow,cold_storage",
+            "--tier-timeout",
+            "cache=5",
+        ]);
+        let Commands::Lookup(args) = cli.command else {
+            panic!("expected lookup command");
+        };
+        assert_eq!(
+            args.chain.tiers,
+            vec![
+                LookupSource::Cache,
+                LookupSource::Arrow,
+                LookupSource::ColdStorage
+            ]
+        );
+        assert_eq!(
+            args.chain.tier_timeout,
+            vec![(LookupSource::Cache, std::time::Duration::from_millis(5))]
+        );
+
+        // No database and no cold storage directory: only the cache is left
+        let cache = std::sync::Arc::new(rasn_cache::CacheLayer::new(100).unwrap());
+        let chain = build_chain(None, &args.chain, cache.clone(), false).unwrap();
+        assert_eq!(chain.sources(), vec![LookupSource::Cache]);
+
+        let database = rasn_arrow::DatabaseHandle::new(rasn_arrow::IpAsnDatabase::new(None, None));
+        let chain = build_chain(
+            Some(std::sync::Arc::new(database)),
+            &args.chain,
+            cache,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            chain.sources(),
+            vec![LookupSource::Cache, LookupSource::Arrow]
+        );
+
+        assert!(parse_tier_timeout("api").is_err());
+        assert!(parse_tier_timeout("geoip=10").is_err());
+        assert!(parse_tier_timeout("api=soon").is_err());
+    }
+
     #[test]
     fn test_output_format_json() {
         let cli = Cli::parse_from(["rasn", "--output", "json", "lookup", "AS15169"]);
@@ -452,11 +2576,281 @@ mod tests {
         if let Commands::Mcp(args) = cli.command {
             assert!(matches!(args.transport, TransportMode::Http));
             assert_eq!(args.port, 9090);
+            assert_eq!(args.reload_interval, 30);
+            assert!(!args.no_watch);
+        } else {
+            panic!("Expected Mcp command");
+        }
+
+        let cli = Cli::parse_from(["rasn", "mcp", "--reload-interval", "5", "--no-watch"]);
+        if let Commands::Mcp(args) = cli.command {
+            assert_eq!(args.reload_interval, 5);
+            assert!(args.no_watch);
         } else {
             panic!("Expected Mcp command");
         }
     }
 
+    #[test]
+    fn test_search_org_command() {
+        let cli = Cli::parse_from(["rasn", "search-org", "google", "--country", "US", "--exact"]);
+        if let Commands::SearchOrg(args) = cli.command {
+            assert_eq!(args.query, "google");
+            assert_eq!(args.country, Some("US".to_string()));
+            assert!(args.exact);
+            assert_eq!(args.limit, 20);
+        } else {
+            panic!("Expected SearchOrg command");
+        }
+    }
+
+    #[test]
+    fn test_country_command() {
+        let cli = Cli::parse_from(["rasn", "-o", "csv", "country", "nl", "--ranges"]);
+        assert!(matches!(cli.output, OutputFormat::Csv));
+        if let Commands::Country(args) = cli.command {
+            assert_eq!(args.country, "nl");
+            assert!(args.ranges);
+        } else {
+            panic!("Expected Country command");
+        }
+    }
+
+    #[test]
+    fn test_build_db_command() {
+        let cli = Cli::parse_from(["rasn", "build-db", "--reference", "/tmp/ref"]);
+        if let Commands::BuildDb(args) = cli.command {
+            assert_eq!(args.reference, std::path::PathBuf::from("/tmp/ref"));
+            assert_eq!(args.data_dir, std::path::PathBuf::from("data"));
+        } else {
+            panic!("Expected BuildDb command");
+        }
+    }
+
+    #[test]
+    fn test_db_verify_command() {
+        let cli = Cli::parse_from(["rasn", "-o", "json", "db", "verify", "v4.parquet"]);
+        assert!(matches!(cli.output, OutputFormat::Json));
+        if let Commands::Db(DbArgs {
+            command: DbCommand::Verify(args),
+        }) = cli.command
+        {
+            assert_eq!(args.file, std::path::PathBuf::from("v4.parquet"));
+        } else {
+            panic!("Expected Db Verify command");
+        }
+    }
+
+    #[test]
+    fn test_db_convert_command() {
+        let cli = Cli::parse_from(["rasn", "db", "convert", "v6.parquet", "-O", "/tmp/v6.arrow"]);
+        if let Commands::Db(DbArgs {
+            command: DbCommand::Convert(args),
+        }) = cli.command
+        {
+            assert_eq!(args.file, std::path::PathBuf::from("v6.parquet"));
+            assert_eq!(args.out, Some(std::path::PathBuf::from("/tmp/v6.arrow")));
+        } else {
+            panic!("Expected Db Convert command");
+        }
+    }
+
+    #[test]
+    fn test_db_diff_command() {
+        let cli = Cli::parse_from([
+            "rasn",
+            "-o",
+            "csv",
+            "db",
+            "diff",
+            "old.parquet",
+            "new.arrow",
+        ]);
+        if let Commands::Db(DbArgs {
+            command: DbCommand::Diff(args),
+        }) = cli.command
+        {
+            assert_eq!(args.old, std::path::PathBuf::from("old.parquet"));
+            assert_eq!(args.new, std::path::PathBuf::from("new.arrow"));
+            assert_eq!(args.limit, 20);
+        } else {
+            panic!("Expected Db Diff command");
+        }
+    }
+
+    #[test]
+    fn test_cidr_command() {
+        let cli = Cli::parse_from([
+            "rasn",
+            "cidr",
+            "subtract",
+            "10.0.0.0/8",
+            "--with",
+            "10.0.0.0/9,10.128.0.0/10",
+        ]);
+        let Commands::Cidr(args) = cli.command else {
+            panic!("Expected Cidr command");
+        };
+        let result = run_cidr_command(args.command, false).unwrap();
+        assert_eq!(result.input_count, 1);
+        assert!(matches!(
+            result.output,
+            CidrOutput::Cidrs(ref cidrs) if cidrs == &["10.192.0.0/10"]
+        ));
+
+        let run = |args: &[&str]| {
+            let Commands::Cidr(cidr) = Cli::parse_from(args).command else {
+                panic!("Expected Cidr command");
+            };
+            run_cidr_command(cidr.command, false).unwrap()
+        };
+
+        let result = run(&[
+            "rasn",
+            "cidr",
+            "aggregate",
+            "192.168.0.0/24",
+            "192.168.1.0/24",
+        ]);
+        assert_eq!(result.compression_ratio, Some(2.0));
+        assert!(matches!(
+            result.output,
+            CidrOutput::Cidrs(ref cidrs) if cidrs == &["192.168.0.0/23"]
+        ));
+
+        let result = run(&["rasn", "cidr", "split", "-p", "26", "192.168.0.0/24"]);
+        assert_eq!(result.output_count, Some(4));
+
+        let result = run(&[
+            "rasn",
+            "cidr",
+            "contains",
+            "--target",
+            "192.168.0.100",
+            "192.168.0.0/24",
+        ]);
+        assert_eq!(result.target.as_deref(), Some("192.168.0.100/32"));
+        assert!(matches!(result.output, CidrOutput::Contained(true)));
+
+        let result = run(&[
+            "rasn",
+            "cidr",
+            "overlaps",
+            "192.168.0.0/23",
+            "192.168.1.0/24",
+        ]);
+        assert!(matches!(
+            result.output,
+            CidrOutput::Pairs(ref pairs) if pairs[0].cidr2 == "192.168.1.0/24"
+        ));
+
+        assert!(Cli::try_parse_from(["rasn", "cidr", "intersect", "10.0.0.0/8"]).is_err());
+
+        let Commands::Cidr(cidr) =
+            Cli::parse_from(["rasn", "cidr", "aggregate", "2001:db8::/33"]).command
+        else {
+            panic!("Expected Cidr command");
+        };
+        let Err(err) = run_cidr_command(cidr.command, false) else {
+            panic!("IPv6 operands must be rejected");
+        };
+        assert!(err.to_string().contains("IPv4 only"));
+
+        let result = run(&[
+            "rasn",
+            "cidr",
+            "range2cidr",
+            "10.0.0.1-10.0.0.4",
+            "2001:db8:: - 2001:db9::ffff",
+        ]);
+        assert_eq!(result.input_count, 2);
+        assert!(matches!(
+            result.output,
+            CidrOutput::Cidrs(ref cidrs) if cidrs == &[
+                "10.0.0.1/32",
+                "10.0.0.2/31",
+                "10.0.0.4/32",
+                "2001:db8::/32",
+                "2001:db9::/112",
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_cidr_analyze_command() {
+        let cidr = Cli::parse_from(["rasn", "-o", "json", "cidr", "analyze", "2001:db8::/32"]);
+        if let Commands::Cidr(CidrArgs {
+            command: CidrCommand::Analyze(args),
+        }) = cidr.command
+        {
+            assert_eq!(args.cidr, "2001:db8::/32");
+            assert_eq!(args.limit, 20);
+        } else {
+            panic!("Expected Cidr Analyze command");
+        }
+
+        let database = rasn_arrow::IpAsnDatabase::new(
+            Some(
+                rasn_arrow::IpRangeTableV4::from_vecs(
+                    vec![0x08080800],
+                    vec![0x0808087F],
+                    vec![15169],
+                    vec!["US".to_string()],
+                    vec!["GOOGLE".to_string()],
+                )
+                .unwrap(),
+            ),
+            None,
+        );
+
+        // Same report as the MCP tool: normalised CIDR, lowercase family
+        let result = analyze_cidr(&database, "8.8.8.9/24", 20).unwrap();
+        assert_eq!(result.cidr, "8.8.8.0/24");
+        assert_eq!(result.family, "ipv4");
+        assert_eq!(result.total_addresses, 256);
+        let coverage = result.coverage.as_ref().unwrap();
+        assert_eq!(coverage.covered_addresses, 128);
+        assert_eq!(coverage.asn_count, 1);
+        assert_eq!(coverage.gap_count, 1);
+
+        let err = analyze_cidr(&database, "2001:db8::/32", 20).unwrap_err();
+        assert!(err.to_string().contains("No IPv6 range table loaded"));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let ip = |s: &str| s.parse::<std::net::IpAddr>().unwrap();
+
+        assert_eq!(
+            parse_range("1.0.0.0\t1.0.0.255\t13335\tUS\tCLOUDFLARENET").unwrap(),
+            (ip("1.0.0.0"), ip("1.0.0.255"))
+        );
+        assert_eq!(parse_range("::1-::2").unwrap(), (ip("::1"), ip("::2")));
+        assert!(parse_range("10.0.0.1").is_err());
+        assert!(parse_range("10.0.0.1-banana").is_err());
+    }
+
+    #[test]
+    fn test_db_info_command() {
+        let cli = Cli::parse_from(["rasn", "db", "info", "data/arrow"]);
+        if let Commands::Db(DbArgs {
+            command: DbCommand::Info(args),
+        }) = cli.command
+        {
+            assert_eq!(args.path, Some(std::path::PathBuf::from("data/arrow")));
+        } else {
+            panic!("Expected Db Info command");
+        }
+
+        let cli = Cli::parse_from(["rasn", "db", "info"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Db(DbArgs {
+                command: DbCommand::Info(InfoArgs { path: None })
+            })
+        ));
+    }
+
     #[test]
     fn test_verbose_flag() {
         let cli = Cli::parse_from(["rasn", "-v", "lookup", "1.1.1.1"]);
@@ -21,9 +21,10 @@
 //! ```
 
 use governor::{Quota, RateLimiter};
-use rasn_core::{Asn, AsnInfo};
+use rasn_core::{Asn, AsnInfo, AsyncAsnLookup, BoxFuture, LookupAnswer, LookupSource, RasnError};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::time::Duration;
@@ -57,6 +58,12 @@ pub enum ApiError {
     NotFound(String),
 }
 
+impl From<ApiError> for RasnError {
+    fn from(err: ApiError) -> Self {
+        RasnError::Network(err.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, ApiError>;
 
 /// API response for ASN lookup
@@ -232,6 +239,25 @@ impl ApiClient {
             organization: response.organization,
             country: response.country,
             description: response.description,
+            handle: None,
+        })
+    }
+}
+
+/// `NotFound` responses are `Ok(None)`; other API errors are
+/// [`RasnError::Network`].
+impl AsyncAsnLookup for ApiClient {
+    fn source(&self) -> LookupSource {
+        LookupSource::Api
+    }
+
+    fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, rasn_core::Result<Option<LookupAnswer>>> {
+        Box::pin(async move {
+            match self.lookup_ip(&ip.to_string()).await {
+                Ok(info) => Ok(Some(LookupAnswer::new(info, LookupSource::Api))),
+                Err(ApiError::NotFound(_)) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
         })
     }
 }
@@ -287,4 +313,18 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), ApiError::NotFound(_)));
     }
+
+    #[tokio::test]
+    async fn test_async_lookup_unreachable() {
+        let client = ApiClient::with_config(
+            "key".to_string(),
+            "http://127.0.0.1:9".to_string(),
+            Duration::from_millis(200),
+        );
+        let backend: &dyn AsyncAsnLookup = &client;
+        assert_eq!(backend.source(), LookupSource::Api);
+
+        let result = backend.lookup("8.8.8.8".parse().unwrap()).await;
+        assert!(matches!(result, Err(RasnError::Network(_))));
+    }
 }
@@ -0,0 +1,349 @@
+//! Tiered lookup chain
+//!
+//! [`LookupChain`] asks a list of backends in order (usually Arrow tables,
+//! cache, cold storage, API, WHOIS) and returns the first answer.
+//! Each tier has its own timeout. Tiers that can hold results
+//! ([`AsnStore`]) and missed are filled with the answer of a later tier, so
+//! the next lookup of the same address stops earlier.
+//!
+//! # Examples
+//!
+//! ```
+//! use rasn_core::{Asn, AsnInfo, AsnLookup, LookupAnswer, LookupChain, LookupSource, Result};
+//! use std::net::IpAddr;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! struct Fixed;
+//!
+//! impl AsnLookup for Fixed {
+//!     fn source(&self) -> LookupSource {
+//!         LookupSource::Api
+//!     }
+//!
+//!     fn lookup(&self, _ip: IpAddr) -> Result<Option<LookupAnswer>> {
+//!         let info = AsnInfo {
+//!             asn: Asn(64512),
+//!             organization: "Example".to_string(),
+//!             country: None,
+//!             description: None,
+//!             handle: None,
+//!         };
+//!         Ok(Some(LookupAnswer::new(info, self.source())))
+//!     }
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<()> {
+//! let chain = LookupChain::new().with_tier(Arc::new(Fixed), Duration::from_secs(1));
+//!
+//! let answer = chain.lookup("192.0.2.1".parse().unwrap()).await?.unwrap();
+//! assert_eq!(answer.source, LookupSource::Api);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::lookup::{AsyncAsnLookup, BoxFuture, LookupAnswer, LookupSource};
+use crate::{AsnInfo, RasnError, Result};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Backend that can keep answers found by other backends
+pub trait AsnStore: Send + Sync {
+    /// Remember the ASN information of an IP address
+    fn store(&self, ip: IpAddr, info: AsnInfo) -> BoxFuture<'_, Result<()>>;
+}
+
+/// One backend of a [`LookupChain`]
+struct Tier {
+    backend: Arc<dyn AsyncAsnLookup>,
+    store: Option<Arc<dyn AsnStore>>,
+    timeout: Duration,
+}
+
+/// Ordered fallback over several lookup backends
+///
+/// A tier that errors or times out counts as a miss. If no tier answers,
+/// the lookup is `Ok(None)` as long as one tier missed cleanly; only when
+/// every tier failed is the last failure returned, so one unreachable
+/// optional tier never turns a miss into an error. Write-back failures are
+/// ignored: they never fail a lookup that found an answer.
+#[derive(Default)]
+pub struct LookupChain {
+    tiers: Vec<Tier>,
+}
+
+impl LookupChain {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a read-only tier
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - Backend asked after all earlier tiers missed
+    /// * `timeout` - Time after which the tier counts as failed
+    pub fn with_tier(mut self, backend: Arc<dyn AsyncAsnLookup>, timeout: Duration) -> Self {
+        self.tiers.push(Tier {
+            backend,
+            store: None,
+            timeout,
+        });
+        self
+    }
+
+    /// Append a tier that is filled with answers from later tiers
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - Backend asked after all earlier tiers missed
+    /// * `timeout` - Time after which a lookup or write-back counts as failed
+    pub fn with_store_tier<T>(mut self, backend: Arc<T>, timeout: Duration) -> Self
+    where
+        T: AsyncAsnLookup + AsnStore + 'static,
+    {
+        self.tiers.push(Tier {
+            backend: backend.clone(),
+            store: Some(backend),
+            timeout,
+        });
+        self
+    }
+
+    /// Get the sources of the tiers, in lookup order
+    pub fn sources(&self) -> Vec<LookupSource> {
+        self.tiers
+            .iter()
+            .map(|tier| tier.backend.source())
+            .collect()
+    }
+
+    /// Get the number of tiers
+    pub fn len(&self) -> usize {
+        self.tiers.len()
+    }
+
+    /// Check if the chain has no tiers
+    pub fn is_empty(&self) -> bool {
+        self.tiers.is_empty()
+    }
+
+    /// Find ASN information for an IP address
+    ///
+    /// The answer keeps the source of the tier that produced it.
+    pub async fn lookup(&self, ip: IpAddr) -> Result<Option<LookupAnswer>> {
+        let mut failure = None;
+        let mut missed = false;
+
+        for (index, tier) in self.tiers.iter().enumerate() {
+            let answer = match tokio::time::timeout(tier.timeout, tier.backend.lookup(ip)).await {
+                Ok(Ok(Some(answer))) => answer,
+                Ok(Ok(None)) => {
+                    missed = true;
+                    continue;
+                }
+                Ok(Err(e)) => {
+                    failure = Some(e);
+                    continue;
+                }
+                Err(_) => {
+                    failure = Some(RasnError::Network(format!(
+                        "{} lookup timed out after {:?}",
+                        tier.backend.source(),
+                        tier.timeout
+                    )));
+                    continue;
+                }
+            };
+
+            self.write_back(&self.tiers[..index], ip, &answer.info)
+                .await;
+            return Ok(Some(answer));
+        }
+
+        match failure {
+            Some(e) if !missed => Err(e),
+            _ => Ok(None),
+        }
+    }
+
+    /// Store an answer in the tiers that missed it
+    async fn write_back(&self, missed: &[Tier], ip: IpAddr, info: &AsnInfo) {
+        for tier in missed {
+            if let Some(ref store) = tier.store {
+                let _ = tokio::time::timeout(tier.timeout, store.store(ip, info.clone())).await;
+            }
+        }
+    }
+}
+
+impl AsyncAsnLookup for LookupChain {
+    /// Get the source of the first tier
+    ///
+    /// Answers carry the source of the tier that actually answered.
+    fn source(&self) -> LookupSource {
+        self.tiers
+            .first()
+            .map_or(LookupSource::Arrow, |tier| tier.backend.source())
+    }
+
+    fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, Result<Option<LookupAnswer>>> {
+        Box::pin(LookupChain::lookup(self, ip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Asn;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    fn info(asn: u32) -> AsnInfo {
+        AsnInfo {
+            asn: Asn(asn),
+            organization: format!("AS{}", asn),
+            country: None,
+            description: None,
+            handle: None,
+        }
+    }
+
+    /// In-memory store tier
+    struct MemoryTier {
+        source: LookupSource,
+        entries: Mutex<HashMap<IpAddr, AsnInfo>>,
+    }
+
+    impl MemoryTier {
+        fn new(source: LookupSource) -> Arc<Self> {
+            Arc::new(Self {
+                source,
+                entries: Mutex::new(HashMap::new()),
+            })
+        }
+    }
+
+    impl AsyncAsnLookup for MemoryTier {
+        fn source(&self) -> LookupSource {
+            self.source
+        }
+
+        fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, Result<Option<LookupAnswer>>> {
+            let found = self.entries.lock().unwrap().get(&ip).cloned();
+            Box::pin(async move { Ok(found.map(|info| LookupAnswer::new(info, self.source))) })
+        }
+    }
+
+    impl AsnStore for MemoryTier {
+        fn store(&self, ip: IpAddr, info: AsnInfo) -> BoxFuture<'_, Result<()>> {
+            self.entries.lock().unwrap().insert(ip, info);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    /// Tier that always answers, fails or hangs
+    enum Remote {
+        Answer(u32),
+        Fail,
+        Hang,
+    }
+
+    impl AsyncAsnLookup for Remote {
+        fn source(&self) -> LookupSource {
+            LookupSource::Api
+        }
+
+        fn lookup(&self, _ip: IpAddr) -> BoxFuture<'_, Result<Option<LookupAnswer>>> {
+            Box::pin(async move {
+                match self {
+                    Remote::Answer(asn) => {
+                        Ok(Some(LookupAnswer::new(info(*asn), LookupSource::Api)))
+                    }
+                    Remote::Fail => Err(RasnError::Network("unreachable".to_string())),
+                    Remote::Hang => std::future::pending().await,
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_back_to_missed_tiers() {
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+        let cache = MemoryTier::new(LookupSource::Cache);
+        let cold = MemoryTier::new(LookupSource::ColdStorage);
+        let timeout = Duration::from_secs(1);
+
+        let chain = LookupChain::new()
+            .with_store_tier(cache.clone(), timeout)
+            .with_store_tier(cold.clone(), timeout)
+            .with_tier(Arc::new(Remote::Answer(64512)), timeout);
+        assert_eq!(
+            chain.sources(),
+            vec![
+                LookupSource::Cache,
+                LookupSource::ColdStorage,
+                LookupSource::Api
+            ]
+        );
+
+        let answer = chain.lookup(ip).await.unwrap().unwrap();
+        assert_eq!(answer.source, LookupSource::Api);
+        assert_eq!(cache.entries.lock().unwrap()[&ip].asn, Asn(64512));
+        assert_eq!(cold.entries.lock().unwrap()[&ip].asn, Asn(64512));
+
+        let answer = chain.lookup(ip).await.unwrap().unwrap();
+        assert_eq!(answer.source, LookupSource::Cache);
+    }
+
+    #[tokio::test]
+    async fn test_no_write_back_to_later_tiers() {
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+        let cache = MemoryTier::new(LookupSource::Cache);
+        cache.entries.lock().unwrap().insert(ip, info(13335));
+        let cold = MemoryTier::new(LookupSource::ColdStorage);
+
+        let chain = LookupChain::new()
+            .with_store_tier(cache, Duration::from_secs(1))
+            .with_store_tier(cold.clone(), Duration::from_secs(1));
+
+        let answer = chain.lookup(ip).await.unwrap().unwrap();
+        assert_eq!(answer.source, LookupSource::Cache);
+        assert!(cold.entries.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_tiers_fall_through() {
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+        let timeout = Duration::from_millis(20);
+
+        let chain = LookupChain::new()
+            .with_tier(Arc::new(Remote::Hang), timeout)
+            .with_tier(Arc::new(Remote::Fail), timeout)
+            .with_tier(Arc::new(Remote::Answer(15169)), timeout);
+        let answer = chain.lookup(ip).await.unwrap().unwrap();
+        assert_eq!(answer.info.asn, Asn(15169));
+
+        // A clean miss in one tier outweighs failures in the others
+        let partly_failing = LookupChain::new()
+            .with_store_tier(MemoryTier::new(LookupSource::Cache), timeout)
+            .with_tier(Arc::new(Remote::Hang), timeout)
+            .with_tier(Arc::new(Remote::Fail), timeout);
+        assert!(partly_failing.lookup(ip).await.unwrap().is_none());
+
+        // Only a chain where every tier failed reports the failure
+        let failing = LookupChain::new()
+            .with_tier(Arc::new(Remote::Fail), timeout)
+            .with_tier(Arc::new(Remote::Hang), timeout);
+        let err = failing.lookup(ip).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+
+        let missing =
+            LookupChain::new().with_store_tier(MemoryTier::new(LookupSource::Cache), timeout);
+        assert!(missing.lookup(ip).await.unwrap().is_none());
+        assert!(LookupChain::new().lookup(ip).await.unwrap().is_none());
+    }
+}
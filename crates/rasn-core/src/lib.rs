@@ -4,6 +4,8 @@
 //! - [`Asn`] - Autonomous System Number
 //! - [`AsnInfo`] - Complete ASN information
 //! - [`RasnError`] - Error types
+//! - [`AsnLookup`] / [`AsyncAsnLookup`] - IP → ASN lookup backends
+//! - [`LookupChain`] - Ordered fallback over lookup backends
 //!
 //!
 //! ```
@@ -16,8 +18,13 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use thiserror::Error;
 
+pub mod chain;
+pub mod lookup;
 pub mod security;
 
+pub use chain::{AsnStore, LookupChain};
+pub use lookup::{AsnLookup, AsyncAsnLookup, Blocking, BoxFuture, LookupAnswer, LookupSource};
+
 /// Autonomous System Number (ASN)
 ///
 /// A unique identifier for an autonomous system on the internet.
@@ -61,6 +68,7 @@ impl From<u32> for Asn {
 ///     organization: "Google LLC".to_string(),
 ///     country: Some("US".to_string()),
 ///     description: Some("Google".to_string()),
+///     handle: Some("GOOGLE".to_string()),
 /// };
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -73,6 +81,9 @@ pub struct AsnInfo {
     pub country: Option<String>,
     /// Human-readable description
     pub description: Option<String>,
+    /// Registry handle of the AS (e.g., "GOOGLE")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
 }
 
 /// Error types for RASN operations
@@ -162,6 +173,7 @@ mod tests {
             organization: "Google LLC".to_string(),
             country: Some("US".to_string()),
             description: Some("Google".to_string()),
+            handle: None,
         };
 
         assert_eq!(info.asn, Asn(15169));
@@ -176,6 +188,7 @@ mod tests {
             organization: "Google LLC".to_string(),
             country: Some("US".to_string()),
             description: None,
+            handle: None,
         };
 
         let json = serde_json::to_string(&info).expect("serialization failed");
@@ -0,0 +1,387 @@
+//! IP → ASN lookup backends
+//!
+//! Every backend (Arrow tables, cold storage, HTTP APIs, test mocks)
+//! answers with a [`LookupAnswer`]: the [`AsnInfo`] plus the
+//! [`LookupSource`] it came from. Backends with cheap, blocking lookups
+//! implement [`AsnLookup`]; network backends implement [`AsyncAsnLookup`].
+//! Every [`AsnLookup`] is also an [`AsyncAsnLookup`], so callers that accept
+//! `Arc<dyn AsyncAsnLookup>` work with any backend.
+//!
+//! Not finding an address is `Ok(None)`; `Err` means the backend itself
+//! failed.
+//!
+//! The automatic [`AsyncAsnLookup`] implementation runs an [`AsnLookup`]
+//! inline on the executor, where a chain tier timeout cannot interrupt it.
+//! That suits in-memory backends; wrap backends that block on disk I/O in
+//! [`Blocking`] so they run on the blocking thread pool and their timeout
+//! holds.
+
+use crate::chain::AsnStore;
+use crate::{AsnInfo, RasnError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Boxed future returned by [`AsyncAsnLookup::lookup`]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Where a lookup answer came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LookupSource {
+    /// In-memory Arrow range tables
+    Arrow,
+    /// Lookup result cache
+    Cache,
+    /// RocksDB cold storage
+    ColdStorage,
+    /// External HTTP API
+    Api,
+    /// WHOIS servers
+    Whois,
+}
+
+impl LookupSource {
+    /// Get the name used in serialized output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LookupSource::Arrow => "arrow",
+            LookupSource::Cache => "cache",
+            LookupSource::ColdStorage => "cold_storage",
+            LookupSource::Api => "api",
+            LookupSource::Whois => "whois",
+        }
+    }
+
+    /// Get the default timeout of a lookup chain tier for this source
+    ///
+    /// Roughly 10-100 times the expected latency of the backend. Blocking
+    /// backends only honour it when wrapped in [`Blocking`].
+    pub fn default_timeout(&self) -> Duration {
+        match self {
+            LookupSource::Arrow | LookupSource::Cache => Duration::from_millis(100),
+            LookupSource::ColdStorage => Duration::from_secs(1),
+            LookupSource::Api => Duration::from_secs(10),
+            LookupSource::Whois => Duration::from_secs(2),
+        }
+    }
+}
+
+impl fmt::Display for LookupSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LookupSource {
+    type Err = RasnError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "arrow" => Ok(LookupSource::Arrow),
+            "cache" => Ok(LookupSource::Cache),
+            "cold_storage" | "rocksdb" => Ok(LookupSource::ColdStorage),
+            "api" => Ok(LookupSource::Api),
+            "whois" => Ok(LookupSource::Whois),
+            other => Err(RasnError::Parse(format!(
+                "Unknown lookup source: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// ASN information tagged with the backend that produced it
+///
+/// Serializes as the [`AsnInfo`] fields plus a `source` field.
+///
+/// # Examples
+///
+/// ```
+/// use rasn_core::{Asn, AsnInfo, LookupAnswer, LookupSource};
+///
+/// let answer = LookupAnswer::new(
+///     AsnInfo {
+///         asn: Asn(15169),
+///         organization: "Google LLC".to_string(),
+///         country: Some("US".to_string()),
+///         description: None,
+///         handle: None,
+///     },
+///     LookupSource::Arrow,
+/// );
+/// assert_eq!(answer.source.as_str(), "arrow");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LookupAnswer {
+    /// The ASN information
+    #[serde(flatten)]
+    pub info: AsnInfo,
+    /// The backend that answered
+    pub source: LookupSource,
+}
+
+impl LookupAnswer {
+    /// Tag ASN information with its source
+    pub fn new(info: AsnInfo, source: LookupSource) -> Self {
+        Self { info, source }
+    }
+}
+
+/// Blocking IP → ASN lookup backend
+///
+/// # Examples
+///
+/// ```
+/// use rasn_core::{Asn, AsnInfo, AsnLookup, LookupAnswer, LookupSource, Result};
+/// use std::net::IpAddr;
+///
+/// struct Fixed;
+///
+/// impl AsnLookup for Fixed {
+///     fn source(&self) -> LookupSource {
+///         LookupSource::Arrow
+///     }
+///
+///     fn lookup(&self, _ip: IpAddr) -> Result<Option<LookupAnswer>> {
+///         let info = AsnInfo {
+///             asn: Asn(64512),
+///             organization: "Example".to_string(),
+///             country: None,
+///             description: None,
+///             handle: None,
+///         };
+///         Ok(Some(LookupAnswer::new(info, self.source())))
+///     }
+/// }
+///
+/// let answer = Fixed.lookup("192.0.2.1".parse().unwrap())?.unwrap();
+/// assert_eq!(answer.info.asn, Asn(64512));
+/// # Ok::<(), rasn_core::RasnError>(())
+/// ```
+pub trait AsnLookup: Send + Sync {
+    /// Get the source tag of this backend's answers
+    fn source(&self) -> LookupSource;
+
+    /// Find ASN information for an IP address
+    fn lookup(&self, ip: IpAddr) -> Result<Option<LookupAnswer>>;
+}
+
+/// Asynchronous IP → ASN lookup backend
+///
+/// Implemented directly by network backends and automatically for every
+/// [`AsnLookup`]. The trait is object safe: store backends as
+/// `Arc<dyn AsyncAsnLookup>`.
+pub trait AsyncAsnLookup: Send + Sync {
+    /// Get the source tag of this backend's answers
+    fn source(&self) -> LookupSource;
+
+    /// Find ASN information for an IP address
+    fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, Result<Option<LookupAnswer>>>;
+}
+
+impl<T: AsnLookup + ?Sized> AsyncAsnLookup for T {
+    fn source(&self) -> LookupSource {
+        AsnLookup::source(self)
+    }
+
+    fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, Result<Option<LookupAnswer>>> {
+        Box::pin(async move { AsnLookup::lookup(self, ip) })
+    }
+}
+
+/// Blocking backend run on the tokio blocking thread pool
+///
+/// A [`LookupChain`](crate::LookupChain) tier timeout only takes effect at
+/// an await point, so a backend that blocks inline (RocksDB cold storage)
+/// can overrun it. Wrapped, the lookup runs on its own thread: when the
+/// timeout expires the chain moves on while the blocked call finishes in
+/// the background. Write-backs of [`AsnStore`] backends are moved off the
+/// executor the same way.
+///
+/// # Examples
+///
+/// ```
+/// use rasn_core::{AsyncAsnLookup, Blocking, LookupChain};
+/// # use rasn_core::{AsnLookup, LookupAnswer, LookupSource, Result};
+/// # use std::net::IpAddr;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// # struct Disk;
+/// # impl AsnLookup for Disk {
+/// #     fn source(&self) -> LookupSource {
+/// #         LookupSource::ColdStorage
+/// #     }
+/// #     fn lookup(&self, _ip: IpAddr) -> Result<Option<LookupAnswer>> {
+/// #         Ok(None)
+/// #     }
+/// # }
+/// let chain = LookupChain::new().with_tier(
+///     Arc::new(Blocking::new(Arc::new(Disk))),
+///     Duration::from_secs(1),
+/// );
+/// assert_eq!(chain.len(), 1);
+/// ```
+pub struct Blocking<T: ?Sized>(Arc<T>);
+
+impl<T: ?Sized> Blocking<T> {
+    /// Wrap a blocking backend
+    pub fn new(backend: Arc<T>) -> Self {
+        Self(backend)
+    }
+}
+
+impl<T: AsnLookup + ?Sized + 'static> AsyncAsnLookup for Blocking<T> {
+    fn source(&self) -> LookupSource {
+        AsnLookup::source(self.0.as_ref())
+    }
+
+    fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, Result<Option<LookupAnswer>>> {
+        let backend = self.0.clone();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || AsnLookup::lookup(backend.as_ref(), ip))
+                .await
+                .map_err(|e| RasnError::Other(format!("{} lookup failed: {}", self.source(), e)))?
+        })
+    }
+}
+
+impl<T: AsnStore + ?Sized + 'static> AsnStore for Blocking<T> {
+    fn store(&self, ip: IpAddr, info: AsnInfo) -> BoxFuture<'_, Result<()>> {
+        let backend = self.0.clone();
+        Box::pin(async move {
+            let runtime = tokio::runtime::Handle::current();
+            tokio::task::spawn_blocking(move || runtime.block_on(backend.store(ip, info)))
+                .await
+                .map_err(|e| RasnError::Other(format!("write-back failed: {}", e)))?
+        })
+    }
+}
+
+impl<T: AsnLookup + ?Sized> AsnLookup for Arc<T> {
+    fn source(&self) -> LookupSource {
+        AsnLookup::source(self.as_ref())
+    }
+
+    fn lookup(&self, ip: IpAddr) -> Result<Option<LookupAnswer>> {
+        AsnLookup::lookup(self.as_ref(), ip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Asn;
+
+    struct Mock(Option<u32>);
+
+    impl AsnLookup for Mock {
+        fn source(&self) -> LookupSource {
+            LookupSource::Cache
+        }
+
+        fn lookup(&self, ip: IpAddr) -> Result<Option<LookupAnswer>> {
+            if ip.is_unspecified() {
+                return Err(RasnError::Database("unavailable".to_string()));
+            }
+            Ok(self.0.map(|asn| {
+                let info = AsnInfo {
+                    asn: Asn(asn),
+                    organization: "Mock".to_string(),
+                    country: None,
+                    description: None,
+                    handle: None,
+                };
+                LookupAnswer::new(info, LookupSource::Cache)
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_backend_is_async() {
+        let backend: Arc<dyn AsyncAsnLookup> = Arc::new(Mock(Some(15169)));
+        assert_eq!(backend.source(), LookupSource::Cache);
+
+        let answer = backend
+            .lookup("8.8.8.8".parse().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(answer.info.asn, Asn(15169));
+        assert_eq!(answer.source, LookupSource::Cache);
+
+        let missing: Arc<dyn AsyncAsnLookup> = Arc::new(Mock(None));
+        let none = missing.lookup("8.8.8.8".parse().unwrap()).await.unwrap();
+        assert!(none.is_none());
+        assert!(backend.lookup("0.0.0.0".parse().unwrap()).await.is_err());
+    }
+
+    /// Backend blocking its thread for a while before answering
+    struct Slow(std::time::Duration);
+
+    impl AsnLookup for Slow {
+        fn source(&self) -> LookupSource {
+            LookupSource::ColdStorage
+        }
+
+        fn lookup(&self, ip: IpAddr) -> Result<Option<LookupAnswer>> {
+            std::thread::sleep(self.0);
+            AsnLookup::lookup(&Mock(Some(64500)), ip)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocking_backend_timeout() {
+        let ip: IpAddr = "8.8.8.8".parse().unwrap();
+
+        let blocking = Blocking::new(Arc::new(Slow(Duration::from_millis(10))));
+        assert_eq!(AsyncAsnLookup::source(&blocking), LookupSource::ColdStorage);
+        let answer = AsyncAsnLookup::lookup(&blocking, ip)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(answer.info.asn, Asn(64500));
+
+        // The timeout fires while the lookup is still blocked
+        let blocking = Blocking::new(Arc::new(Slow(Duration::from_millis(500))));
+        let started = std::time::Instant::now();
+        let result = tokio::time::timeout(
+            Duration::from_millis(50),
+            AsyncAsnLookup::lookup(&blocking, ip),
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_answer_serialization() {
+        let answer = AsnLookup::lookup(&Arc::new(Mock(Some(13335))), "1.1.1.1".parse().unwrap())
+            .unwrap()
+            .unwrap();
+        let json = serde_json::to_value(&answer).unwrap();
+
+        assert_eq!(json["asn"], 13335);
+        assert_eq!(json["organization"], "Mock");
+        assert_eq!(json["source"], "cache");
+
+        let back: LookupAnswer = serde_json::from_value(json).unwrap();
+        assert_eq!(back, answer);
+        assert_eq!(LookupSource::ColdStorage.to_string(), "cold_storage");
+        assert_eq!(
+            "RocksDB".parse::<LookupSource>().unwrap(),
+            LookupSource::ColdStorage
+        );
+        assert_eq!(
+            "whois".parse::<LookupSource>().unwrap(),
+            LookupSource::Whois
+        );
+        assert!("geoip".parse::<LookupSource>().is_err());
+    }
+}
@@ -30,6 +30,7 @@
 //!     organization: "Google".to_string(),
 //!     country: Some("US".to_string()),
 //!     description: Some("Google LLC".to_string()),
+//!     handle: None,
 //! };
 //! storage.put_asn_info(&info)?;
 //!
@@ -41,8 +42,11 @@
 //! # }
 //! ```
 
-use rasn_core::AsnInfo;
+use rasn_core::{
+    Asn, AsnInfo, AsnLookup, AsnStore, BoxFuture, LookupAnswer, LookupSource, RasnError,
+};
 use rocksdb::{BlockBasedOptions, Options, DB};
+use std::net::IpAddr;
 use std::path::Path;
 use std::sync::Arc;
 use thiserror::Error;
@@ -79,6 +83,12 @@ impl From<serde_json::Error> for StorageError {
     }
 }
 
+impl From<StorageError> for RasnError {
+    fn from(err: StorageError) -> Self {
+        RasnError::Database(err.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, StorageError>;
 
 /// Column family names
@@ -241,10 +251,52 @@ impl ColdStorage {
     }
 }
 
+/// Stored ranges are IPv4 only. The organization is empty when the ASN has
+/// no stored metadata.
+impl AsnLookup for ColdStorage {
+    fn source(&self) -> LookupSource {
+        LookupSource::ColdStorage
+    }
+
+    fn lookup(&self, ip: IpAddr) -> rasn_core::Result<Option<LookupAnswer>> {
+        let IpAddr::V4(ipv4) = ip.to_canonical() else {
+            return Ok(None);
+        };
+        let Some(asn) = self.find_ip(u32::from(ipv4))? else {
+            return Ok(None);
+        };
+
+        let info = self.get_asn_info(asn)?.unwrap_or_else(|| AsnInfo {
+            asn: Asn(asn),
+            organization: String::new(),
+            country: None,
+            description: None,
+            handle: None,
+        });
+        Ok(Some(LookupAnswer::new(info, LookupSource::ColdStorage)))
+    }
+}
+
+/// Write-backs are stored as single-address ranges. IPv6 addresses are
+/// skipped.
+impl AsnStore for ColdStorage {
+    fn store(&self, ip: IpAddr, info: AsnInfo) -> BoxFuture<'_, rasn_core::Result<()>> {
+        Box::pin(async move {
+            let IpAddr::V4(ipv4) = ip.to_canonical() else {
+                return Ok(());
+            };
+            let ip = u32::from(ipv4);
+
+            self.put_ip_range(ip, ip, info.asn.0)?;
+            self.put_asn_info(&info)?;
+            Ok(())
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rasn_core::Asn;
     use tempfile::TempDir;
 
     fn create_temp_storage() -> (ColdStorage, TempDir) {
@@ -268,6 +320,7 @@ mod tests {
             organization: "Google".to_string(),
             country: Some("US".to_string()),
             description: Some("Google LLC".to_string()),
+            handle: None,
         };
 
         storage.put_asn_info(&info).unwrap();
@@ -312,6 +365,7 @@ mod tests {
             organization: "Google".to_string(),
             country: Some("US".to_string()),
             description: None,
+            handle: None,
         };
 
         storage.put_asn_info(&info).unwrap();
@@ -332,4 +386,69 @@ mod tests {
         assert_eq!(storage.find_ip(0x08080808).unwrap(), Some(15169));
         assert_eq!(storage.find_ip(0x01010101).unwrap(), Some(13335));
     }
+
+    #[test]
+    fn test_asn_lookup_trait() {
+        let (storage, _temp) = create_temp_storage();
+        storage.put_ip_range(0x08080800, 0x080808FF, 15169).unwrap();
+        storage.put_ip_range(0x01010100, 0x010101FF, 13335).unwrap();
+        storage
+            .put_asn_info(&AsnInfo {
+                asn: Asn(15169),
+                organization: "Google".to_string(),
+                country: Some("US".to_string()),
+                description: None,
+                handle: None,
+            })
+            .unwrap();
+
+        let backend: &dyn AsnLookup = &storage;
+        assert_eq!(backend.source(), LookupSource::ColdStorage);
+
+        let answer = backend.lookup("8.8.8.8".parse().unwrap()).unwrap().unwrap();
+        assert_eq!(answer.info.organization, "Google");
+        assert_eq!(answer.source, LookupSource::ColdStorage);
+
+        // Range without metadata
+        let answer = backend.lookup("1.1.1.1".parse().unwrap()).unwrap().unwrap();
+        assert_eq!(answer.info.asn, Asn(13335));
+        assert!(answer.info.organization.is_empty());
+
+        assert!(backend
+            .lookup("9.9.9.9".parse().unwrap())
+            .unwrap()
+            .is_none());
+        assert!(backend
+            .lookup("2001:db8::1".parse().unwrap())
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_store_write_back() {
+        let (storage, _temp) = create_temp_storage();
+        let info = AsnInfo {
+            asn: Asn(64512),
+            organization: "Example".to_string(),
+            country: None,
+            description: None,
+            handle: None,
+        };
+
+        storage
+            .store("192.0.2.1".parse().unwrap(), info.clone())
+            .await
+            .unwrap();
+        storage
+            .store("2001:db8::1".parse().unwrap(), info)
+            .await
+            .unwrap();
+
+        assert_eq!(storage.find_ip(0xC000_0201).unwrap(), Some(64512));
+        assert_eq!(storage.find_ip(0xC000_0202).unwrap(), None);
+        assert_eq!(
+            storage.get_asn_info(64512).unwrap().unwrap().organization,
+            "Example"
+        );
+    }
 }
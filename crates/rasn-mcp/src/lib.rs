@@ -33,16 +33,21 @@
 //! # }
 //! ```
 
+pub mod reload;
 pub mod transport;
 
-use rasn_arrow::IpRangeTableV4;
+use rasn_arrow::{
+    CidrAnalysis, DatabaseHandle, DatasetInfo, IpAsnDatabase, OrgQuery, ReloadReport, SnapshotDate,
+    SnapshotStore,
+};
 use rasn_cache::CacheLayer;
-use rasn_cidr::Cidr;
+use rasn_cidr::{overlapping_pairs, range_to_cidrs, range_to_cidrs6, Cidr, CidrSet, IpCidr};
+use rasn_core::{AsyncAsnLookup, LookupAnswer, LookupChain, LookupSource};
 use rasn_resolver::DnsResolver;
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::path::Path;
 use std::sync::Arc;
-use std::time::Duration;
 use thiserror::Error;
 
 /// MCP server errors
@@ -138,6 +143,12 @@ impl JsonRpcError {
 #[derive(Debug, Deserialize)]
 struct LookupIpParams {
     ip: String,
+    /// Attach the build metadata of the table that answered
+    #[serde(default)]
+    include_dataset: bool,
+    /// Answer from the snapshot valid at this date (`YYYY-MM-DD`)
+    #[serde(default)]
+    at: Option<String>,
 }
 
 /// Lookup ASN request parameters
@@ -152,25 +163,74 @@ struct LookupDomainParams {
     domain: String,
 }
 
+/// Organization search request parameters
+#[derive(Debug, Deserialize)]
+struct OrgToAsnParams {
+    organization: String,
+    country: Option<String>,
+    #[serde(default)]
+    exact_match: bool,
+    #[serde(default = "default_org_limit")]
+    limit: usize,
+}
+
+fn default_org_limit() -> usize {
+    50
+}
+
+/// Country summary request parameters
+#[derive(Debug, Deserialize)]
+struct CountrySummaryParams {
+    country: String,
+    #[serde(default)]
+    include_ranges: bool,
+    limit: Option<usize>,
+}
+
 /// Bulk lookup request parameters
 #[derive(Debug, Deserialize)]
 struct BulkLookupParams {
     ips: Vec<String>,
+    /// Attach the build metadata of the table that answered
+    #[serde(default)]
+    include_dataset: bool,
 }
 
 /// CIDR analyze request parameters
 #[derive(Debug, Deserialize)]
 struct CidrAnalyzeParams {
     cidr: String,
+    /// Maximum number of ASNs, countries and gaps listed
+    limit: Option<usize>,
+}
+
+/// CIDR operations request parameters
+#[derive(Debug, Deserialize)]
+struct CidrOperationsParams {
+    operation: String,
+    cidrs: Vec<String>,
+    /// Single CIDR operand of contains, overlaps, intersect and subtract
+    target: Option<String>,
+    /// CIDR list operand of intersect and subtract
+    #[serde(default)]
+    targets: Vec<String>,
+    split_prefix: Option<u8>,
 }
 
 /// Model Context Protocol Server
 ///
-/// Handles JSON-RPC 2.0 requests for ASN lookups.
+/// Handles JSON-RPC 2.0 requests for ASN lookups. IP lookups go through an
+/// [`AsyncAsnLookup`] backend: the cache in front of the loaded database
+/// unless replaced with [`McpServer::with_lookup`] or
+/// [`McpServer::with_chain`].
 pub struct McpServer {
-    arrow_table: Option<Arc<IpRangeTableV4>>,
+    database: Option<Arc<DatabaseHandle>>,
+    lookup: Option<Arc<dyn AsyncAsnLookup>>,
     cache: Arc<CacheLayer>,
+    /// Cached answers can only have come from the database tables
+    cache_fronts_database: bool,
     resolver: Option<Arc<DnsResolver>>,
+    snapshots: Option<Arc<SnapshotStore>>,
 }
 
 impl McpServer {
@@ -178,29 +238,283 @@ impl McpServer {
     ///
     /// # Arguments
     ///
-    /// * `arrow_path` - Optional path to Arrow/Parquet data
+    /// * `arrow_path` - Optional path to Arrow/Parquet data (data directory
+    ///   or IPv4 Parquet file)
     pub fn new(arrow_path: Option<&Path>) -> Result<Self> {
-        let arrow_table = if let Some(path) = arrow_path {
-            Some(Arc::new(
-                IpRangeTableV4::from_parquet(path)
-                    .map_err(|e| McpError::ArrowError(e.to_string()))?,
-            ))
+        let database = if let Some(path) = arrow_path {
+            Some(DatabaseHandle::open(path).map_err(|e| McpError::ArrowError(e.to_string()))?)
         } else {
             None
         };
 
+        Self::with_database_handle(database.map(Arc::new))
+    }
+
+    /// Create a new MCP server from an already loaded database
+    ///
+    /// The database cannot be reloaded; use [`McpServer::new`] or
+    /// [`McpServer::with_database_handle`] for that.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - Optional dual-stack IP→ASN database
+    pub fn with_database(database: Option<IpAsnDatabase>) -> Result<Self> {
+        Self::with_database_handle(database.map(|db| Arc::new(DatabaseHandle::new(db))))
+    }
+
+    /// Create a new MCP server from a reloadable database handle
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - Optional database handle, for example one that is
+    ///   also a tier of the lookup chain
+    pub fn with_database_handle(database: Option<Arc<DatabaseHandle>>) -> Result<Self> {
         let cache =
             Arc::new(CacheLayer::new(10000).map_err(|e| McpError::InternalError(e.to_string()))?);
 
         let resolver = DnsResolver::new().ok().map(Arc::new);
 
+        // Answers are cached in front of the tables, like any other backend
+        let lookup = database.clone().map(|database| {
+            let chain = LookupChain::new()
+                .with_store_tier(cache.clone(), LookupSource::Cache.default_timeout())
+                .with_tier(database, LookupSource::Arrow.default_timeout());
+            Arc::new(chain) as Arc<dyn AsyncAsnLookup>
+        });
+
         Ok(Self {
-            arrow_table,
+            cache_fronts_database: lookup.is_some(),
+            database,
+            lookup,
             cache,
             resolver,
+            snapshots: None,
         })
     }
 
+    /// Answer IP lookups from another backend
+    ///
+    /// Answers are cached: the backend is placed behind the server's cache in
+    /// a [`LookupChain`]. The database, if any, still serves ASN,
+    /// organization and country queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasn_arrow::IpAsnDatabase;
+    /// use rasn_mcp::McpServer;
+    /// use std::sync::Arc;
+    ///
+    /// let backend = Arc::new(IpAsnDatabase::new(None, None));
+    /// let server = McpServer::with_database(None)?.with_lookup(backend);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_lookup(self, lookup: Arc<dyn AsyncAsnLookup>) -> Self {
+        let source = lookup.source();
+        let chain = LookupChain::new()
+            .with_store_tier(self.cache.clone(), LookupSource::Cache.default_timeout())
+            .with_tier(lookup, source.default_timeout());
+        self.with_chain(chain)
+    }
+
+    /// Answer IP lookups from a tiered lookup chain
+    ///
+    /// The chain is used as is; include a cache tier to cache its answers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasn_arrow::IpAsnDatabase;
+    /// use rasn_core::{LookupChain, LookupSource};
+    /// use rasn_mcp::McpServer;
+    /// use std::sync::Arc;
+    ///
+    /// let chain = LookupChain::new().with_tier(
+    ///     Arc::new(IpAsnDatabase::new(None, None)),
+    ///     LookupSource::Arrow.default_timeout(),
+    /// );
+    /// let server = McpServer::with_database(None)?.with_chain(chain);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_chain(mut self, chain: LookupChain) -> Self {
+        self.lookup = Some(Arc::new(chain));
+        self.cache_fronts_database = false;
+        self
+    }
+
+    /// Answer `lookup_ip` requests with an `at` date from historical snapshots
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rasn_arrow::SnapshotStore;
+    /// use rasn_mcp::McpServer;
+    /// use std::path::Path;
+    ///
+    /// let snapshots = SnapshotStore::open(Path::new("data/snapshots"))?;
+    /// let server = McpServer::new(Some(Path::new("data/arrow")))?.with_snapshots(snapshots);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_snapshots(mut self, snapshots: SnapshotStore) -> Self {
+        self.snapshots = Some(Arc::new(snapshots));
+        self
+    }
+
+    /// Get the cache in front of the database or the [`McpServer::with_lookup`]
+    /// backend
+    ///
+    /// Also cleared after every successful reload; give it to the cache tier
+    /// of a custom [`LookupChain`] so reloads invalidate that tier too.
+    pub fn cache(&self) -> Arc<CacheLayer> {
+        self.cache.clone()
+    }
+
+    /// Get a snapshot of the current database
+    fn database(&self) -> Option<Arc<IpAsnDatabase>> {
+        self.database.as_ref().map(|handle| handle.load())
+    }
+
+    /// Reload the database from its files
+    ///
+    /// The new files are validated before they replace the current
+    /// database; on failure the server keeps answering from the old one.
+    /// Cached lookup results are dropped after a successful reload.
+    pub async fn reload_data(&self) -> Result<ReloadReport> {
+        let handle = self
+            .database
+            .clone()
+            .ok_or_else(|| McpError::InternalError("No database loaded".to_string()))?;
+
+        let report = tokio::task::spawn_blocking(move || handle.reload())
+            .await
+            .map_err(|e| McpError::InternalError(e.to_string()))?
+            .map_err(|e| McpError::ArrowError(e.to_string()))?;
+
+        self.cache.clear().await;
+        Ok(report)
+    }
+
+    /// Get the build metadata of the table that answered a lookup
+    ///
+    /// `None` unless the answer came from the Arrow tables and the table
+    /// file recorded its build metadata.
+    fn answer_dataset(&self, ip: IpAddr, answer: &LookupAnswer) -> Option<DatasetInfo> {
+        let from_tables = match answer.source {
+            LookupSource::Arrow => true,
+            // The cache is cleared on reload, so its answers match the
+            // current tables
+            LookupSource::Cache => self.cache_fronts_database,
+            _ => false,
+        };
+        if !from_tables {
+            return None;
+        }
+
+        // IPv4-mapped IPv6 addresses are answered from the IPv4 table
+        let database = self.database()?;
+        match ip.to_canonical() {
+            IpAddr::V4(_) => database.v4()?.dataset_info().cloned(),
+            IpAddr::V6(_) => database.v6()?.dataset_info().cloned(),
+        }
+    }
+
+    /// Answer a miss on an address in an IANA special-purpose block
+    ///
+    /// No ASN owns these blocks; the registry entry tells the caller why
+    /// the address has no owner.
+    fn special_to_value(ip: IpAddr) -> Option<serde_json::Value> {
+        let special = rasn_cidr::classify(ip)?;
+        Some(serde_json::json!({
+            "ip": ip.to_string(),
+            "asn": null,
+            "organization": special.name,
+            "special": special
+        }))
+    }
+
+    /// Serialize a lookup answer, with its dataset if requested
+    fn answer_to_value(
+        &self,
+        ip: IpAddr,
+        answer: &LookupAnswer,
+        include_dataset: bool,
+    ) -> Result<serde_json::Value> {
+        let mut value =
+            serde_json::to_value(answer).map_err(|e| McpError::InternalError(e.to_string()))?;
+        if include_dataset {
+            value["dataset"] = serde_json::json!(self.answer_dataset(ip, answer));
+        }
+        Ok(value)
+    }
+
+    /// Look up an address in the snapshot valid at a date
+    async fn lookup_ip_at(
+        &self,
+        ip: IpAddr,
+        at: &str,
+        include_dataset: bool,
+    ) -> Result<serde_json::Value> {
+        let date: SnapshotDate = at
+            .parse()
+            .map_err(|e: rasn_arrow::ArrowError| McpError::InvalidRequest(e.to_string()))?;
+        let snapshots = self
+            .snapshots
+            .clone()
+            .ok_or_else(|| McpError::InternalError("No snapshot store configured".to_string()))?;
+
+        // The first query of a snapshot loads its files
+        let loaded = tokio::task::spawn_blocking(move || snapshots.database_at(date))
+            .await
+            .map_err(|e| McpError::InternalError(e.to_string()))?;
+        let (snapshot, database) = match loaded {
+            Ok(found) => found,
+            // Special-purpose blocks are classified without data
+            Err(e) => {
+                return Self::special_to_value(ip)
+                    .ok_or_else(|| McpError::ArrowError(e.to_string()))
+            }
+        };
+
+        let Some(info) = IpAsnDatabase::lookup(&database, ip) else {
+            let mut value = Self::special_to_value(ip).ok_or_else(|| {
+                McpError::InternalError(format!("No ASN found in snapshot {}", snapshot))
+            })?;
+            value["snapshot"] = serde_json::json!(snapshot);
+            return Ok(value);
+        };
+        let mut value =
+            serde_json::to_value(&info).map_err(|e| McpError::InternalError(e.to_string()))?;
+        value["source"] = serde_json::json!(LookupSource::Arrow);
+        value["snapshot"] = serde_json::json!(snapshot);
+        if include_dataset {
+            let dataset = match ip.to_canonical() {
+                IpAddr::V4(_) => database.v4().and_then(|t| t.dataset_info()),
+                IpAddr::V6(_) => database.v6().and_then(|t| t.dataset_info()),
+            };
+            value["dataset"] = serde_json::json!(dataset);
+        }
+        Ok(value)
+    }
+
+    /// Look up an address with the configured backend
+    ///
+    /// Addresses in never-routed blocks (private, loopback, documentation,
+    /// ...) miss without querying any tier, so they never reach the API or
+    /// WHOIS; callers classify them on the miss.
+    async fn lookup_ip(&self, ip: IpAddr) -> Result<Option<LookupAnswer>> {
+        if rasn_cidr::classify(ip).is_some_and(|special| special.class.is_never_routed()) {
+            return Ok(None);
+        }
+
+        match self.lookup {
+            Some(ref lookup) => lookup
+                .lookup(ip)
+                .await
+                .map_err(|e| McpError::InternalError(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
     /// Handle a JSON-RPC 2.0 request
     ///
     /// # Arguments
@@ -228,10 +542,15 @@ impl McpServer {
             "lookup_ip" => self.handle_lookup_ip(&request.params).await,
             "lookup_asn" => self.handle_lookup_asn(&request.params).await,
             "lookup_domain" => self.handle_lookup_domain(&request.params).await,
+            "org_to_asn" => self.handle_org_to_asn(&request.params).await,
+            "country_summary" => self.handle_country_summary(&request.params).await,
             "bulk_lookup" => self.handle_bulk_lookup(&request.params).await,
             "cidr_analyze" => self.handle_cidr_analyze(&request.params).await,
+            "cidr_operations" => self.handle_cidr_operations(&request.params).await,
             "reverse_lookup" => self.handle_reverse_lookup(&request.params).await,
             "enrich_data" => self.handle_enrich_data(&request.params).await,
+            "reload_data" => self.handle_reload_data().await,
+            "data_info" => self.handle_data_info().await,
             "ping" => Ok(serde_json::json!({"status": "ok"})),
             _ => Err(McpError::MethodNotFound(request.method.clone())),
         };
@@ -269,52 +588,70 @@ impl McpServer {
         let params: LookupIpParams = serde_json::from_value(params.clone())
             .map_err(|e| McpError::InvalidRequest(format!("Invalid params: {}", e)))?;
 
-        // Check cache first
-        if let Some(cached) = self.cache.get(&params.ip).await {
-            return serde_json::to_value(&cached)
-                .map_err(|e| McpError::InternalError(e.to_string()));
-        }
-
-        // Parse IP to u32
-        let ip_u32 = self
+        // Parse IPv4 or IPv6 address
+        let ip = self
             .parse_ip(&params.ip)
             .map_err(McpError::InvalidRequest)?;
 
-        // Lookup in Arrow table
-        if let Some(ref table) = self.arrow_table {
-            if let Some(info) = table.find_ip(ip_u32) {
-                // Cache result
-                self.cache
-                    .set(&params.ip, info.clone(), Duration::from_secs(300))
-                    .await;
-
-                return serde_json::to_value(&info)
-                    .map_err(|e| McpError::InternalError(e.to_string()));
-            }
+        if let Some(ref at) = params.at {
+            return self.lookup_ip_at(ip, at, params.include_dataset).await;
         }
 
-        Err(McpError::InternalError("No ASN found".to_string()))
+        match self.lookup_ip(ip).await {
+            Ok(Some(answer)) => self.answer_to_value(ip, &answer, params.include_dataset),
+            Ok(None) => Self::special_to_value(ip)
+                .ok_or_else(|| McpError::InternalError("No ASN found".to_string())),
+            // Special-purpose blocks are classified without data, so a
+            // failing tier must not turn them into an error
+            Err(e) => Self::special_to_value(ip).ok_or(e),
+        }
     }
 
     /// Handle lookup_asn method
+    ///
+    /// Returns the organization, country and every announced IPv4/IPv6 range
+    /// as CIDR prefixes, answered from the ASN reverse index.
     async fn handle_lookup_asn(&self, params: &serde_json::Value) -> Result<serde_json::Value> {
         let params: LookupAsnParams = serde_json::from_value(params.clone())
             .map_err(|e| McpError::InvalidRequest(format!("Invalid params: {}", e)))?;
 
-        // Search Arrow table for ASN
-        if let Some(ref table) = self.arrow_table {
-            // Linear search for now - could optimize with index
-            for i in 0..1000 {
-                if let Some(info) = table.find_ip(i) {
-                    if info.asn.0 == params.asn {
-                        return serde_json::to_value(&info)
-                            .map_err(|e| McpError::InternalError(e.to_string()));
-                    }
-                }
-            }
-        }
+        let prefixes = self
+            .database()
+            .as_ref()
+            .and_then(|db| db.asn_prefixes(rasn_core::Asn(params.asn)))
+            .ok_or_else(|| McpError::InternalError("ASN not found".to_string()))?;
+
+        let ipv4_prefixes: Vec<String> = prefixes
+            .ipv4_ranges
+            .iter()
+            .flat_map(|&(start, end)| range_to_cidrs(start, end))
+            .map(|cidr| cidr.to_string())
+            .collect();
+        let ipv6_prefixes: Vec<String> = prefixes
+            .ipv6_ranges
+            .iter()
+            .flat_map(|&(start, end)| range_to_cidrs6(start, end))
+            .map(|cidr| cidr.to_string())
+            .collect();
 
-        Err(McpError::InternalError("ASN not found".to_string()))
+        Ok(serde_json::json!({
+            "asn": prefixes.asn,
+            "organization": prefixes.organization,
+            "country": prefixes.country,
+            "handle": prefixes.handle,
+            "description": prefixes.description,
+            "ipv4_prefixes": ipv4_prefixes,
+            "ipv6_prefixes": ipv6_prefixes,
+            "totals": {
+                "ipv4_ranges": prefixes.ipv4_ranges.len(),
+                "ipv6_ranges": prefixes.ipv6_ranges.len(),
+                "ipv4_prefixes": ipv4_prefixes.len(),
+                "ipv6_prefixes": ipv6_prefixes.len(),
+                "ipv4_addresses": prefixes.ipv4_address_count(),
+                // u128 does not fit in a JSON number
+                "ipv6_addresses": prefixes.ipv6_address_count().to_string()
+            }
+        }))
     }
 
     /// Handle lookup_domain method
@@ -330,26 +667,13 @@ impl McpServer {
                 .map_err(|e| McpError::InternalError(e.to_string()))?;
 
             if let Some(ip_addr) = ips.first() {
-                let ip_u32 = match ip_addr {
-                    std::net::IpAddr::V4(ipv4) => {
-                        let octets = ipv4.octets();
-                        u32::from_be_bytes(octets)
-                    }
-                    std::net::IpAddr::V6(_) => {
-                        return Err(McpError::InternalError("IPv6 not supported".to_string()));
-                    }
-                };
-
-                // Lookup IP in Arrow table
-                if let Some(ref table) = self.arrow_table {
-                    if let Some(info) = table.find_ip(ip_u32) {
-                        return serde_json::to_value(serde_json::json!({
-                            "domain": params.domain,
-                            "ip": format!("{}", ip_addr),
-                            "asn_info": info
-                        }))
-                        .map_err(|e| McpError::InternalError(e.to_string()));
-                    }
+                if let Some(answer) = self.lookup_ip(*ip_addr).await? {
+                    return serde_json::to_value(serde_json::json!({
+                        "domain": params.domain,
+                        "ip": format!("{}", ip_addr),
+                        "asn_info": answer
+                    }))
+                    .map_err(|e| McpError::InternalError(e.to_string()));
                 }
             }
         }
@@ -357,6 +681,99 @@ impl McpServer {
         Err(McpError::InternalError("Domain lookup failed".to_string()))
     }
 
+    /// Handle org_to_asn method
+    async fn handle_org_to_asn(&self, params: &serde_json::Value) -> Result<serde_json::Value> {
+        let params: OrgToAsnParams = serde_json::from_value(params.clone())
+            .map_err(|e| McpError::InvalidRequest(format!("Invalid params: {}", e)))?;
+
+        if params.organization.trim().chars().count() < 2 {
+            return Err(McpError::InvalidRequest(
+                "Organization must be at least 2 characters".to_string(),
+            ));
+        }
+
+        let mut query = OrgQuery::new(&params.organization)
+            .exact(params.exact_match)
+            .with_limit(params.limit);
+        if let Some(ref country) = params.country {
+            query = query.with_country(country);
+        }
+
+        let matches = self
+            .database()
+            .as_ref()
+            .map(|db| db.search_org(&query))
+            .unwrap_or_default();
+        let total_ip_ranges: usize = matches.iter().map(|m| m.ip_range_count).sum();
+
+        Ok(serde_json::json!({
+            "organization": params.organization,
+            "matches": matches,
+            "total_asns": matches.len(),
+            "total_ip_ranges": total_ip_ranges
+        }))
+    }
+
+    /// Handle country_summary method
+    async fn handle_country_summary(
+        &self,
+        params: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let params: CountrySummaryParams = serde_json::from_value(params.clone())
+            .map_err(|e| McpError::InvalidRequest(format!("Invalid params: {}", e)))?;
+
+        let country = params.country.trim();
+        if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(McpError::InvalidRequest(format!(
+                "Invalid country code: {}",
+                params.country
+            )));
+        }
+
+        let summary = self
+            .database()
+            .as_ref()
+            .and_then(|db| db.country_summary(country))
+            .ok_or_else(|| McpError::InternalError("Country not found".to_string()))?;
+
+        let limit = params.limit.unwrap_or(usize::MAX);
+        let mut result = serde_json::json!({
+            "country": summary.country,
+            "total_asns": summary.asns.len(),
+            "asns": summary.asns.iter().take(limit).collect::<Vec<_>>(),
+            "totals": {
+                "ipv4_ranges": summary.ipv4_ranges.len(),
+                "ipv6_ranges": summary.ipv6_ranges.len(),
+                "ipv4_addresses": summary.ipv4_address_count(),
+                // u128 does not fit in a JSON number
+                "ipv6_addresses": summary.ipv6_address_count().to_string()
+            }
+        });
+        // Geolocated ranges from the country index, kept apart from the
+        // registration totals the per-ASN counts add up to
+        if let Some(ref geo) = summary.geo_ipv4_ranges {
+            result["totals"]["geo_ipv4_ranges"] = geo.len().into();
+            result["totals"]["geo_ipv4_addresses"] = summary.geo_ipv4_address_count().into();
+        }
+
+        if params.include_ranges {
+            result["ipv4_prefixes"] = summary
+                .ipv4_ranges
+                .iter()
+                .flat_map(|&(start, end)| range_to_cidrs(start, end))
+                .map(|cidr| cidr.to_string())
+                .collect();
+            result["ipv6_prefixes"] = summary
+                .ipv6_ranges
+                .iter()
+                .flat_map(|&(start, end)| range_to_cidrs6(start, end))
+                .map(|cidr| cidr.to_string())
+                .collect();
+        }
+
+        Ok(result)
+    }
+
     /// Handle bulk_lookup method
     async fn handle_bulk_lookup(&self, params: &serde_json::Value) -> Result<serde_json::Value> {
         let params: BulkLookupParams = serde_json::from_value(params.clone())
@@ -364,39 +781,238 @@ impl McpServer {
 
         let mut results = Vec::new();
         for ip_str in params.ips {
-            let ip_u32 = self.parse_ip(&ip_str).ok();
-            let info = if let (Some(ip), Some(ref table)) = (ip_u32, &self.arrow_table) {
-                table.find_ip(ip)
-            } else {
-                None
+            // Failures are reported per address; they never abort the batch
+            let (info, special, error) = match self.parse_ip(&ip_str) {
+                Ok(ip) => match self.lookup_ip(ip).await {
+                    Ok(Some(answer)) => (
+                        Some(self.answer_to_value(ip, &answer, params.include_dataset)?),
+                        None,
+                        None,
+                    ),
+                    Ok(None) => (None, rasn_cidr::classify(ip), None),
+                    Err(e) => (None, rasn_cidr::classify(ip), Some(e.to_string())),
+                },
+                Err(e) => (None, None, Some(e)),
             };
 
-            results.push(serde_json::json!({
+            let mut result = serde_json::json!({
                 "ip": ip_str,
                 "asn_info": info
-            }));
+            });
+            if let Some(special) = special {
+                result["special"] = serde_json::json!(special);
+            }
+            if let Some(error) = error {
+                result["error"] = serde_json::json!(error);
+            }
+            results.push(result);
         }
 
         serde_json::to_value(&results).map_err(|e| McpError::InternalError(e.to_string()))
     }
 
+    /// Handle reload_data method
+    async fn handle_reload_data(&self) -> Result<serde_json::Value> {
+        let report = self.reload_data().await?;
+        Ok(serde_json::json!({
+            "status": "reloaded",
+            "path": report.path.display().to_string(),
+            "ipv4_ranges": report.ipv4_ranges,
+            "ipv6_ranges": report.ipv6_ranges,
+            "generation": report.generation
+        }))
+    }
+
+    /// Handle data_info method
+    ///
+    /// Reports the build metadata and size of each loaded range table.
+    async fn handle_data_info(&self) -> Result<serde_json::Value> {
+        let handle = self
+            .database
+            .as_ref()
+            .ok_or_else(|| McpError::InternalError("No database loaded".to_string()))?;
+        let database = handle.load();
+
+        Ok(serde_json::json!({
+            "path": handle.path().map(|p| p.display().to_string()),
+            "generation": handle.generation(),
+            "ipv4": database.v4().map(|t| serde_json::json!({
+                "ranges": t.len(),
+                "dataset": t.dataset_info()
+            })),
+            "ipv6": database.v6().map(|t| serde_json::json!({
+                "ranges": t.len(),
+                "dataset": t.dataset_info()
+            }))
+        }))
+    }
+
     /// Handle cidr_analyze method
+    ///
+    /// Besides the block arithmetic, reports which ASNs and countries hold
+    /// the block and its unallocated gaps when the address family's table
+    /// is loaded.
     async fn handle_cidr_analyze(&self, params: &serde_json::Value) -> Result<serde_json::Value> {
         let params: CidrAnalyzeParams = serde_json::from_value(params.clone())
             .map_err(|e| McpError::InvalidRequest(format!("Invalid params: {}", e)))?;
 
         let cidr =
-            Cidr::parse(&params.cidr).map_err(|e| McpError::InvalidRequest(e.to_string()))?;
+            IpCidr::parse(&params.cidr).map_err(|e| McpError::InvalidRequest(e.to_string()))?;
 
-        Ok(serde_json::json!({
-            "cidr": params.cidr,
-            "network": cidr.network(),
-            "broadcast": cidr.broadcast(),
-            "first_usable": cidr.first_usable(),
-            "last_usable": cidr.last_usable(),
-            "total_ips": cidr.size(),
-            "prefix_len": cidr.prefix_len()
-        }))
+        let limit = params.limit.unwrap_or(usize::MAX);
+        let analysis = match self.database() {
+            Some(database) => database.analyze_cidr(&cidr, limit),
+            None => CidrAnalysis::new(&cidr, None, limit),
+        };
+
+        serde_json::to_value(analysis).map_err(|e| McpError::InternalError(e.to_string()))
+    }
+
+    /// Handle cidr_operations method
+    ///
+    /// aggregate, supernet, split, contains, overlaps, intersect and
+    /// subtract over the `cidrs` list, computed on merged address ranges.
+    /// IPv4 only: IPv6 operands are rejected with an explicit error.
+    async fn handle_cidr_operations(
+        &self,
+        params: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let params: CidrOperationsParams = serde_json::from_value(params.clone())
+            .map_err(|e| McpError::InvalidRequest(format!("Invalid params: {}", e)))?;
+
+        let parse = |s: &String| match IpCidr::parse(s) {
+            Ok(IpCidr::V4(cidr)) => Ok(cidr),
+            Ok(IpCidr::V6(_)) => Err(McpError::InvalidRequest(format!(
+                "cidr_operations supports IPv4 only, got {}",
+                s.trim()
+            ))),
+            Err(e) => Err(McpError::InvalidRequest(e.to_string())),
+        };
+        let strings =
+            |cidrs: Vec<Cidr>| -> Vec<String> { cidrs.iter().map(|c| c.to_string()).collect() };
+
+        let cidrs = params.cidrs.iter().map(parse).collect::<Result<Vec<_>>>()?;
+        if cidrs.is_empty() {
+            return Err(McpError::InvalidRequest(
+                "cidrs must not be empty".to_string(),
+            ));
+        }
+        let target = params.target.as_ref().map(parse).transpose()?;
+        let require_target = || {
+            target.ok_or_else(|| {
+                McpError::InvalidRequest(format!("{} requires a target", params.operation))
+            })
+        };
+
+        let output = match params.operation.as_str() {
+            "aggregate" => strings(cidrs.iter().copied().collect::<CidrSet>().cidrs()),
+            "supernet" => strings(
+                cidrs
+                    .iter()
+                    .copied()
+                    .collect::<CidrSet>()
+                    .supernet()
+                    .into_iter()
+                    .collect(),
+            ),
+            "split" => {
+                let prefix = params.split_prefix.ok_or_else(|| {
+                    McpError::InvalidRequest("split requires split_prefix".to_string())
+                })?;
+                let mut subnets = Vec::new();
+                for cidr in &cidrs {
+                    subnets.extend(
+                        cidr.split(prefix)
+                            .map_err(|e| McpError::InvalidRequest(e.to_string()))?,
+                    );
+                    if subnets.len() as u64 > rasn_cidr::MAX_SPLIT {
+                        return Err(McpError::InvalidRequest(format!(
+                            "split produces more than {} subnets",
+                            rasn_cidr::MAX_SPLIT
+                        )));
+                    }
+                }
+                strings(subnets)
+            }
+            "contains" => {
+                let target = require_target()?;
+                let set: CidrSet = cidrs.iter().copied().collect();
+                return Ok(serde_json::json!({
+                    "operation": params.operation,
+                    "input_count": cidrs.len(),
+                    "target": target.to_string(),
+                    "result": set.contains_cidr(&target)
+                }));
+            }
+            "overlaps" => match target {
+                Some(target) => strings(
+                    cidrs
+                        .iter()
+                        .filter(|cidr| cidr.overlaps(&target))
+                        .copied()
+                        .collect(),
+                ),
+                None => {
+                    let pairs: Vec<serde_json::Value> = overlapping_pairs(&cidrs)
+                        .iter()
+                        .map(|(outer, inner)| {
+                            serde_json::json!({
+                                "cidr1": outer.to_string(),
+                                "cidr2": inner.to_string()
+                            })
+                        })
+                        .collect();
+                    return Ok(serde_json::json!({
+                        "operation": params.operation,
+                        "input_count": cidrs.len(),
+                        "output_count": pairs.len(),
+                        "output": pairs
+                    }));
+                }
+            },
+            "intersect" | "subtract" => {
+                let other = params
+                    .target
+                    .iter()
+                    .chain(&params.targets)
+                    .map(parse)
+                    .collect::<Result<Vec<_>>>()?;
+                if other.is_empty() {
+                    return Err(McpError::InvalidRequest(format!(
+                        "{} requires target or targets",
+                        params.operation
+                    )));
+                }
+
+                let set: CidrSet = cidrs.iter().copied().collect();
+                let other: CidrSet = other.into_iter().collect();
+                let result = if params.operation == "intersect" {
+                    set.intersect(&other)
+                } else {
+                    set.subtract(&other)
+                };
+                strings(result.cidrs())
+            }
+            other => {
+                return Err(McpError::InvalidRequest(format!(
+                    "Unknown operation: {}",
+                    other
+                )))
+            }
+        };
+
+        let output_count = output.len();
+        let mut result = serde_json::json!({
+            "operation": params.operation,
+            "input_count": cidrs.len(),
+            "output_count": output_count,
+            "output": output
+        });
+        if params.operation == "aggregate" {
+            result["compression_ratio"] =
+                serde_json::json!(cidrs.len() as f64 / output_count as f64);
+        }
+        Ok(result)
     }
 
     /// Handle reverse_lookup method
@@ -430,28 +1046,18 @@ impl McpServer {
         }))
     }
 
-    /// Parse IP address string to u32
-    fn parse_ip(&self, ip: &str) -> std::result::Result<u32, String> {
-        let octets: Vec<&str> = ip.split('.').collect();
-        if octets.len() != 4 {
-            return Err("Invalid IP address format".to_string());
-        }
-
-        let mut result = 0u32;
-        for (i, octet_str) in octets.iter().enumerate() {
-            let octet: u8 = octet_str
-                .parse()
-                .map_err(|_| format!("Invalid octet: {}", octet_str))?;
-            result |= (octet as u32) << (24 - i * 8);
-        }
-
-        Ok(result)
+    /// Parse IPv4 or IPv6 address string
+    fn parse_ip(&self, ip: &str) -> std::result::Result<IpAddr, String> {
+        ip.trim()
+            .parse()
+            .map_err(|_| format!("Invalid IP address: {}", ip))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rasn_arrow::test_util::{google_v4, write_google_v4};
 
     #[tokio::test]
     async fn test_parse_request() {
@@ -509,11 +1115,793 @@ mod tests {
         assert!(response.contains("-32600")); // Invalid request error code
     }
 
+    #[tokio::test]
+    async fn test_lookup_ipv6() {
+        use rasn_arrow::IpRangeTableV6;
+
+        let start: std::net::Ipv6Addr = "2606:4700::".parse().unwrap();
+        let end: std::net::Ipv6Addr = "2606:4700:ffff:ffff:ffff:ffff:ffff:ffff".parse().unwrap();
+        let v6 = IpRangeTableV6::from_vecs(
+            vec![u128::from(start)],
+            vec![u128::from(end)],
+            vec![13335],
+            vec!["US".to_string()],
+            vec!["CLOUDFLARENET".to_string()],
+        )
+        .unwrap();
+        let server = McpServer::with_database(Some(IpAsnDatabase::new(None, Some(v6)))).unwrap();
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "lookup_ip",
+            "params": {"ip": "2606:4700:4700::1111"},
+            "id": 1
+        }"#;
+
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("13335"));
+        assert!(response.contains("CLOUDFLARENET"));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_special_purpose() {
+        let v4 = google_v4(15169);
+        let server = McpServer::with_database(Some(IpAsnDatabase::new(Some(v4), None))).unwrap();
+
+        let request = r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "100.64.12.1"}, "id": 1}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        let result = &response["result"];
+        assert!(result["asn"].is_null());
+        assert_eq!(result["organization"], "Shared Address Space");
+        assert_eq!(result["special"]["class"], "cgnat");
+        assert_eq!(result["special"]["cidr"], "100.64.0.0/10");
+        assert_eq!(result["special"]["rfc"], "RFC 6598");
+
+        let request =
+            r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "fd00::1"}, "id": 2}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"]["special"]["class"], "private");
+
+        // Ordinary addresses still miss
+        let request =
+            r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "1.1.1.1"}, "id": 3}"#;
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("No ASN found"));
+
+        let request = r#"{"jsonrpc": "2.0", "method": "bulk_lookup", "params": {"ips": ["8.8.8.8", "192.0.2.1", "1.1.1.1"]}, "id": 4}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        let results = &response["result"];
+        assert_eq!(results[0]["asn_info"]["asn"], 15169);
+        assert!(results[0].get("special").is_none());
+        assert!(results[1]["asn_info"].is_null());
+        assert_eq!(results[1]["special"]["class"], "documentation");
+        assert!(results[2].get("special").is_none());
+    }
+
+    /// Backend answering every address with one ASN
+    struct MockLookup(u32);
+
+    impl rasn_core::AsnLookup for MockLookup {
+        fn source(&self) -> LookupSource {
+            LookupSource::Api
+        }
+
+        fn lookup(&self, ip: IpAddr) -> rasn_core::Result<Option<LookupAnswer>> {
+            if ip == IpAddr::from([9, 9, 9, 9]) {
+                return Err(rasn_core::RasnError::Network("unreachable".to_string()));
+            }
+            let info = rasn_core::AsnInfo {
+                asn: rasn_core::Asn(self.0),
+                organization: "MOCK".to_string(),
+                country: None,
+                description: None,
+                handle: None,
+            };
+            Ok(Some(LookupAnswer::new(info, LookupSource::Api)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lookup_with_mock_backend() {
+        let server = McpServer::new(None)
+            .unwrap()
+            .with_lookup(Arc::new(MockLookup(64512)));
+
+        let request =
+            r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "1.1.1.1"}, "id": 1}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"]["asn"], 64512);
+        assert_eq!(response["result"]["source"], "api");
+
+        // Served from the cache the second time
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"]["source"], "cache");
+
+        let request = r#"{"jsonrpc": "2.0", "method": "bulk_lookup", "params": {"ips": ["1.0.0.1", "bogus"]}, "id": 2}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"][0]["asn_info"]["asn"], 64512);
+        assert!(response["result"][1]["asn_info"].is_null());
+
+        // The failing backend counts as a miss behind the cache's clean miss
+        let request =
+            r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "9.9.9.9"}, "id": 3}"#;
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("No ASN found"));
+
+        // Only a chain where every tier fails reports the failure
+        let server = McpServer::new(None)
+            .unwrap()
+            .with_chain(LookupChain::new().with_tier(
+                Arc::new(MockLookup(64512)),
+                LookupSource::Api.default_timeout(),
+            ));
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("unreachable"));
+
+        // One failing address does not abort the batch
+        let request = r#"{"jsonrpc": "2.0", "method": "bulk_lookup", "params": {"ips": ["9.9.9.9", "8.8.8.8", "bogus"]}, "id": 4}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        let results = &response["result"];
+        assert!(results[0]["asn_info"].is_null());
+        assert!(results[0]["error"]
+            .as_str()
+            .unwrap()
+            .contains("unreachable"));
+        assert_eq!(results[1]["asn_info"]["asn"], 64512);
+        assert!(results[1].get("error").is_none());
+        assert!(results[2]["error"].is_string());
+    }
+
+    /// Backend whose every lookup fails, counting the lookups
+    #[derive(Default)]
+    struct FailingLookup(std::sync::atomic::AtomicUsize);
+
+    impl FailingLookup {
+        fn calls(&self) -> usize {
+            self.0.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    impl rasn_core::AsnLookup for FailingLookup {
+        fn source(&self) -> LookupSource {
+            LookupSource::Whois
+        }
+
+        fn lookup(&self, _ip: IpAddr) -> rasn_core::Result<Option<LookupAnswer>> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(rasn_core::RasnError::Network("unreachable".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lookup_special_with_failing_tier() {
+        let whois = Arc::new(FailingLookup::default());
+        let server = McpServer::new(None).unwrap().with_chain(
+            LookupChain::new().with_tier(whois.clone(), LookupSource::Whois.default_timeout()),
+        );
+        let lookup = |ip: &str| {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "lookup_ip",
+                "params": {"ip": ip},
+                "id": 1
+            });
+            let server = &server;
+            async move {
+                let response = server.handle_request(&request.to_string()).await.unwrap();
+                serde_json::from_str::<serde_json::Value>(&response).unwrap()
+            }
+        };
+
+        // Never-routed blocks are classified without querying the tier
+        let response = lookup("100.64.0.1").await;
+        assert_eq!(response["result"]["special"]["class"], "cgnat");
+        let response = lookup("::ffff:10.0.0.1").await;
+        assert_eq!(response["result"]["special"]["class"], "private");
+        assert_eq!(whois.calls(), 0);
+
+        // Other reserved blocks are classified although the tier failed
+        let response = lookup("240.0.0.1").await;
+        assert_eq!(response["result"]["special"]["class"], "reserved");
+        assert_eq!(whois.calls(), 1);
+
+        let request = r#"{"jsonrpc": "2.0", "method": "bulk_lookup", "params": {"ips": ["192.168.1.1", "127.0.0.1"]}, "id": 2}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"][0]["special"]["class"], "private");
+        assert!(response["result"][1].get("error").is_none());
+        assert_eq!(whois.calls(), 1);
+
+        let response = lookup("8.8.8.8").await;
+        assert!(response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("unreachable"));
+    }
+
+    #[tokio::test]
+    async fn test_reload_data() {
+        use rasn_arrow::database::IPV4_IPC_FILE;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(IPV4_IPC_FILE);
+        write_google_v4(&path, 15169);
+
+        let server = McpServer::new(Some(dir.path())).unwrap();
+        let database = server.database().unwrap();
+        let info = IpAsnDatabase::lookup(&database, "8.8.8.8".parse().unwrap()).unwrap();
+        server
+            .cache()
+            .set("8.8.8.8", info, std::time::Duration::from_secs(60))
+            .await;
+
+        std::fs::remove_file(&path).unwrap();
+        write_google_v4(&path, 64512);
+
+        let request = r#"{"jsonrpc": "2.0", "method": "reload_data", "params": {}, "id": 1}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"]["status"], "reloaded");
+        assert_eq!(response["result"]["ipv4_ranges"], 1);
+        assert!(server.cache().get("8.8.8.8").await.is_none());
+
+        let request =
+            r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "8.8.8.8"}, "id": 2}"#;
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("64512"));
+
+        // A broken file is rejected and the loaded data stays in place
+        std::fs::remove_file(&path).unwrap();
+        std::fs::write(&path, b"not arrow").unwrap();
+        let request = r#"{"jsonrpc": "2.0", "method": "reload_data", "params": {}, "id": 3}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert!(response["error"].is_object());
+        let response = server
+            .handle_request(
+                r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "8.8.8.8"}, "id": 4}"#,
+            )
+            .await
+            .unwrap();
+        assert!(response.contains("64512"));
+
+        let server = McpServer::with_database(None).unwrap();
+        assert!(server.reload_data().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_data_info() {
+        use rasn_arrow::database::IPV4_IPC_FILE;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let info = DatasetInfo {
+            schema_version: Some(1),
+            build_time: Some("2026-03-01T12:00:00Z".to_string()),
+            source_file: Some("ip2asn-v4.tsv.gz".to_string()),
+            row_count: Some(1),
+            ..Default::default()
+        };
+        google_v4(15169)
+            .with_dataset_info(info)
+            .write_ipc(&dir.path().join(IPV4_IPC_FILE))
+            .unwrap();
+        let server = McpServer::new(Some(dir.path())).unwrap();
+
+        let request = r#"{"jsonrpc": "2.0", "method": "data_info", "params": {}, "id": 1}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        let result = &response["result"];
+        assert_eq!(result["ipv4"]["ranges"], 1);
+        assert_eq!(
+            result["ipv4"]["dataset"]["build_time"],
+            "2026-03-01T12:00:00Z"
+        );
+        assert!(result["ipv6"].is_null());
+
+        let request = r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "8.8.8.8", "include_dataset": true}, "id": 2}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(
+            response["result"]["dataset"]["source_file"],
+            "ip2asn-v4.tsv.gz"
+        );
+
+        let request =
+            r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "8.8.8.8"}, "id": 3}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert!(response["result"].get("dataset").is_none());
+        // Repeated lookups are answered by the cache in front of the tables
+        assert_eq!(response["result"]["source"], "cache");
+
+        let request = r#"{"jsonrpc": "2.0", "method": "bulk_lookup", "params": {"ips": ["8.8.8.8"], "include_dataset": true}, "id": 4}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"][0]["asn_info"]["dataset"]["row_count"], 1);
+
+        // IPv4-mapped addresses report the IPv4 table they were answered from
+        let request = r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "::ffff:8.8.8.8", "include_dataset": true}, "id": 6}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"]["asn"], 15169);
+        assert_eq!(
+            response["result"]["dataset"]["source_file"],
+            "ip2asn-v4.tsv.gz"
+        );
+
+        let server = McpServer::with_database(None).unwrap();
+        let response = server.handle_request(request).await.unwrap();
+        assert!(!response.contains("dataset"));
+        let request = r#"{"jsonrpc": "2.0", "method": "data_info", "params": {}, "id": 5}"#;
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("No database loaded"));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_ip_at() {
+        use rasn_arrow::database::IPV4_IPC_FILE;
+
+        let root = tempfile::TempDir::new().unwrap();
+        for (date, asn) in [("2026-01-01", 64500), ("2026-03-01", 64501)] {
+            let dir = root.path().join(date);
+            std::fs::create_dir(&dir).unwrap();
+            write_google_v4(&dir.join(IPV4_IPC_FILE), asn);
+        }
+        let server = McpServer::with_database(None)
+            .unwrap()
+            .with_snapshots(SnapshotStore::open(root.path()).unwrap());
+
+        let request = r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "8.8.8.8", "at": "2026-02-15"}, "id": 1}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"]["asn"], 64500);
+        assert_eq!(response["result"]["snapshot"], "2026-01-01");
+        assert_eq!(response["result"]["source"], "arrow");
+
+        let request = r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "8.8.8.8", "at": "2026-03-01"}, "id": 2}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"]["asn"], 64501);
+
+        for (at, code) in [("2025-12-31", -32603), ("yesterday", -32600)] {
+            let request = format!(
+                r#"{{"jsonrpc": "2.0", "method": "lookup_ip", "params": {{"ip": "8.8.8.8", "at": "{}"}}, "id": 3}}"#,
+                at
+            );
+            let response: serde_json::Value =
+                serde_json::from_str(&server.handle_request(&request).await.unwrap()).unwrap();
+            assert_eq!(response["error"]["code"], code, "{}", at);
+        }
+
+        // No snapshot that early, but documentation space needs none
+        let request = r#"{"jsonrpc": "2.0", "method": "lookup_ip", "params": {"ip": "192.0.2.1", "at": "2025-12-31"}, "id": 4}"#;
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_request(request).await.unwrap()).unwrap();
+        assert_eq!(response["result"]["special"]["class"], "documentation");
+
+        let server = McpServer::with_database(None).unwrap();
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("No snapshot store configured"));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_asn_metadata() {
+        use rasn_arrow::AsnMetadataTable;
+
+        let metadata = AsnMetadataTable::from_vecs(
+            vec![15169],
+            vec!["GOOGLE".to_string()],
+            vec!["Google LLC".to_string()],
+        )
+        .unwrap();
+        let database = IpAsnDatabase::new(None, None).with_metadata(metadata);
+        let server = McpServer::with_database(Some(database)).unwrap();
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "lookup_asn",
+            "params": {"asn": 15169},
+            "id": 1
+        }"#;
+
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("Google LLC"));
+        assert!(response.contains("\"handle\":\"GOOGLE\""));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_asn_prefixes() {
+        use rasn_arrow::{IpRangeTableV4, IpRangeTableV6};
+
+        // 8.8.8.0 - 8.8.9.127 is a /24 plus a /25
+        let v4 = IpRangeTableV4::from_vecs(
+            vec![0x08080800, 0x0A000000],
+            vec![0x0808097F, 0x0A0000FF],
+            vec![15169, 64496],
+            vec!["US".to_string(), "ZZ".to_string()],
+            vec!["GOOGLE".to_string(), "DOC".to_string()],
+        )
+        .unwrap();
+        let start: std::net::Ipv6Addr = "2001:4860::".parse().unwrap();
+        let end: std::net::Ipv6Addr = "2001:4860:ffff:ffff:ffff:ffff:ffff:ffff".parse().unwrap();
+        let v6 = IpRangeTableV6::from_vecs(
+            vec![u128::from(start)],
+            vec![u128::from(end)],
+            vec![15169],
+            vec!["US".to_string()],
+            vec!["GOOGLE".to_string()],
+        )
+        .unwrap();
+        let server =
+            McpServer::with_database(Some(IpAsnDatabase::new(Some(v4), Some(v6)))).unwrap();
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "lookup_asn",
+            "params": {"asn": 15169},
+            "id": 1
+        }"#;
+
+        let response = server.handle_request(request).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let result = &response["result"];
+
+        assert_eq!(result["organization"], "GOOGLE");
+        assert_eq!(result["country"], "US");
+        assert_eq!(
+            result["ipv4_prefixes"],
+            serde_json::json!(["8.8.8.0/24", "8.8.9.0/25"])
+        );
+        assert_eq!(
+            result["ipv6_prefixes"],
+            serde_json::json!(["2001:4860::/32"])
+        );
+        assert_eq!(result["totals"]["ipv4_addresses"], 384);
+        assert_eq!(result["totals"]["ipv4_prefixes"], 2);
+        assert_eq!(
+            result["totals"]["ipv6_addresses"],
+            (1u128 << 96).to_string()
+        );
+
+        // Unknown ASNs are an error
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "lookup_asn",
+            "params": {"asn": 64512},
+            "id": 2
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("ASN not found"));
+    }
+
+    #[tokio::test]
+    async fn test_org_to_asn() {
+        use rasn_arrow::IpRangeTableV4;
+
+        let v4 = IpRangeTableV4::from_vecs(
+            vec![0x08080800, 0x22000000, 0x23000000],
+            vec![0x080808FF, 0x220000FF, 0x230000FF],
+            vec![15169, 396982, 15169],
+            vec!["US".to_string(), "US".to_string(), "US".to_string()],
+            vec![
+                "GOOGLE".to_string(),
+                "GOOGLE-CLOUD-PLATFORM".to_string(),
+                "GOOGLE".to_string(),
+            ],
+        )
+        .unwrap();
+        let server = McpServer::with_database(Some(IpAsnDatabase::new(Some(v4), None))).unwrap();
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "org_to_asn",
+            "params": {"organization": "google"},
+            "id": 1
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let result = &response["result"];
+
+        assert_eq!(result["total_asns"], 2);
+        assert_eq!(result["total_ip_ranges"], 3);
+        assert_eq!(result["matches"][0]["asn"], 15169);
+        assert_eq!(result["matches"][0]["match"], "exact");
+        assert_eq!(result["matches"][1]["name"], "GOOGLE-CLOUD-PLATFORM");
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "org_to_asn",
+            "params": {"organization": "GOOGLE", "exact_match": true, "country": "US"},
+            "id": 2
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["result"]["total_asns"], 1);
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "org_to_asn",
+            "params": {"organization": "g"},
+            "id": 3
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("-32600"));
+    }
+
+    #[tokio::test]
+    async fn test_country_summary() {
+        use rasn_arrow::IpRangeTableV4;
+
+        let v4 = IpRangeTableV4::from_vecs(
+            vec![0x08080800, 0x01010100],
+            vec![0x080808FF, 0x010101FF],
+            vec![15169, 13335],
+            vec!["US".to_string(), "AU".to_string()],
+            vec!["GOOGLE".to_string(), "CLOUDFLARENET".to_string()],
+        )
+        .unwrap();
+        let server = McpServer::with_database(Some(IpAsnDatabase::new(Some(v4), None))).unwrap();
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "country_summary",
+            "params": {"country": "us", "include_ranges": true},
+            "id": 1
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let result = &response["result"];
+
+        assert_eq!(result["country"], "US");
+        assert_eq!(result["total_asns"], 1);
+        assert_eq!(result["asns"][0]["asn"], 15169);
+        assert_eq!(result["totals"]["ipv4_addresses"], 256);
+        assert_eq!(result["ipv4_prefixes"], serde_json::json!(["8.8.8.0/24"]));
+        assert!(result["totals"].get("geo_ipv4_ranges").is_none());
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "country_summary",
+            "params": {"country": "USA"},
+            "id": 2
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("-32600"));
+    }
+
+    #[tokio::test]
+    async fn test_cidr_analyze() {
+        let server = McpServer::new(None).unwrap();
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "cidr_analyze",
+            "params": {"cidr": "192.168.1.5/24"},
+            "id": 1
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let result = &response["result"];
+        assert_eq!(result["cidr"], "192.168.1.0/24");
+        assert_eq!(result["family"], "ipv4");
+        assert_eq!(result["start"], "192.168.1.0");
+        assert_eq!(result["end"], "192.168.1.255");
+        assert_eq!(result["first_usable"], "192.168.1.1");
+        assert_eq!(result["last_usable"], "192.168.1.254");
+        assert_eq!(result["total_addresses"], "256");
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "cidr_analyze",
+            "params": {"cidr": "2001:DB8:0::1/32"},
+            "id": 2
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let result = &response["result"];
+        assert_eq!(result["family"], "ipv6");
+        assert_eq!(result["cidr"], "2001:db8::/32");
+        assert_eq!(result["start"], "2001:db8::");
+        assert_eq!(result["end"], "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff");
+        assert_eq!(result["total_addresses"], "79228162514264337593543950336");
+        assert_eq!(result["prefix_len"], 32);
+        assert!(result.get("first_usable").is_none());
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "cidr_analyze",
+            "params": {"cidr": "2001:db8::/129"},
+            "id": 3
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.contains("-32600"));
+    }
+
+    #[tokio::test]
+    async fn test_cidr_analyze_coverage() {
+        use rasn_arrow::IpRangeTableV4;
+
+        // 8.8.8.0/24 split between two ASNs, 8.8.9.0/24 unallocated
+        let v4 = IpRangeTableV4::from_vecs(
+            vec![0x08080800, 0x08080880],
+            vec![0x0808087F, 0x080808FF],
+            vec![15169, 64500],
+            vec!["US".to_string(), "NL".to_string()],
+            vec!["GOOGLE".to_string(), "EXAMPLE".to_string()],
+        )
+        .unwrap();
+        let server = McpServer::with_database(Some(IpAsnDatabase::new(Some(v4), None))).unwrap();
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "cidr_analyze",
+            "params": {"cidr": "8.8.8.0/23", "limit": 1},
+            "id": 1
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let coverage = &response["result"]["coverage"];
+
+        assert_eq!(coverage["covered_addresses"], "256");
+        assert_eq!(coverage["percent_covered"], 50.0);
+        assert_eq!(coverage["ranges"], 2);
+        assert_eq!(coverage["asn_count"], 2);
+        assert_eq!(coverage["asns"].as_array().unwrap().len(), 1);
+        assert_eq!(coverage["asns"][0]["asn"], 15169);
+        assert_eq!(coverage["asns"][0]["percent"], 25.0);
+        assert_eq!(coverage["country_count"], 2);
+        assert_eq!(coverage["gap_count"], 1);
+        assert_eq!(coverage["gaps"][0]["start"], "8.8.9.0");
+        assert_eq!(coverage["gaps"][0]["addresses"], "256");
+
+        // No IPv6 table loaded
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "cidr_analyze",
+            "params": {"cidr": "2001:db8::/32"},
+            "id": 2
+        }"#;
+        let response = server.handle_request(request).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert!(response["result"]["coverage"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_cidr_operations() {
+        let server = McpServer::new(None).unwrap();
+        let call = |params: serde_json::Value| {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "cidr_operations",
+                "params": params,
+                "id": 1
+            });
+            let server = &server;
+            async move {
+                let response = server.handle_request(&request.to_string()).await.unwrap();
+                serde_json::from_str::<serde_json::Value>(&response).unwrap()
+            }
+        };
+
+        let response = call(serde_json::json!({
+            "operation": "aggregate",
+            "cidrs": ["192.168.0.0/24", "192.168.1.0/24", "192.168.2.0/24"]
+        }))
+        .await;
+        let result = &response["result"];
+        assert_eq!(
+            result["output"],
+            serde_json::json!(["192.168.0.0/23", "192.168.2.0/24"])
+        );
+        assert_eq!(result["input_count"], 3);
+        assert_eq!(result["compression_ratio"], 1.5);
+
+        let response = call(serde_json::json!({
+            "operation": "supernet",
+            "cidrs": ["192.168.0.0/24", "192.168.2.0/24"]
+        }))
+        .await;
+        assert_eq!(
+            response["result"]["output"],
+            serde_json::json!(["192.168.0.0/22"])
+        );
+
+        let response = call(serde_json::json!({
+            "operation": "split",
+            "cidrs": ["192.168.0.0/24"],
+            "split_prefix": 26
+        }))
+        .await;
+        assert_eq!(response["result"]["output_count"], 4);
+        assert_eq!(response["result"]["output"][3], "192.168.0.192/26");
+
+        let response = call(serde_json::json!({
+            "operation": "contains",
+            "cidrs": ["192.168.0.0/24"],
+            "target": "192.168.0.100/32"
+        }))
+        .await;
+        assert_eq!(response["result"]["result"], true);
+
+        let response = call(serde_json::json!({
+            "operation": "overlaps",
+            "cidrs": ["192.168.0.0/23", "192.168.1.0/24"]
+        }))
+        .await;
+        assert_eq!(
+            response["result"]["output"],
+            serde_json::json!([{"cidr1": "192.168.0.0/23", "cidr2": "192.168.1.0/24"}])
+        );
+
+        let response = call(serde_json::json!({
+            "operation": "subtract",
+            "cidrs": ["10.0.0.0/8"],
+            "targets": ["10.0.0.0/9", "10.128.0.0/10"]
+        }))
+        .await;
+        assert_eq!(
+            response["result"]["output"],
+            serde_json::json!(["10.192.0.0/10"])
+        );
+
+        let response = call(serde_json::json!({
+            "operation": "intersect",
+            "cidrs": ["10.0.0.0/8", "192.168.0.0/16"],
+            "target": "10.1.0.0/16"
+        }))
+        .await;
+        assert_eq!(
+            response["result"]["output"],
+            serde_json::json!(["10.1.0.0/16"])
+        );
+
+        for params in [
+            serde_json::json!({"operation": "split", "cidrs": ["10.0.0.0/8"], "split_prefix": 32}),
+            serde_json::json!({"operation": "contains", "cidrs": ["10.0.0.0/8"]}),
+            serde_json::json!({"operation": "subtract", "cidrs": ["10.0.0.0/8"]}),
+            serde_json::json!({"operation": "median", "cidrs": ["10.0.0.0/8"]}),
+            serde_json::json!({"operation": "aggregate", "cidrs": []}),
+        ] {
+            let response = call(params).await;
+            assert_eq!(response["error"]["code"], -32600);
+        }
+
+        // IPv6 operands are rejected by name, not as malformed IPv4
+        let response = call(serde_json::json!({
+            "operation": "contains",
+            "cidrs": ["10.0.0.0/8"],
+            "target": "2001:db8::/32"
+        }))
+        .await;
+        assert_eq!(response["error"]["code"], -32600);
+        assert!(response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("IPv4 only"));
+    }
+
     #[test]
     fn test_parse_ip() {
         let server = McpServer::new(None).unwrap();
-        assert_eq!(server.parse_ip("8.8.8.8").unwrap(), 0x08080808);
-        assert_eq!(server.parse_ip("192.168.1.1").unwrap(), 0xC0A80101);
+        assert_eq!(
+            server.parse_ip("8.8.8.8").unwrap(),
+            IpAddr::from([8, 8, 8, 8])
+        );
+        assert_eq!(
+            server.parse_ip("192.168.1.1").unwrap(),
+            IpAddr::from([192, 168, 1, 1])
+        );
+        assert_eq!(
+            server.parse_ip("2001:4860:4860::8888").unwrap(),
+            "2001:4860:4860::8888".parse::<IpAddr>().unwrap()
+        );
         assert!(server.parse_ip("invalid").is_err());
         assert!(server.parse_ip("256.0.0.1").is_err());
     }
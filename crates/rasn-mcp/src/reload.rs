@@ -0,0 +1,140 @@
+//! Reload triggers for the MCP server
+//!
+//! [`spawn_reload_watcher`] runs [`McpServer::reload_data`] when the data
+//! files change on disk (polled by modification time) or, on Unix, when the
+//! process receives `SIGHUP`. Clients can also trigger a reload with the
+//! `reload_data` method.
+
+use crate::{McpServer, Result};
+use rasn_arrow::ReloadReport;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Watch the server's data files and reload them when they change
+///
+/// Runs on its own thread with its own runtime, so it works with both the
+/// blocking and the async STDIO transport. `on_reload` receives the outcome
+/// of every reload attempt; a failed attempt keeps the current data and is
+/// retried only after the files change again.
+///
+/// # Arguments
+///
+/// * `server` - Server whose database is reloaded
+/// * `poll_interval` - How often the file modification times are checked
+/// * `on_reload` - Called after every reload attempt
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasn_mcp::{reload::spawn_reload_watcher, McpServer};
+/// use std::path::Path;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let server = Arc::new(McpServer::new(Some(Path::new("data/arrow")))?);
+/// spawn_reload_watcher(server.clone(), Duration::from_secs(10), |result| {
+///     if let Err(e) = result {
+///         eprintln!("reload failed: {}", e);
+///     }
+/// })?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn spawn_reload_watcher<F>(
+    server: Arc<McpServer>,
+    poll_interval: Duration,
+    on_reload: F,
+) -> std::io::Result<JoinHandle<()>>
+where
+    F: Fn(Result<ReloadReport>) + Send + 'static,
+{
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    std::thread::Builder::new()
+        .name("rasn-reload".to_string())
+        .spawn(move || runtime.block_on(watch(server, poll_interval, on_reload)))
+}
+
+/// Reload on file changes and `SIGHUP` until the process exits
+async fn watch<F>(server: Arc<McpServer>, poll_interval: Duration, on_reload: F)
+where
+    F: Fn(Result<ReloadReport>),
+{
+    let modified = || server.database.as_ref().and_then(|h| h.modified());
+    let mut last_modified = modified();
+
+    #[cfg(unix)]
+    let mut hangup = {
+        use tokio::signal::unix::{signal, SignalKind};
+        signal(SignalKind::hangup()).ok()
+    };
+
+    let mut ticker = tokio::time::interval(poll_interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        #[cfg(unix)]
+        let signalled = async {
+            match hangup {
+                Some(ref mut hangup) => hangup.recv().await,
+                None => std::future::pending().await,
+            }
+        };
+        #[cfg(not(unix))]
+        let signalled = std::future::pending::<Option<()>>();
+
+        tokio::select! {
+            _ = ticker.tick() => {
+                let current = modified();
+                if current == last_modified {
+                    continue;
+                }
+                last_modified = current;
+            }
+            _ = signalled => {
+                last_modified = modified();
+            }
+        }
+
+        on_reload(server.reload_data().await);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rasn_arrow::database::IPV4_IPC_FILE;
+    use rasn_arrow::test_util::write_google_v4;
+    use std::sync::mpsc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_watcher_reloads_changed_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(IPV4_IPC_FILE);
+        write_google_v4(&path, 15169);
+
+        let server = Arc::new(McpServer::new(Some(dir.path())).unwrap());
+        let (tx, rx) = mpsc::channel();
+        spawn_reload_watcher(server.clone(), Duration::from_millis(20), move |result| {
+            tx.send(result.map(|report| report.generation)).unwrap();
+        })
+        .unwrap();
+
+        // Let the watcher record the initial modification time
+        std::thread::sleep(Duration::from_millis(100));
+        std::fs::remove_file(&path).unwrap();
+        write_google_v4(&path, 64512);
+
+        let generation = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+        assert_eq!(generation, 1);
+
+        let database = server.database().unwrap();
+        assert_eq!(
+            database.lookup("8.8.8.8".parse().unwrap()).unwrap().asn.0,
+            64512
+        );
+    }
+}
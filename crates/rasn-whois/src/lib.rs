@@ -20,8 +20,9 @@
 //! # }
 //! ```
 
-use rasn_core::Asn;
+use rasn_core::{Asn, AsnInfo, AsyncAsnLookup, BoxFuture, LookupAnswer, LookupSource, RasnError};
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::time::Duration;
 use thiserror::Error;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -49,6 +50,15 @@ pub enum WhoisError {
 
 pub type Result<T> = std::result::Result<T, WhoisError>;
 
+impl From<WhoisError> for RasnError {
+    fn from(err: WhoisError) -> Self {
+        match err {
+            WhoisError::ParseError(msg) => RasnError::Parse(msg),
+            other => RasnError::Network(other.to_string()),
+        }
+    }
+}
+
 /// WHOIS response data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhoisResponse {
@@ -169,6 +179,43 @@ impl WhoisClient {
         })
     }
 
+    /// Query the origin ASN of an IP address
+    ///
+    /// Reads the `OriginAS`, `OrgName`, `OrgId` and `Country` fields of
+    /// ARIN's network record. Returns `None` when the record names no origin
+    /// AS, which includes addresses ARIN only refers to another registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - IPv4 or IPv6 address to query
+    pub async fn query_ip(&self, ip: IpAddr) -> Result<Option<AsnInfo>> {
+        let server = WhoisServer::arin();
+        let query = format!("n {}\r\n", ip);
+
+        let raw_response = self.query_server(&server, &query).await?;
+        Ok(Self::parse_network(&raw_response))
+    }
+
+    /// Build ASN information from a network record
+    fn parse_network(response: &str) -> Option<AsnInfo> {
+        // "AS15169" or "AS15169, AS36040": the first origin wins
+        let origin = Self::extract_field(response, "OriginAS:")?;
+        let asn = origin
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .next()?
+            .trim_start_matches("AS")
+            .parse()
+            .ok()?;
+
+        Some(AsnInfo {
+            asn: Asn(asn),
+            organization: Self::extract_field(response, "OrgName:").unwrap_or_default(),
+            country: Self::extract_field(response, "Country:"),
+            description: Self::extract_field(response, "NetName:"),
+            handle: Self::extract_field(response, "OrgId:"),
+        })
+    }
+
     /// Query WHOIS server with timeout
     async fn query_server(&self, server: &WhoisServer, query: &str) -> Result<String> {
         let addr = format!("{}:{}", server.host, server.port);
@@ -222,6 +269,20 @@ impl Default for WhoisClient {
     }
 }
 
+/// Last-resort tier of a lookup chain, see [`WhoisClient::query_ip`]
+impl AsyncAsnLookup for WhoisClient {
+    fn source(&self) -> LookupSource {
+        LookupSource::Whois
+    }
+
+    fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, rasn_core::Result<Option<LookupAnswer>>> {
+        Box::pin(async move {
+            let info = self.query_ip(ip).await?;
+            Ok(info.map(|info| LookupAnswer::new(info, LookupSource::Whois)))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -257,6 +318,20 @@ mod tests {
         assert_eq!(WhoisClient::extract_field(response, "NotFound:"), None);
     }
 
+    #[test]
+    fn test_parse_network() {
+        let response = "NetRange: 8.8.8.0 - 8.8.8.255\nNetName: GOGL\nOriginAS: AS15169, AS36040\nOrgName: Google LLC\nOrgId: GOGL\nCountry: US\n";
+
+        let info = WhoisClient::parse_network(response).unwrap();
+        assert_eq!(info.asn, Asn(15169));
+        assert_eq!(info.organization, "Google LLC");
+        assert_eq!(info.country.as_deref(), Some("US"));
+        assert_eq!(info.handle.as_deref(), Some("GOGL"));
+
+        assert!(WhoisClient::parse_network("NetName: X\nOriginAS:\n").is_none());
+        assert!(WhoisClient::parse_network("NetName: X\n").is_none());
+    }
+
     #[test]
     fn test_server_configs() {
         let servers = WhoisClient::available_servers();
@@ -20,8 +20,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  - lookup_domain");
     println!("  - bulk_lookup");
     println!("  - cidr_analyze");
+    println!("  - cidr_operations");
     println!("  - reverse_lookup");
     println!("  - enrich_data");
+    println!("  - reload_data");
+    println!("  - data_info");
     println!("  - ping");
 
     // Example request